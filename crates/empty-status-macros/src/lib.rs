@@ -41,6 +41,11 @@ pub fn derive_rotate_next(input: TokenStream) -> TokenStream {
         let next = &variants[(i + 1) % n];
         quote! { Self::#cur => Self::#next }
     });
+    let prev_arms = (0..n).map(|i| {
+        let cur = &variants[i];
+        let prev = &variants[(i + n - 1) % n];
+        quote! { Self::#cur => Self::#prev }
+    });
 
     quote! {
         impl #ident {
@@ -49,6 +54,12 @@ pub fn derive_rotate_next(input: TokenStream) -> TokenStream {
                     #(#arms,)*
                 }
             }
+
+            pub const fn prev(self) -> Self {
+                match self {
+                    #(#prev_arms,)*
+                }
+            }
         }
     }
     .into()
@@ -1,20 +1,42 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use serde_inline_default::serde_inline_default;
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tracing::{debug, error, info, warn};
 use xdg::BaseDirectories;
 
-use crate::core::EmptyStatus;
-use crate::machine::runtime::{spawn_machine_actor, MachineWrapper};
+use crate::core::{Color, EmptyStatus, Theme};
+use crate::machine::dump::{DumpVisitor, UnitDump};
+use crate::machine::effects::{EffectEngine, Effects};
+use crate::machine::http::RateLimitSpec;
+use crate::machine::runtime::{spawn_machine_actor, ActorInputs, MachineWrapper};
+use crate::machine::types::UnitMachine;
 use crate::machine::units::bat::BatMachine;
+use crate::machine::units::broken::BrokenMachine;
+use crate::machine::units::clipboard::ClipboardMachine;
+use crate::machine::units::command::CommandMachine;
 use crate::machine::units::cpu::CpuMachine;
 use crate::machine::units::disk::DiskMachine;
+use crate::machine::units::file::FileMachine;
+use crate::machine::units::http::HttpMachine;
+use crate::machine::units::load::LoadMachine;
+use crate::machine::units::locks::LocksMachine;
 use crate::machine::units::mem::MemMachine;
+use crate::machine::units::mic::MicMachine;
 use crate::machine::units::net::NetMachine;
+use crate::machine::units::spacer::SpacerMachine;
 use crate::machine::units::time::TimeMachine;
+use crate::machine::units::uptime::UptimeMachine;
 use crate::machine::units::weather::WeatherMachine;
 use crate::machine::units::wifi::WifiMachine;
+use crate::machine::units::window::WindowMachine;
+use crate::render::color::Srgb8;
 
 const CONFIG_PREFIX: &str = "empty-status";
 const CONFIG_FILE: &str = "config.toml";
@@ -22,10 +44,17 @@ const CONFIG_FILE: &str = "config.toml";
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct RootConfig {
+    /// Parsed one by one in `visit_units`, so a bad unit only breaks itself.
     #[serde(default)]
-    units: Vec<UnitConfig>,
+    units: Vec<toml::Value>,
     #[serde(default)]
     global: GlobalConfig,
+    #[serde(default)]
+    http: HttpConfig,
+    #[serde(default)]
+    theme: ThemeConfig,
+    #[serde(default)]
+    metrics: MetricsConfig,
 }
 
 #[derive(Deserialize, Debug)]
@@ -41,12 +70,32 @@ enum UnitConfig {
     Mem(UnitSpec<crate::units::mem::MemConfig>),
     #[serde(rename = "Disk")]
     Disk(UnitSpec<crate::units::disk::DiskConfig>),
+    #[serde(rename = "File")]
+    File(UnitSpec<crate::units::file::FileConfig>),
     #[serde(rename = "Wifi")]
     Wifi(UnitSpec<crate::units::wifi::WifiConfig>),
     #[serde(rename = "Bat")]
     Bat(UnitSpec<crate::units::bat::BatConfig>),
     #[serde(rename = "Net")]
     Net(UnitSpec<crate::units::net::NetConfig>),
+    #[serde(rename = "Clipboard")]
+    Clipboard(UnitSpec<crate::units::clipboard::ClipboardConfig>),
+    #[serde(rename = "Mic")]
+    Mic(UnitSpec<crate::units::mic::MicConfig>),
+    #[serde(rename = "Locks")]
+    Locks(UnitSpec<crate::units::locks::LocksConfig>),
+    #[serde(rename = "Window")]
+    Window(UnitSpec<crate::units::window::WindowConfig>),
+    #[serde(rename = "Http")]
+    Http(UnitSpec<crate::units::http::HttpUnitConfig>),
+    #[serde(rename = "Command")]
+    Command(UnitSpec<crate::units::command::CommandConfig>),
+    #[serde(rename = "Load")]
+    Load(UnitSpec<crate::units::load::LoadConfig>),
+    #[serde(rename = "Uptime")]
+    Uptime(UnitSpec<crate::units::uptime::UptimeConfig>),
+    #[serde(rename = "Spacer")]
+    Spacer(UnitSpec<crate::units::spacer::SpacerConfig>),
 
     // Stub for future drop-in units. Intentionally not implemented yet.
     // When we do, we should make this a hard boundary with explicit schema and effects.
@@ -60,6 +109,8 @@ struct UnitSpec<Cfg> {
     #[serde(flatten)]
     sched: SchedulingCfg,
     #[serde(flatten)]
+    decor: DecorationCfg,
+    #[serde(flatten)]
     cfg: Cfg,
 }
 
@@ -68,6 +119,54 @@ struct UnitSpec<Cfg> {
 pub struct SchedulingCfg {
     #[serde_inline_default(0.333)]
     pub poll_interval: f64,
+    #[serde_inline_default(PollMode::Interval)]
+    pub mode: PollMode,
+    /// Re-initialize the unit, with backoff, after it panics; otherwise it
+    /// stays on its "crashed" view.
+    #[serde_inline_default(true)]
+    pub restart_on_crash: bool,
+    /// A click-requested poll within this long (seconds) of the last poll is
+    /// deferred until it has passed, so click spam can't burst polls.
+    #[serde_inline_default(0.5)]
+    pub click_poll_cooldown_sec: f64,
+}
+
+/// What may trigger a unit's poll besides the initial one.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PollMode {
+    /// Every `poll_interval`, plus whenever the unit asks.
+    Interval,
+    /// Only when the unit asks, e.g. from a click or a tick.
+    OnClick,
+    /// Only from clicks; unit tick requests are ignored too.
+    Manual,
+}
+
+/// Literal text wrapped around a unit's output, e.g. `│` dividers or labels.
+#[serde_inline_default]
+#[derive(Deserialize, Debug, Clone)]
+pub struct DecorationCfg {
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+    /// Shown instead of the unit while it is collapsed (`collapse_modifier` + click).
+    #[serde_inline_default("·".to_string())]
+    pub collapsed_glyph: String,
+    #[serde(default)]
+    pub start_collapsed: bool,
+    /// Base text color for the unit; inline colors still win. Defaults to the
+    /// global `text_color`.
+    #[serde(default)]
+    pub text_color: Option<Srgb8>,
+    /// Drop the unit's label (e.g. `cpu `) and show only its values. Defaults
+    /// to the global `compact`.
+    #[serde(default)]
+    pub compact: Option<bool>,
+    /// Replaces the unit's own label (`cpu`, `net eth0`, ...), e.g. `wan`.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone, Copy)]
@@ -75,6 +174,15 @@ pub struct SchedulingCfg {
 pub struct GlobalConfig {
     pub min_polling_interval: f64,
     pub padding: i32,
+    pub order: UnitOrder,
+    pub collapse_modifier: ClickModifier,
+    pub flush: FlushMode,
+    pub max_flush_latency_sec: f64,
+    /// Base text color for every unit without its own `text_color`; unset
+    /// leaves i3bar's default.
+    pub text_color: Option<Srgb8>,
+    /// Drop unit labels (`cpu`, `net eth0`, ...) for a narrower bar.
+    pub compact: bool,
 }
 
 impl Default for GlobalConfig {
@@ -82,156 +190,436 @@ impl Default for GlobalConfig {
         Self {
             min_polling_interval: 0.25,
             padding: 1,
+            order: UnitOrder::Reverse,
+            collapse_modifier: ClickModifier::Shift,
+            flush: FlushMode::Periodic,
+            max_flush_latency_sec: 10.0,
+            text_color: None,
+            compact: false,
         }
     }
 }
 
-pub fn load_status_from_cfg() -> Result<EmptyStatus> {
-    let xdg = BaseDirectories::with_prefix(CONFIG_PREFIX);
-    let path: PathBuf = xdg.place_config_file(CONFIG_FILE)?;
+/// Colors for the states every unit shares, so status semantics theme as one.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Units still waiting on their first reading.
+    pub loading: Srgb8,
+    /// Failed reads, bad config and other errors; also the `Error` border.
+    pub error: Srgb8,
+    /// The border of a `Degraded` unit.
+    pub degraded: Srgb8,
+    /// A configured device or data source that isn't there.
+    pub missing: Srgb8,
+    /// `[theme.palette]`: the hex behind each `core::Color`.
+    pub palette: Theme,
+}
 
-    let text = if path.exists() {
-        fs::read_to_string(&path)?
-    } else {
-        let sample = sample_config();
-        fs::write(&path, sample)?;
-        sample.into()
-    };
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        let palette = Theme::base16_tomorrow();
+        Self {
+            loading: palette.get(Color::Violet),
+            error: palette.get(Color::Red),
+            degraded: palette.get(Color::Yellow),
+            missing: palette.get(Color::Brown),
+            palette,
+        }
+    }
+}
 
-    let raw: RootConfig =
-        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+/// When the output loop writes a status line.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlushMode {
+    /// Every `min_polling_interval`, changed or not.
+    Periodic,
+    /// When a unit's view changes (at most every `min_polling_interval`), or
+    /// after `max_flush_latency_sec` without one.
+    Reactive,
+}
 
-    let (click_tx, _) = tokio::sync::broadcast::channel::<crate::core::ClickEvent>(16);
-    let mut machine_wrappers: Vec<MachineWrapper> = Vec::new();
-    let effects = crate::machine::effects::EffectEngine::new();
-
-    for (handle, uc) in raw.units.iter().enumerate() {
-        let spawn_result: Result<&'static str> = match uc {
-            UnitConfig::Weather(spec) => {
-                let mach = std::sync::Arc::new(WeatherMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Weather")
-            }
-            UnitConfig::Time(spec) => {
-                let mach = std::sync::Arc::new(TimeMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Time")
-            }
-            UnitConfig::Cpu(spec) => {
-                let mach = std::sync::Arc::new(CpuMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Cpu")
-            }
-            UnitConfig::Mem(spec) => {
-                let mach = std::sync::Arc::new(MemMachine::new(spec.cfg));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Mem")
-            }
-            UnitConfig::Disk(spec) => {
-                let mach = std::sync::Arc::new(DiskMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Disk")
-            }
-            UnitConfig::Wifi(spec) => {
-                let mach = std::sync::Arc::new(WifiMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Wifi")
-            }
-            UnitConfig::Bat(spec) => {
-                let mach = std::sync::Arc::new(BatMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Bat")
-            }
-            UnitConfig::Net(spec) => {
-                let mach = std::sync::Arc::new(NetMachine::new(spec.cfg.clone()));
-                machine_wrappers.push(spawn_machine_actor(
-                    mach,
-                    effects.clone(),
-                    spec.sched,
-                    raw.global,
-                    handle,
-                    &click_tx,
-                ));
-                Ok("Net")
-            }
+/// Where the first configured unit lands on the bar.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitOrder {
+    /// Config order left to right: the topmost unit is leftmost.
+    Config,
+    /// Config order right to left: the topmost unit is rightmost.
+    Reverse,
+}
+
+/// A modifier as reported in i3bar click events.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickModifier {
+    Shift,
+    Control,
+    Mod1,
+    Mod4,
+}
+
+impl ClickModifier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Shift => "Shift",
+            Self::Control => "Control",
+            Self::Mod1 => "Mod1",
+            Self::Mod4 => "Mod4",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// node_exporter textfile-collector file (`*.prom`) rewritten with every
+    /// unit's raw readings; unset disables the export.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Per-host rate limit overrides. Unlisted hosts use the unit's own policy.
+    #[serde(default)]
+    pub ratelimit: HashMap<String, RateLimitCfg>,
+}
+
+/// `burst` requests per `per_ms` milliseconds; both must be nonzero.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields, try_from = "RawRateLimitCfg")]
+pub struct RateLimitCfg {
+    pub burst: u32,
+    pub per_ms: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRateLimitCfg {
+    burst: u32,
+    per_ms: u64,
+}
+
+impl TryFrom<RawRateLimitCfg> for RateLimitCfg {
+    type Error = String;
+
+    fn try_from(raw: RawRateLimitCfg) -> Result<Self, Self::Error> {
+        if raw.burst == 0 {
+            return Err("rate limit `burst` must be at least 1".to_string());
+        }
+        if raw.per_ms == 0 {
+            return Err("rate limit `per_ms` must be at least 1".to_string());
+        }
+        Ok(Self {
+            burst: raw.burst,
+            per_ms: raw.per_ms,
+        })
+    }
+}
+
+impl From<RateLimitCfg> for RateLimitSpec {
+    fn from(value: RateLimitCfg) -> Self {
+        Self {
+            per: Duration::from_millis(value.per_ms),
+            burst: value.burst,
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn rate_overrides(&self) -> HashMap<String, RateLimitSpec> {
+        self.ratelimit
+            .iter()
+            .map(|(host, cfg)| (host.clone(), RateLimitSpec::from(*cfg)))
+            .collect()
+    }
+}
+
+/// Receives each configured unit as its concrete machine, so the one `match`
+/// over `UnitConfig` serves every way of running units (the bar, `--dump`).
+pub(crate) trait MachineVisitor {
+    fn visit<M: UnitMachine>(
+        &mut self,
+        machine: Arc<M>,
+        sched: SchedulingCfg,
+        decor: DecorationCfg,
+        handle: usize,
+    );
+}
+
+impl UnitConfig {
+    /// Builds this unit's machine and hands it to `visitor`. Returns the unit type.
+    fn visit(&self, handle: usize, visitor: &mut impl MachineVisitor) -> &'static str {
+        macro_rules! arm {
+            ($spec:expr, $machine:ident, $name:literal) => {{
+                let mach = Arc::new($machine::new($spec.cfg.clone()));
+                visitor.visit(mach, $spec.sched, $spec.decor.clone(), handle);
+                $name
+            }};
+        }
+        match self {
+            UnitConfig::Weather(spec) => arm!(spec, WeatherMachine, "Weather"),
+            UnitConfig::Time(spec) => arm!(spec, TimeMachine, "Time"),
+            UnitConfig::Cpu(spec) => arm!(spec, CpuMachine, "Cpu"),
+            UnitConfig::Mem(spec) => arm!(spec, MemMachine, "Mem"),
+            UnitConfig::Disk(spec) => arm!(spec, DiskMachine, "Disk"),
+            UnitConfig::File(spec) => arm!(spec, FileMachine, "File"),
+            UnitConfig::Wifi(spec) => arm!(spec, WifiMachine, "Wifi"),
+            UnitConfig::Bat(spec) => arm!(spec, BatMachine, "Bat"),
+            UnitConfig::Net(spec) => arm!(spec, NetMachine, "Net"),
+            UnitConfig::Clipboard(spec) => arm!(spec, ClipboardMachine, "Clipboard"),
+            UnitConfig::Mic(spec) => arm!(spec, MicMachine, "Mic"),
+            UnitConfig::Locks(spec) => arm!(spec, LocksMachine, "Locks"),
+            UnitConfig::Window(spec) => arm!(spec, WindowMachine, "Window"),
+            UnitConfig::Http(spec) => arm!(spec, HttpMachine, "Http"),
+            UnitConfig::Command(spec) => arm!(spec, CommandMachine, "Command"),
+            UnitConfig::Load(spec) => arm!(spec, LoadMachine, "Load"),
+            UnitConfig::Uptime(spec) => arm!(spec, UptimeMachine, "Uptime"),
+            UnitConfig::Spacer(spec) => arm!(spec, SpacerMachine, "Spacer"),
             UnitConfig::_External => {
                 warn!("Skipping external unit type (not implemented yet)");
-                Ok("External")
+                "External"
             }
-        };
+        }
+    }
+}
+
+/// The scheduling and decoration keys of a unit whose own config is broken.
+#[derive(Deserialize)]
+struct FallbackSpec {
+    #[serde(flatten)]
+    sched: SchedulingCfg,
+    #[serde(flatten)]
+    decor: DecorationCfg,
+}
 
-        match spawn_result {
-            Ok(kind) => {
+/// Visits every unit in config order. Units that fail to parse, or have an
+/// unknown type, are visited as a `BrokenMachine` in their place.
+fn visit_units(units: &[toml::Value], visitor: &mut impl MachineVisitor) {
+    for (handle, value) in units.iter().enumerate() {
+        let kind = value
+            .get("type")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("<no type>");
+        let reason = match UnitConfig::deserialize(value.clone()) {
+            Ok(UnitConfig::_External) => "unknown unit type".to_string(),
+            Ok(uc) => {
+                let kind = uc.visit(handle, visitor);
                 info!("Successfully loaded unit '{kind}'");
                 debug!("Unit config: {uc:?}");
+                continue;
+            }
+            Err(e) => e.message().to_string(),
+        };
+        error!("Failed to load unit #{handle} ({kind}): {reason}");
+
+        // Keep whatever placement keys still parse (e.g. `prefix`).
+        let empty = toml::Value::Table(toml::Table::new());
+        let Ok(spec) =
+            FallbackSpec::deserialize(value.clone()).or_else(|_| FallbackSpec::deserialize(empty))
+        else {
+            continue;
+        };
+        let mach = Arc::new(BrokenMachine::new(kind.to_string()));
+        visitor.visit(mach, spec.sched, spec.decor, handle);
+    }
+}
+
+struct SpawnVisitor<'a> {
+    effects: Arc<EffectEngine>,
+    global: GlobalConfig,
+    paused_tx: &'a tokio::sync::watch::Sender<bool>,
+    wrappers: Vec<MachineWrapper>,
+}
+
+impl MachineVisitor for SpawnVisitor<'_> {
+    fn visit<M: UnitMachine>(
+        &mut self,
+        machine: Arc<M>,
+        sched: SchedulingCfg,
+        decor: DecorationCfg,
+        handle: usize,
+    ) {
+        self.wrappers.push(spawn_machine_actor(
+            machine,
+            self.effects.clone(),
+            sched,
+            decor,
+            self.global,
+            handle,
+            ActorInputs {
+                paused_rx: self.paused_tx.subscribe(),
+            },
+        ));
+    }
+}
+
+/// Collects units whose config fails `validate`.
+#[derive(Default)]
+struct CheckVisitor {
+    problems: Vec<String>,
+}
+
+impl MachineVisitor for CheckVisitor {
+    fn visit<M: UnitMachine>(
+        &mut self,
+        machine: Arc<M>,
+        _sched: SchedulingCfg,
+        _decor: DecorationCfg,
+        handle: usize,
+    ) {
+        if let Err(e) = crate::machine::runtime::init_validated(&*machine) {
+            self.problems
+                .push(format!("unit #{handle} ({}): {e}", machine.name()));
+        }
+    }
+}
+
+fn xdg_config_path() -> Result<PathBuf> {
+    BaseDirectories::with_prefix(CONFIG_PREFIX)
+        .get_config_file(CONFIG_FILE)
+        .context("no XDG config directory (is HOME set?)")
+}
+
+/// Reads `explicit` if given (a missing file is an error), else the XDG
+/// config. Without one the text is empty, i.e. defaults and no units; nothing
+/// is written, see [`init_cfg`].
+fn read_config_text(explicit: Option<&Path>) -> Result<(PathBuf, String)> {
+    if let Some(path) = explicit {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        return Ok((path.to_path_buf(), text));
+    }
+    let path = xdg_config_path()?;
+    if !path.exists() {
+        warn!(
+            "No config at {}; running without units. `empty-status --init` writes a sample there.",
+            path.display()
+        );
+        return Ok((path, String::new()));
+    }
+    let text = fs::read_to_string(&path)?;
+    Ok((path, text))
+}
+
+/// Writes the sample config to `explicit` or the XDG path for `--init`,
+/// refusing to replace an existing file.
+pub fn init_cfg(explicit: Option<&Path>) -> Result<PathBuf> {
+    let path = match explicit {
+        Some(path) => path.to_path_buf(),
+        None => BaseDirectories::with_prefix(CONFIG_PREFIX).place_config_file(CONFIG_FILE)?,
+    };
+    anyhow::ensure!(!path.exists(), "{} already exists", path.display());
+    fs::write(&path, sample_config()).with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
+fn load_root_config(explicit: Option<&Path>) -> Result<RootConfig> {
+    let (path, text) = read_config_text(explicit)?;
+    toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Every problem in the config, one line each: unknown unit types, per-unit
+/// parse failures and failed unit validation. Unlike loading, one bad unit does
+/// not hide problems in the others.
+pub fn check_cfg(explicit: Option<&Path>) -> Result<(PathBuf, Vec<String>)> {
+    let (path, text) = read_config_text(explicit)?;
+    let problems = check_config_text(&text);
+    Ok((path, problems))
+}
+
+pub(crate) fn check_config_text(text: &str) -> Vec<String> {
+    let root: toml::Table = match toml::from_str(text) {
+        Ok(root) => root,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut problems = Vec::new();
+    let mut checker = CheckVisitor::default();
+    let units = root.get("units").and_then(toml::Value::as_array);
+    for (handle, value) in units.into_iter().flatten().enumerate() {
+        let ty = value
+            .get("type")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("<no type>");
+        match UnitConfig::deserialize(value.clone()) {
+            Ok(UnitConfig::_External) => {
+                problems.push(format!("unit #{handle} ({ty}): unknown unit type"));
             }
-            Err(e) => {
-                error!("Failed to load unit: {e:#}");
+            Ok(uc) => {
+                uc.visit(handle, &mut checker);
             }
+            Err(e) => problems.push(format!("unit #{handle} ({ty}): {}", e.message())),
         }
     }
+    problems.extend(checker.problems);
+
+    // Units were checked one by one; the rest covers `[global]`, `[http]` and
+    // unknown top-level keys.
+    let mut rest = root;
+    rest.remove("units");
+    if let Err(e) = RootConfig::deserialize(toml::Value::Table(rest)) {
+        problems.push(e.message().to_string());
+    }
+    problems
+}
+
+pub fn load_status_from_cfg(explicit: Option<&Path>) -> Result<EmptyStatus> {
+    let raw = load_root_config(explicit)?;
+    crate::core::set_theme(raw.theme);
+
+    let (click_tx, _) = tokio::sync::broadcast::channel::<crate::core::ClickEvent>(16);
+    let (paused_tx, _) = tokio::sync::watch::channel(false);
+    let mut spawner = SpawnVisitor {
+        effects: EffectEngine::new(raw.http.rate_overrides()),
+        global: raw.global,
+        paused_tx: &paused_tx,
+        wrappers: Vec::new(),
+    };
+
+    visit_units(&raw.units, &mut spawner);
+    let machine_wrappers = spawner.wrappers;
 
     info!("Using global config: {:?}", raw.global);
-    Ok(EmptyStatus::new(raw.global, machine_wrappers, click_tx))
+    Ok(EmptyStatus::new(
+        raw.global,
+        raw.theme,
+        raw.metrics,
+        machine_wrappers,
+        click_tx,
+        paused_tx,
+    ))
+}
+
+/// Initializes and polls every configured unit once, without the bar.
+pub async fn dump_units_from_cfg(explicit: Option<&Path>) -> Result<Vec<UnitDump>> {
+    let raw = load_root_config(explicit)?;
+    crate::core::set_theme(raw.theme);
+    let effects = EffectEngine::new(raw.http.rate_overrides());
+    Ok(dump_units(&raw.units, effects, raw.theme.palette).await)
+}
+
+/// [`dump_units_from_cfg`] for already-read `[[units]]`, through `effects`,
+/// with pango colors from `palette`.
+pub(crate) async fn dump_units<E: Effects + 'static>(
+    units: &[toml::Value],
+    effects: Arc<E>,
+    palette: Theme,
+) -> Vec<UnitDump> {
+    let mut dumper = DumpVisitor::new(effects, units, palette);
+    visit_units(units, &mut dumper);
+    dumper.finish().await
 }
 
 fn sample_config() -> &'static str {
     r#"# Global config.
-
+[global]
 min_polling_interval = 0.15
 padding = 1
 
 # Units appear on the bar in the same order as they are defined here.
-# Topmost is rightmost.
+# Topmost is rightmost (set `order = "config"` to make it leftmost).
 
 [[units]]
 type = "Time"
@@ -2,7 +2,10 @@
 mod tests {
     use serde::Deserialize;
 
-    use crate::config::{GlobalConfig, SchedulingCfg};
+    use crate::config::{
+        DecorationCfg, GlobalConfig, HttpConfig, MetricsConfig, SchedulingCfg, ThemeConfig,
+    };
+    use crate::render::color::Srgb8;
 
     #[derive(Deserialize)]
     #[serde(deny_unknown_fields)]
@@ -10,6 +13,12 @@ mod tests {
     struct RootConfigForTest {
         global: GlobalConfig,
         #[serde(default)]
+        http: HttpConfig,
+        #[serde(default)]
+        theme: ThemeConfig,
+        #[serde(default)]
+        metrics: MetricsConfig,
+        #[serde(default)]
         units: Vec<UnitConfigForTest>,
     }
 
@@ -27,12 +36,32 @@ mod tests {
         Mem(UnitSpecForTest<crate::units::mem::MemConfig>),
         #[serde(rename = "Disk")]
         Disk(UnitSpecForTest<crate::units::disk::DiskConfig>),
+        #[serde(rename = "File")]
+        File(UnitSpecForTest<crate::units::file::FileConfig>),
         #[serde(rename = "Wifi")]
         Wifi(UnitSpecForTest<crate::units::wifi::WifiConfig>),
         #[serde(rename = "Bat")]
         Bat(UnitSpecForTest<crate::units::bat::BatConfig>),
         #[serde(rename = "Net")]
         Net(UnitSpecForTest<crate::units::net::NetConfig>),
+        #[serde(rename = "Clipboard")]
+        Clipboard(UnitSpecForTest<crate::units::clipboard::ClipboardConfig>),
+        #[serde(rename = "Mic")]
+        Mic(UnitSpecForTest<crate::units::mic::MicConfig>),
+        #[serde(rename = "Locks")]
+        Locks(UnitSpecForTest<crate::units::locks::LocksConfig>),
+        #[serde(rename = "Window")]
+        Window(UnitSpecForTest<crate::units::window::WindowConfig>),
+        #[serde(rename = "Http")]
+        Http(UnitSpecForTest<crate::units::http::HttpUnitConfig>),
+        #[serde(rename = "Command")]
+        Command(UnitSpecForTest<crate::units::command::CommandConfig>),
+        #[serde(rename = "Load")]
+        Load(UnitSpecForTest<crate::units::load::LoadConfig>),
+        #[serde(rename = "Uptime")]
+        Uptime(UnitSpecForTest<crate::units::uptime::UptimeConfig>),
+        #[serde(rename = "Spacer")]
+        Spacer(UnitSpecForTest<crate::units::spacer::SpacerConfig>),
     }
 
     #[derive(Deserialize)]
@@ -42,6 +71,8 @@ mod tests {
         #[serde(flatten)]
         sched: SchedulingCfg,
         #[serde(flatten)]
+        decor: DecorationCfg,
+        #[serde(flatten)]
         cfg: Cfg,
     }
 
@@ -73,6 +104,199 @@ poll_interval = 0.5
         let _: RootConfigForTest = toml::from_str(text).unwrap();
     }
 
+    #[test]
+    fn example_config_passes_check() {
+        let text = include_str!("../config.example.toml");
+        assert_eq!(crate::config::check_config_text(text), Vec::<String>::new());
+    }
+
+    #[test]
+    fn explicit_config_path_is_used_as_is() {
+        let example =
+            std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml"));
+        let (path, problems) = crate::config::check_cfg(Some(example)).unwrap();
+        assert_eq!(path, example);
+        assert!(problems.is_empty(), "{problems:?}");
+
+        let missing = example.with_file_name("no-such-config.toml");
+        assert!(crate::config::check_cfg(Some(&missing)).is_err());
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn init_writes_the_sample_once() {
+        let path =
+            std::env::temp_dir().join(format!("empty-status-init-{}.toml", std::process::id()));
+        assert_eq!(crate::config::init_cfg(Some(&path)).unwrap(), path);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let cfg: RootConfigForTest = toml::from_str(&text).unwrap();
+        assert_eq!(cfg.units.len(), 1);
+        assert!((cfg.global.min_polling_interval - 0.15).abs() < f64::EPSILON);
+        let (_, problems) = crate::config::check_cfg(Some(&path)).unwrap();
+        assert!(problems.is_empty(), "{problems:?}");
+        assert!(crate::config::init_cfg(Some(&path)).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Example config -> units -> `init` + one poll against effects that fail
+    /// every request: each unit must still render something.
+    #[tokio::test]
+    async fn example_config_renders_every_unit_offline() {
+        use crate::machine::effects::MockEffects;
+        let root: toml::Table = toml::from_str(include_str!("../config.example.toml")).unwrap();
+        let units = root["units"].as_array().unwrap();
+        let dumps = crate::config::dump_units(
+            units,
+            std::sync::Arc::new(MockEffects::default()),
+            crate::core::Theme::default(),
+        )
+        .await;
+        assert_eq!(dumps.len(), units.len());
+        for dump in &dumps {
+            assert!(
+                !dump.text.trim().is_empty(),
+                "{} rendered nothing",
+                dump.unit
+            );
+            assert_ne!(dump.unit, "Bad cfg", "unit #{} failed to load", dump.handle);
+            assert_eq!(dump.config, units[dump.handle]);
+        }
+    }
+
+    /// A unit that fails to load keeps its place on the bar as an error view.
+    #[tokio::test]
+    async fn broken_units_show_a_placeholder_in_place() {
+        use crate::machine::effects::MockEffects;
+        use crate::machine::types::{Health, UnitMachine};
+        let root: toml::Table = toml::from_str(
+            r#"
+[[units]]
+type = "Time"
+
+[[units]]
+type = "Nope"
+
+[[units]]
+type = "Time"
+bogus = 1
+"#,
+        )
+        .unwrap();
+        let units = root["units"].as_array().unwrap();
+        let dumps = crate::config::dump_units(
+            units,
+            std::sync::Arc::new(MockEffects::default()),
+            crate::core::Theme::default(),
+        )
+        .await;
+        let placed: Vec<_> = dumps.iter().map(|d| (d.handle, d.unit)).collect();
+        assert_eq!(placed, [(0, "Time"), (1, "Bad cfg"), (2, "Bad cfg")]);
+
+        let broken = crate::machine::units::broken::BrokenMachine::new("Nope".to_string());
+        let (_, view, _) = broken.init();
+        assert_eq!(crate::units::testing::plain(&view), "bad cfg: Nope");
+        assert_eq!(view.health, Health::Error);
+        assert_eq!(
+            crate::units::testing::pango(&view),
+            "<span color='#CC6666'>bad cfg: Nope</span>"
+        );
+        assert_eq!(dumps[2].text, "bad cfg: Time");
+    }
+
+    #[test]
+    fn check_reports_each_bad_unit() {
+        let text = r#"
+[global]
+padding = "wide"
+
+[[units]]
+type = "Nope"
+
+[[units]]
+type = "Time"
+bogus = 1
+
+[[units]]
+type = "Disk"
+
+[[units]]
+type = "Time"
+"#;
+        let problems = crate::config::check_config_text(text);
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].starts_with("unit #0 (Nope): unknown unit type"));
+        assert!(problems[1].starts_with("unit #1 (Time): unknown field `bogus`"));
+        assert!(problems[2].starts_with("unit #2 (Disk): "));
+    }
+
+    #[test]
+    fn text_color_must_be_hex() {
+        let ok: GlobalConfig = toml::from_str("text_color = \"#c5c8c6\"").unwrap();
+        assert_eq!(ok.text_color.map(Srgb8::to_hex).as_deref(), Some("#C5C8C6"));
+        assert!(toml::from_str::<GlobalConfig>("text_color = \"grey\"").is_err());
+    }
+
+    #[test]
+    fn theme_overrides_only_listed_colors() {
+        let theme: ThemeConfig = toml::from_str("error = \"#ff0000\"").unwrap();
+        assert_eq!(theme.error.to_hex(), "#FF0000");
+        assert_eq!(theme.loading, Srgb8::from(crate::core::VIOLET));
+        assert_eq!(theme.missing, Srgb8::from(crate::core::BROWN));
+    }
+
+    #[test]
+    fn palette_defaults_to_base16_tomorrow_and_overrides_by_slot() {
+        use crate::core::{Color, Theme};
+        let base = Theme::base16_tomorrow();
+        for (color, hex) in [
+            (Color::DarkGrey, crate::core::DARK_GREY),
+            (Color::Grey, crate::core::GREY),
+            (Color::Red, crate::core::RED),
+            (Color::Orange, crate::core::ORANGE),
+            (Color::Yellow, crate::core::YELLOW),
+            (Color::Green, crate::core::GREEN),
+            (Color::Cyan, crate::core::CYAN),
+            (Color::Blue, crate::core::BLUE),
+            (Color::Violet, crate::core::VIOLET),
+            (Color::Brown, crate::core::BROWN),
+        ] {
+            assert_eq!(base.get(color), Srgb8::from(hex), "{color:?}");
+        }
+
+        let theme: ThemeConfig = toml::from_str("[palette]\nred = \"#ff0000\"").unwrap();
+        assert_eq!(theme.palette.get(Color::Red).to_hex(), "#FF0000");
+        assert_eq!(theme.palette.get(Color::Green), base.green);
+        assert!(toml::from_str::<ThemeConfig>("[palette]\npurple = \"#ff0000\"").is_err());
+    }
+
+    #[test]
+    fn http_ratelimit_overrides_parse() {
+        let text = r#"
+[global]
+min_polling_interval = 0.25
+padding = 1
+
+[http.ratelimit]
+"api.open-meteo.com" = { burst = 2, per_ms = 60000 }
+"#;
+        let cfg: RootConfigForTest = toml::from_str(text).unwrap();
+        let spec = cfg.http.rate_overrides()["api.open-meteo.com"];
+        assert_eq!(spec.burst, 2);
+        assert_eq!(spec.per, std::time::Duration::from_secs(60));
+    }
+
+    #[test]
+    fn http_ratelimit_rejects_zero_burst_or_period() {
+        for entry in ["{ burst = 0, per_ms = 1000 }", "{ burst = 1, per_ms = 0 }"] {
+            let text = format!("[global]\n[http.ratelimit]\n\"example.com\" = {entry}\n");
+            let err = toml::from_str::<RootConfigForTest>(&text).err();
+            assert!(
+                err.is_some_and(|e| e.message().contains("must be at least 1")),
+                "{entry}"
+            );
+        }
+    }
+
     #[test]
     fn disk_unit_allows_partlabel_only() {
         let text = r#"
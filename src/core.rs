@@ -1,11 +1,8 @@
 use serde::{Deserialize, Serialize};
-use tokio::io::AsyncBufReadExt;
-use tokio::io::BufReader;
-use tokio::sync::broadcast::{channel, Sender};
-use tracing::warn;
 
-use crate::config::GlobalConfig;
-use crate::machine::runtime::{run_empty_status_machines, MachineWrapper};
+use crate::config::{ClickModifier, GlobalConfig, MetricsConfig, ThemeConfig};
+use crate::machine::runtime::{run_empty_status_machines, BarConfig, MachineWrapper};
+use crate::render::color::Srgb8;
 
 // Color definitions from the base16 tomorrow theme
 pub const DARK_GREY: &str = "#373B41";
@@ -19,10 +16,99 @@ pub const BLUE: &str = "#81A2BE";
 pub const VIOLET: &str = "#B294BB";
 pub const BROWN: &str = "#A3685A";
 
+/// A palette slot. Units name the color they mean and the `[theme.palette]`
+/// decides its hex when the markup is rendered (see [`Theme::get`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    DarkGrey,
+    Grey,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Violet,
+    Brown,
+}
+
+/// The hex behind every [`Color`].
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub dark_grey: Srgb8,
+    pub grey: Srgb8,
+    pub red: Srgb8,
+    pub orange: Srgb8,
+    pub yellow: Srgb8,
+    pub green: Srgb8,
+    pub cyan: Srgb8,
+    pub blue: Srgb8,
+    pub violet: Srgb8,
+    pub brown: Srgb8,
+}
+
+impl Theme {
+    /// The palette the hex constants above define.
+    pub fn base16_tomorrow() -> Self {
+        Self {
+            dark_grey: DARK_GREY.into(),
+            grey: GREY.into(),
+            red: RED.into(),
+            orange: ORANGE.into(),
+            yellow: YELLOW.into(),
+            green: GREEN.into(),
+            cyan: CYAN.into(),
+            blue: BLUE.into(),
+            violet: VIOLET.into(),
+            brown: BROWN.into(),
+        }
+    }
+
+    pub fn get(&self, color: Color) -> Srgb8 {
+        match color {
+            Color::DarkGrey => self.dark_grey,
+            Color::Grey => self.grey,
+            Color::Red => self.red,
+            Color::Orange => self.orange,
+            Color::Yellow => self.yellow,
+            Color::Green => self.green,
+            Color::Cyan => self.cyan,
+            Color::Blue => self.blue,
+            Color::Violet => self.violet,
+            Color::Brown => self.brown,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::base16_tomorrow()
+    }
+}
+
+static THEME: std::sync::OnceLock<ThemeConfig> = std::sync::OnceLock::new();
+
+/// Installs the configured `[theme]`; only the first call takes effect.
+pub fn set_theme(theme: ThemeConfig) {
+    let _ = THEME.set(theme);
+}
+
+/// The configured `[theme]`, or the default palette before one is installed.
+pub fn theme() -> ThemeConfig {
+    THEME.get().copied().unwrap_or_default()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OutputChunk {
     pub full_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_text: Option<String>,
     pub name: String,
+    /// Set to the block's index when a unit spans several blocks, so clicks
+    /// can tell them apart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
     pub markup: String,
     pub border: String,
     pub separator: String,
@@ -31,19 +117,24 @@ pub struct OutputChunk {
     pub background: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub urgent: bool,
 }
 
 impl OutputChunk {
     pub fn new(name: &str, text: String) -> Self {
         Self {
             full_text: text,
+            short_text: None,
             name: name.to_string(),
+            instance: None,
             markup: "pango".to_string(),
             border: DARK_GREY.to_string(),
             separator: "false".to_string(),
             separator_block_width: 0,
             background: None,
             color: None,
+            urgent: false,
         }
     }
 }
@@ -63,61 +154,61 @@ pub struct ClickEvent {
     pub height: i32,
 }
 
+impl ClickEvent {
+    pub const BUTTON_LEFT: i32 = 1;
+    pub const BUTTON_MIDDLE: i32 = 2;
+    pub const BUTTON_RIGHT: i32 = 3;
+    pub const BUTTON_SCROLL_UP: i32 = 4;
+    pub const BUTTON_SCROLL_DOWN: i32 = 5;
+
+    pub fn has_modifier(&self, modifier: ClickModifier) -> bool {
+        self.modifiers.iter().any(|m| m == modifier.as_str())
+    }
+}
+
 pub struct EmptyStatus {
     cfg: GlobalConfig,
+    theme: ThemeConfig,
+    metrics: MetricsConfig,
     machine_wrappers: Vec<MachineWrapper>,
     machine_click_tx: tokio::sync::broadcast::Sender<ClickEvent>,
+    /// `true` while i3bar has paused us; actors and the output loop subscribe.
+    paused_tx: tokio::sync::watch::Sender<bool>,
 }
 
 impl EmptyStatus {
     pub fn new(
         cfg: GlobalConfig,
+        theme: ThemeConfig,
+        metrics: MetricsConfig,
         machine_wrappers: Vec<MachineWrapper>,
         machine_click_tx: tokio::sync::broadcast::Sender<ClickEvent>,
+        paused_tx: tokio::sync::watch::Sender<bool>,
     ) -> Self {
         Self {
             cfg,
+            theme,
+            metrics,
             machine_wrappers,
             machine_click_tx,
+            paused_tx,
         }
     }
 
     pub async fn run(self) {
-        let (click_tx, _) = channel::<ClickEvent>(16);
-        tokio::spawn(read_clicks_task(click_tx.clone()));
-
-        // bridge i3 click events to machine bus
-        {
-            let machine_click_tx = self.machine_click_tx.clone();
-            let mut legacy_rx = click_tx.subscribe();
-            tokio::spawn(async move {
-                while let Ok(click) = legacy_rx.recv().await {
-                    let _ = machine_click_tx.send(click);
-                }
-            });
-        }
-
-        run_empty_status_machines(self.machine_wrappers, self.cfg, self.machine_click_tx).await;
-    }
-}
-
-async fn read_clicks_task(click_tx: Sender<ClickEvent>) {
-    let stdin = tokio::io::stdin();
-    let mut lines = BufReader::new(stdin).lines();
-    let _ = lines.next_line().await;
-    while let Ok(Some(line)) = lines.next_line().await {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let line = line.trim_end_matches(',');
-        match serde_json::from_str::<ClickEvent>(line.trim_start_matches(',')) {
-            Ok(click) => {
-                let _ = click_tx.send(click);
-            }
-            Err(e) => {
-                warn!(%line, %e, "Failed to parse click event");
-            }
-        }
+        tokio::spawn(crate::i3bar::read_clicks(self.machine_click_tx.clone()));
+        let paused_rx = self.paused_tx.subscribe();
+        tokio::spawn(crate::i3bar::watch_stop_signals(self.paused_tx));
+        run_empty_status_machines(
+            self.machine_wrappers,
+            BarConfig {
+                global: self.cfg,
+                theme: self.theme,
+                metrics: self.metrics,
+            },
+            self.machine_click_tx,
+            paused_rx,
+        )
+        .await;
     }
 }
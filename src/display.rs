@@ -1,38 +1,52 @@
-use crate::core::{CYAN, GREEN, ORANGE, RED, YELLOW};
-use crate::render::color::Srgb8;
+use crate::core::Color;
+use serde::Deserialize;
 
-pub const COL_USE_COOL: &str = CYAN;
-pub const COL_USE_NORM: &str = GREEN;
-pub const COL_USE_HIGH: &str = YELLOW;
-pub const COL_USE_VERY_HIGH: &str = ORANGE;
-pub const COL_USE_SCREAMING: &str = RED;
+pub const COL_USE_COOL: Color = Color::Cyan;
+pub const COL_USE_NORM: Color = Color::Green;
+pub const COL_USE_HIGH: Color = Color::Yellow;
+pub const COL_USE_VERY_HIGH: Color = Color::Orange;
+pub const COL_USE_SCREAMING: Color = Color::Red;
 
-pub fn color_by_breakpoint<T: Into<String>, const N: usize>(
+/// Index of the band `value` falls in: the first breakpoint it is strictly
+/// below, or `breakpoints.len()` past the last. `breakpoints` must be ascending.
+/// A value exactly on a breakpoint belongs to the band above it; every
+/// breakpoint-banded display (colors, Disk bars) goes through here.
+pub fn band_index(value: f64, breakpoints: &[f64]) -> usize {
+    breakpoints
+        .iter()
+        .position(|&bp| value < bp)
+        .unwrap_or(breakpoints.len())
+}
+
+/// Picks `colors[i]` for band `i` (see [`band_index`]), else `outer_color`.
+pub fn color_by_breakpoint<const N: usize>(
     value: f64,
     breakpoints: &[f64; N],
-    colors: &[&'static str; N],
-    outer_color: T,
-) -> Srgb8 {
-    for (i, &bp) in breakpoints.iter().enumerate() {
-        if value < bp {
-            return Srgb8::from(colors[i]);
-        }
-    }
-    Srgb8::from(outer_color.into())
+    colors: &[Color; N],
+    outer_color: Color,
+) -> Color {
+    colors
+        .get(band_index(value, breakpoints))
+        .copied()
+        .unwrap_or(outer_color)
 }
 const PCT_BPS: &[f64; 4] = &[20.0, 40.0, 60.0, 80.0];
-const PCT_COLORS: &[&str; 4] = &[COL_USE_COOL, COL_USE_NORM, COL_USE_HIGH, COL_USE_VERY_HIGH];
+const PCT_COLORS: &[Color; 4] = &[COL_USE_COOL, COL_USE_NORM, COL_USE_HIGH, COL_USE_VERY_HIGH];
 
-pub fn color_by_pct(value: f64) -> String {
-    color_by_breakpoint(value, PCT_BPS, PCT_COLORS, COL_USE_SCREAMING).to_hex()
+/// Cool to screaming as `value` (percent) rises, with bands at 20/40/60/80.
+pub fn color_by_pct(value: f64) -> Color {
+    color_by_breakpoint(value, PCT_BPS, PCT_COLORS, COL_USE_SCREAMING)
 }
 
 // TODO implement proper gradients! yeah!
-pub fn color_by_pct_custom(value: f64, breakpoints: &[f64; 4]) -> String {
-    color_by_breakpoint(value, breakpoints, PCT_COLORS, COL_USE_SCREAMING).to_hex()
+/// As [`color_by_pct`] with caller-chosen band edges.
+pub fn color_by_pct_custom(value: f64, breakpoints: &[f64; 4]) -> Color {
+    color_by_breakpoint(value, breakpoints, PCT_COLORS, COL_USE_SCREAMING)
 }
 
-pub fn color_by_pct_rev(value: f64) -> String {
+/// [`color_by_pct`] with the palette reversed, for levels where low is bad
+/// (battery charge, signal strength).
+pub fn color_by_pct_rev(value: f64) -> Color {
     color_by_breakpoint(
         value,
         PCT_BPS,
@@ -44,11 +58,77 @@ pub fn color_by_pct_rev(value: f64) -> String {
         ],
         COL_USE_COOL,
     )
-    .to_hex()
 }
 
-pub fn format_duration(seconds: f64) -> String {
+/// Display unit for temperatures; readings and color scales stay in Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TempUnits {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TempUnits {
+    pub fn suffix(&self) -> &str {
+        match self {
+            TempUnits::Celsius => "C",
+            TempUnits::Fahrenheit => "F",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            TempUnits::Celsius => TempUnits::Fahrenheit,
+            TempUnits::Fahrenheit => TempUnits::Celsius,
+        }
+    }
+
+    pub fn convert_from_celcius(&self, temp_c: f64) -> f64 {
+        match self {
+            TempUnits::Celsius => temp_c,
+            TempUnits::Fahrenheit => temp_c * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+/// Upper bound for the configurable `*precision` options.
+pub const MAX_PRECISION: u8 = 3;
+
+/// `value` right-aligned to `int_width` integer digits plus `precision`
+/// (clamped to [`MAX_PRECISION`]) decimals, so columns keep their width.
+pub fn format_fixed(value: f64, int_width: usize, precision: u8) -> String {
+    let precision = usize::from(precision.min(MAX_PRECISION));
+    let width = int_width + if precision > 0 { precision + 1 } else { 0 };
+    format!("{value:>width$.precision$}")
+}
+
+/// How [`format_duration`] lays out a span of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationStyle {
+    /// Two units in fixed-width columns: ` 3 d  4 h`.
+    Padded,
+    /// Two units, no padding: `3d4h`.
+    Compact,
+    /// The largest whole unit spelled out: `3 days`.
+    Single,
+}
+
+/// Seconds per unit, largest first, with short and long names.
+const DURATION_UNITS: [(f64, &str, &str); 6] = [
+    (31_557_600.0, "y", "year"),
+    (604_800.0, "w", "week"),
+    (86_400.0, "d", "day"),
+    (3600.0, "h", "hour"),
+    (60.0, "m", "minute"),
+    (1.0, "s", "second"),
+];
+
+pub fn format_duration(seconds: f64, style: DurationStyle) -> String {
     if seconds < 60.0 {
+        if style == DurationStyle::Single && seconds >= 1.0 {
+            return plural(seconds.floor() as u32, "second");
+        }
         // Handle small values
         let (value, unit) = if seconds < 1e-9 {
             (seconds * 1e12, "ps")
@@ -63,41 +143,88 @@ pub fn format_duration(seconds: f64) -> String {
         };
 
         let precision = (2.0 - value.log10().floor()).clamp(0.0, 2.0) as usize;
-        format!(
-            "  {:.precision$} {:<2} ",
-            value,
-            unit,
-            precision = precision
-        )
+        match style {
+            DurationStyle::Padded => format!("  {value:.precision$} {unit:<2} "),
+            DurationStyle::Compact => format!("{value:.precision$}{unit}"),
+            DurationStyle::Single => format!("{value:.precision$} {unit}"),
+        }
     } else if seconds < 3_155_760_000.0 {
-        // Less than 10 years
-        if seconds < 3600.0 {
-            // < 1 hour
-            let min = (seconds / 60.0).floor() as u32;
-            let sec = (seconds % 60.0) as u32;
-            format!("{min:2} m {sec:2} s")
-        } else if seconds < 86400.0 {
-            // < 1 day
-            let hr = (seconds / 3600.0).floor() as u32;
-            let min = ((seconds % 3600.0) / 60.0) as u32;
-            format!("{hr:2} h {min:2} m")
-        } else if seconds < 604_800.0 {
-            // < 1 week
-            let day = (seconds / 86400.0).floor() as u32;
-            let hr = ((seconds % 86400.0) / 3600.0) as u32;
-            format!("{day:2} d {hr:2} h")
-        } else if seconds < 31_557_600.0 {
-            // < 1 year
-            let week = (seconds / 604_800.0).floor() as u32;
-            let day = ((seconds % 604_800.0) / 86_400.0) as u32;
-            format!("{week:2} w {day:2} d")
-        } else {
-            // < 10 years
-            let year = (seconds / 31_557_600.0).floor() as u32;
-            let week = ((seconds % 31_557_600.0) / 604_800.0) as u32;
-            format!("{year:2} y {week:2} w")
+        // Less than 10 years: the largest unit that fits, then the next one.
+        let ix = DURATION_UNITS
+            .iter()
+            .position(|&(size, _, _)| seconds >= size)
+            .unwrap_or(DURATION_UNITS.len() - 2);
+        let (size, major, major_long) = DURATION_UNITS[ix];
+        let (sub_size, minor, _) = DURATION_UNITS[ix + 1];
+        let big = (seconds / size).floor() as u32;
+        let small = ((seconds % size) / sub_size) as u32;
+        match style {
+            DurationStyle::Padded => format!("{big:2} {major} {small:2} {minor}"),
+            DurationStyle::Compact => format!("{big}{major}{small}{minor}"),
+            DurationStyle::Single => plural(big, major_long),
         }
     } else {
-        " > 10 y  ".to_string()
+        match style {
+            DurationStyle::Padded => " > 10 y  ".to_string(),
+            DurationStyle::Compact => ">10y".to_string(),
+            DurationStyle::Single => "> 10 years".to_string(),
+        }
+    }
+}
+
+fn plural(n: u32, unit: &str) -> String {
+    format!("{n} {unit}{}", if n == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        color_by_pct, color_by_pct_rev, format_duration, format_fixed, DurationStyle, COL_USE_COOL,
+        COL_USE_NORM, COL_USE_SCREAMING, COL_USE_VERY_HIGH,
+    };
+
+    #[test]
+    fn value_on_a_breakpoint_takes_the_next_band() {
+        assert_eq!(color_by_pct(19.99), COL_USE_COOL);
+        assert_eq!(color_by_pct(20.0), COL_USE_NORM);
+        assert_eq!(color_by_pct(79.99), COL_USE_VERY_HIGH);
+        assert_eq!(color_by_pct(80.0), COL_USE_SCREAMING);
+        assert_eq!(color_by_pct_rev(19.99), COL_USE_SCREAMING);
+        assert_eq!(color_by_pct_rev(20.0), COL_USE_VERY_HIGH);
+        assert_eq!(color_by_pct_rev(80.0), COL_USE_COOL);
+    }
+
+    #[test]
+    fn fixed_width_grows_with_precision() {
+        assert_eq!(format_fixed(45.26, 3, 0), " 45");
+        assert_eq!(format_fixed(45.26, 3, 1), " 45.3");
+        assert_eq!(format_fixed(0.4, 1, 2), "0.40");
+        assert_eq!(format_fixed(1.0, 1, 9), "1.000");
+    }
+
+    #[test]
+    fn duration_styles_across_unit_boundaries() {
+        use DurationStyle::{Compact, Padded, Single};
+        let cases: [(f64, &str, &str, &str); 9] = [
+            (0.35, "  350 ms ", "350ms", "350 ms"),
+            (42.0, "  42.0 s  ", "42.0s", "42 seconds"),
+            (60.0, " 1 m  0 s", "1m0s", "1 minute"),
+            (3599.0, "59 m 59 s", "59m59s", "59 minutes"),
+            (3600.0, " 1 h  0 m", "1h0m", "1 hour"),
+            (
+                3.0 * 86_400.0 + 4.0 * 3600.0 + 60.0,
+                " 3 d  4 h",
+                "3d4h",
+                "3 days",
+            ),
+            (604_800.0 + 86_400.0, " 1 w  1 d", "1w1d", "1 week"),
+            (2.0 * 31_557_600.0, " 2 y  0 w", "2y0w", "2 years"),
+            (4e9, " > 10 y  ", ">10y", "> 10 years"),
+        ];
+        for (secs, padded, compact, single) in cases {
+            assert_eq!(format_duration(secs, Padded), padded, "{secs}");
+            assert_eq!(format_duration(secs, Compact), compact, "{secs}");
+            assert_eq!(format_duration(secs, Single), single, "{secs}");
+        }
     }
 }
@@ -0,0 +1,164 @@
+//! The i3bar protocol: header, status-line framing and click input. Both the
+//! output loop and the click reader go through here, so the wire format has a
+//! single source of truth.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::broadcast::Sender;
+use tokio::sync::watch;
+use tracing::warn;
+
+use crate::config::UnitOrder;
+use crate::core::{ClickEvent, OutputChunk};
+
+/// Protocol header plus the opening of the endless status array. It asks
+/// i3bar to pause us with SIGUSR1/SIGUSR2 instead of SIGSTOP/SIGCONT, so a
+/// hidden bar stops polling rather than piling up work.
+pub const HEADER: &str =
+    "{\"version\":1,\"click_events\":true,\"stop_signal\":10,\"cont_signal\":12}\n[\n";
+
+/// Publishes `true` on i3bar's stop signal and `false` on its cont signal.
+pub async fn watch_stop_signals(paused_tx: watch::Sender<bool>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let (Ok(mut stop), Ok(mut cont)) = (
+        signal(SignalKind::user_defined1()),
+        signal(SignalKind::user_defined2()),
+    ) else {
+        warn!("Cannot listen for stop/cont signals; the bar will never pause");
+        return;
+    };
+    loop {
+        let paused = tokio::select! {
+            Some(()) = stop.recv() => true,
+            Some(()) = cont.recv() => false,
+            else => break,
+        };
+        paused_tx.send_replace(paused);
+    }
+}
+
+/// Maps config order to the left-to-right order i3bar renders.
+pub fn order(handles: &[usize], order: UnitOrder) -> Vec<usize> {
+    let mut out = handles.to_vec();
+    if order == UnitOrder::Reverse {
+        out.reverse();
+    }
+    out
+}
+
+/// One element of the i3bar status array. Every element but the last carries a
+/// trailing comma; the last one also closes the array opened by the header.
+pub fn status_line(
+    handles: &[usize],
+    latest: &HashMap<usize, Vec<OutputChunk>>,
+    last: bool,
+) -> String {
+    let chunks: Vec<String> = handles
+        .iter()
+        .filter_map(|h| latest.get(h))
+        .flatten()
+        .map(|chunk| serde_json::to_string(chunk).unwrap_or_default())
+        .collect();
+    let terminator = if last { "\n]\n" } else { ",\n" };
+    format!("[{}]{terminator}", chunks.join(","))
+}
+
+pub fn write_line(line: &str) {
+    let _ = io::stdout().write_all(line.as_bytes());
+    let _ = io::stdout().flush();
+}
+
+/// Parses one line of i3bar's click stream; `None` for blank lines. Elements of
+/// the endless array may carry a comma on either side.
+fn parse_click(line: &str) -> Option<serde_json::Result<ClickEvent>> {
+    let line = line.trim().trim_matches(',');
+    (!line.is_empty()).then(|| serde_json::from_str(line))
+}
+
+/// Forwards clicks from stdin until it closes. The first line opens the array.
+pub async fn read_clicks(click_tx: Sender<ClickEvent>) {
+    let stdin = tokio::io::stdin();
+    let mut lines = BufReader::new(stdin).lines();
+    let _ = lines.next_line().await;
+    while let Ok(Some(line)) = lines.next_line().await {
+        match parse_click(&line) {
+            Some(Ok(click)) => {
+                let _ = click_tx.send(click);
+            }
+            Some(Err(e)) => warn!(%line, %e, "Failed to parse click event"),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn final_status_line_closes_the_array() {
+        let latest = HashMap::from([(0, vec![OutputChunk::new("a::0", "x".into())])]);
+        let header = "[\n";
+        let stream = format!(
+            "{header}{}{}",
+            status_line(&[0], &latest, false),
+            status_line(&[0], &latest, true)
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&stream).unwrap();
+        assert_eq!(parsed.as_array().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn multi_block_units_are_flattened_in_order() {
+        let latest = HashMap::from([
+            (0, vec![OutputChunk::new("a::0", "x".into())]),
+            (
+                1,
+                vec![
+                    OutputChunk::new("b::1", "y".into()),
+                    OutputChunk::new("b::1", "z".into()),
+                ],
+            ),
+        ]);
+        let line = status_line(&[1, 0], &latest, false);
+        let parsed: serde_json::Value =
+            serde_json::from_str(line.trim().trim_end_matches(',')).unwrap();
+        let texts: Vec<_> = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["full_text"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(texts, ["y", "z", "x"]);
+    }
+
+    #[test]
+    fn header_declares_catchable_stop_signals() {
+        let header = HEADER.trim().trim_end_matches('[');
+        let header: serde_json::Value = serde_json::from_str(header).unwrap();
+        // SIGUSR1 / SIGUSR2 on Linux, the signals `watch_stop_signals` listens to.
+        assert_eq!(
+            (
+                header["stop_signal"].as_i64(),
+                header["cont_signal"].as_i64()
+            ),
+            (Some(10), Some(12))
+        );
+    }
+
+    #[test]
+    fn order_follows_config() {
+        assert_eq!(order(&[0, 1, 2], UnitOrder::Reverse), [2, 1, 0]);
+        assert_eq!(order(&[0, 1, 2], UnitOrder::Config), [0, 1, 2]);
+    }
+
+    #[test]
+    fn click_lines_tolerate_array_commas() {
+        let click = r#"{"name":"Cpu::0","instance":null,"button":1,"modifiers":[],"x":0,"y":0,"relative_x":0,"relative_y":0,"width":0,"height":0}"#;
+        for line in [click.to_string(), format!(",{click}"), format!("{click},")] {
+            assert_eq!(parse_click(&line).unwrap().unwrap().name, "Cpu::0");
+        }
+        assert!(parse_click(" , ").is_none());
+    }
+}
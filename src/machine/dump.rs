@@ -0,0 +1,79 @@
+//! One-shot diagnostic run: every unit is initialized and polled once and the
+//! result reported as data instead of driving i3bar.
+
+use crate::config::{DecorationCfg, MachineVisitor, SchedulingCfg};
+use crate::core::Theme;
+use crate::machine::effects::Effects;
+use crate::machine::runtime::poll_once;
+use crate::machine::types::{Health, UnitMachine};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct UnitDump {
+    pub unit: &'static str,
+    pub handle: usize,
+    /// The unit's `[[units]]` entry as read from the loaded config file.
+    pub config: toml::Value,
+    /// The rendered text with styling dropped.
+    pub text: String,
+    /// The pango string i3bar would receive.
+    pub pango: String,
+    pub health: Health,
+    pub error: Option<String>,
+}
+
+pub struct DumpVisitor<E> {
+    effects: Arc<E>,
+    /// The `[[units]]` being visited, indexed by handle.
+    units: Vec<toml::Value>,
+    polls: Vec<BoxFuture<'static, UnitDump>>,
+    palette: Theme,
+}
+
+impl<E: Effects + 'static> DumpVisitor<E> {
+    pub fn new(effects: Arc<E>, units: &[toml::Value], palette: Theme) -> Self {
+        Self {
+            effects,
+            units: units.to_vec(),
+            polls: Vec::new(),
+            palette,
+        }
+    }
+
+    /// Runs all collected polls concurrently, in config order.
+    pub async fn finish(self) -> Vec<UnitDump> {
+        futures::future::join_all(self.polls).await
+    }
+}
+
+impl<E: Effects + 'static> MachineVisitor for DumpVisitor<E> {
+    fn visit<M: UnitMachine>(
+        &mut self,
+        machine: Arc<M>,
+        _sched: SchedulingCfg,
+        _decor: DecorationCfg,
+        handle: usize,
+    ) {
+        let effects = self.effects.clone();
+        let palette = self.palette;
+        let config = self
+            .units
+            .get(handle)
+            .cloned()
+            .unwrap_or_else(|| toml::Value::Table(toml::Table::new()));
+        self.polls.push(Box::pin(async move {
+            let (view, error) = poll_once(&*machine, &*effects).await;
+            UnitDump {
+                unit: machine.name(),
+                handle,
+                config,
+                text: view.body.plain_text(),
+                pango: crate::render::pango::to_pango(&view.body, &palette),
+                health: view.health,
+                error,
+            }
+        }));
+    }
+}
@@ -22,6 +22,7 @@ impl HttpCacheKey {
 pub enum EffectReq {
     HttpGet(HttpGet),
     ProcBatch(ProcBatch),
+    ProcRun(ProcRun),
     FsRead(FsRead),
     FsListDir(FsListDir),
 }
@@ -43,10 +44,35 @@ pub struct HttpPolicy {
 pub enum EffectOut {
     Http(HttpResponse),
     ProcLines(Vec<String>),
+    ProcOutput(ProcOutput),
     FsBytes(bytes::Bytes),
     DirEntries(DirEntries),
 }
 
+/// Where machine units send their effect requests. [`EffectEngine`] does the
+/// real I/O; tests substitute `MockEffects`.
+pub trait Effects: Send + Sync {
+    fn run(
+        &self,
+        req: EffectReq,
+    ) -> impl std::future::Future<Output = Result<EffectOut, TransportError>> + Send;
+}
+
+#[cfg(test)]
+impl EffectReq {
+    /// The request's cache/process key.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            EffectReq::HttpGet(get) => &get.key.0,
+            EffectReq::ProcBatch(pb) => &pb.key.0,
+            EffectReq::ProcRun(pr) => &pr.key.0,
+            EffectReq::FsRead(fr) => &fr.key.0,
+            EffectReq::FsListDir(fl) => &fl.key.0,
+        }
+    }
+}
+
 pub trait EffectOutExpect: Sized {
     fn expect_from(out: EffectOut) -> anyhow::Result<Self>;
 }
@@ -61,7 +87,10 @@ impl EffectOutExpect for HttpResponse {
     fn expect_from(out: EffectOut) -> anyhow::Result<Self> {
         match out {
             EffectOut::Http(v) => Ok(v),
-            EffectOut::ProcLines(_) | EffectOut::FsBytes(_) | EffectOut::DirEntries(_) => {
+            EffectOut::ProcLines(_)
+            | EffectOut::ProcOutput(_)
+            | EffectOut::FsBytes(_)
+            | EffectOut::DirEntries(_) => {
                 anyhow::bail!("unexpected effect output")
             }
         }
@@ -72,7 +101,10 @@ impl EffectOutExpect for Vec<String> {
     fn expect_from(out: EffectOut) -> anyhow::Result<Self> {
         match out {
             EffectOut::ProcLines(v) => Ok(v),
-            EffectOut::Http(_) | EffectOut::FsBytes(_) | EffectOut::DirEntries(_) => {
+            EffectOut::Http(_)
+            | EffectOut::ProcOutput(_)
+            | EffectOut::FsBytes(_)
+            | EffectOut::DirEntries(_) => {
                 anyhow::bail!("unexpected effect output")
             }
         }
@@ -83,7 +115,10 @@ impl EffectOutExpect for bytes::Bytes {
     fn expect_from(out: EffectOut) -> anyhow::Result<Self> {
         match out {
             EffectOut::FsBytes(v) => Ok(v),
-            EffectOut::Http(_) | EffectOut::ProcLines(_) | EffectOut::DirEntries(_) => {
+            EffectOut::Http(_)
+            | EffectOut::ProcLines(_)
+            | EffectOut::ProcOutput(_)
+            | EffectOut::DirEntries(_) => {
                 anyhow::bail!("unexpected effect output")
             }
         }
@@ -94,7 +129,24 @@ impl EffectOutExpect for DirEntries {
     fn expect_from(out: EffectOut) -> anyhow::Result<Self> {
         match out {
             EffectOut::DirEntries(v) => Ok(v),
-            EffectOut::Http(_) | EffectOut::ProcLines(_) | EffectOut::FsBytes(_) => {
+            EffectOut::Http(_)
+            | EffectOut::ProcLines(_)
+            | EffectOut::ProcOutput(_)
+            | EffectOut::FsBytes(_) => {
+                anyhow::bail!("unexpected effect output")
+            }
+        }
+    }
+}
+
+impl EffectOutExpect for ProcOutput {
+    fn expect_from(out: EffectOut) -> anyhow::Result<Self> {
+        match out {
+            EffectOut::ProcOutput(v) => Ok(v),
+            EffectOut::Http(_)
+            | EffectOut::ProcLines(_)
+            | EffectOut::FsBytes(_)
+            | EffectOut::DirEntries(_) => {
                 anyhow::bail!("unexpected effect output")
             }
         }
@@ -145,11 +197,35 @@ impl ProcKey {
     }
 }
 
+/// A long-running command whose stdout lines are drained on each poll. The
+/// limits are enforced as the command runs, polled or not; the next poll
+/// reports the kill and the one after restarts it.
 #[derive(Debug, Clone)]
 pub struct ProcBatch {
     pub key: ProcKey,
     pub cmd: Vec<String>,
     pub max_lines: usize,
+    /// Kill the command once it has run this long.
+    pub max_runtime: Option<Duration>,
+    /// Kill the command if it prints nothing for this long.
+    pub max_idle: Option<Duration>,
+}
+
+/// A one-shot command run to completion, killed if it outlives `timeout`.
+#[derive(Debug, Clone)]
+pub struct ProcRun {
+    pub key: ProcKey,
+    pub cmd: Vec<String>,
+    /// Set in the child's environment on top of ours.
+    pub env: Vec<(String, String)>,
+    pub timeout: Duration,
+    pub cache_fresh_for: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcOutput {
+    pub success: bool,
+    pub stdout: bytes::Bytes,
 }
 
 #[derive(Debug, Clone)]
@@ -157,9 +233,42 @@ pub struct DirEntries(pub Vec<String>);
 
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
+    pub status: u16,
+    /// Response headers with lowercased names; repeated headers keep the last
+    /// value and non-UTF-8 values are dropped.
+    pub headers: HashMap<String, String>,
     pub body: bytes::Bytes,
 }
 
+/// How long the server asked us to wait: `Retry-After` (seconds or an HTTP
+/// date), or `X-RateLimit-Reset` (seconds, or a Unix time) once
+/// `X-RateLimit-Remaining` reaches 0.
+pub fn retry_after(headers: &HashMap<String, String>) -> Option<Duration> {
+    let now = chrono::Utc::now();
+    if let Some(v) = headers.get("retry-after").map(|v| v.trim()) {
+        if let Ok(secs) = v.parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+        let at = chrono::DateTime::parse_from_rfc2822(v).ok()?;
+        return Some((at.to_utc() - now).to_std().unwrap_or_default());
+    }
+    if headers.get("x-ratelimit-remaining").map(|v| v.trim()) != Some("0") {
+        return None;
+    }
+    let reset = headers
+        .get("x-ratelimit-reset")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    // Values past 2001 are timestamps rather than delays.
+    let epoch_threshold = 1_000_000_000;
+    if reset < epoch_threshold {
+        return Some(Duration::from_secs(reset));
+    }
+    let at = chrono::DateTime::from_timestamp(i64::try_from(reset).ok()?, 0)?;
+    Some((at - now).to_std().unwrap_or_default())
+}
+
 #[derive(Debug)]
 struct HttpCacheEntry {
     fresh_until: Instant,
@@ -176,9 +285,49 @@ pub struct EffectEngine {
     http: Mutex<HttpState>,
     clients: crate::machine::http::ClientPool,
     procs: Mutex<HashMap<ProcKey, ProcState>>,
+    runs: Mutex<HashMap<ProcKey, ProcRunCacheEntry>>,
     fs: Mutex<HashMap<FsKey, FsCacheEntry>>,
     dirs: Mutex<HashMap<DirKey, DirCacheEntry>>,
-    http_log: Option<std::sync::Mutex<std::fs::File>>,
+    http_log: Option<std::sync::Mutex<HttpLog>>,
+    rate_overrides: HashMap<String, crate::machine::http::RateLimitSpec>,
+}
+
+// Rotate `http.log` past this size, keeping `http.log.1` .. `http.log.N`.
+const HTTP_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const HTTP_LOG_GENERATIONS: usize = 2;
+
+#[derive(Debug)]
+struct HttpLog {
+    path: std::path::PathBuf,
+    file: std::fs::File,
+}
+
+impl HttpLog {
+    fn open(path: std::path::PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path, file })
+    }
+
+    fn generation_path(&self, n: usize) -> std::path::PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        name.into()
+    }
+
+    fn rotate_if_needed(&mut self) -> std::io::Result<()> {
+        if self.file.metadata()?.len() < HTTP_LOG_MAX_BYTES {
+            return Ok(());
+        }
+        for n in (1..HTTP_LOG_GENERATIONS).rev() {
+            let from = self.generation_path(n);
+            if from.exists() {
+                std::fs::rename(from, self.generation_path(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.generation_path(1))?;
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -187,6 +336,13 @@ struct FsCacheEntry {
     bytes: bytes::Bytes,
 }
 
+#[derive(Debug, Clone)]
+struct ProcRunCacheEntry {
+    // Freshness is judged per request, so a caller can force a rerun.
+    ran_at: Instant,
+    output: ProcOutput,
+}
+
 #[derive(Debug, Clone)]
 struct DirCacheEntry {
     fresh_until: Instant,
@@ -195,41 +351,73 @@ struct DirCacheEntry {
 
 #[derive(Debug)]
 struct ProcState {
-    _child: tokio::process::Child,
-    rx: tokio::sync::mpsc::UnboundedReceiver<String>,
+    /// Stdout lines, ending with the exceeded limit's name if the reader
+    /// killed the command.
+    rx: tokio::sync::mpsc::UnboundedReceiver<Result<String, &'static str>>,
+}
+
+impl Effects for EffectEngine {
+    async fn run(&self, req: EffectReq) -> Result<EffectOut, TransportError> {
+        EffectEngine::run(self, req).await
+    }
+}
+
+/// Canned outputs by request key, for driving machine units without I/O.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockEffects {
+    outs: HashMap<String, EffectOut>,
+}
+
+#[cfg(test)]
+impl MockEffects {
+    #[must_use]
+    pub fn with(mut self, key: impl Into<String>, out: EffectOut) -> Self {
+        self.outs.insert(key.into(), out);
+        self
+    }
+}
+
+#[cfg(test)]
+impl Effects for MockEffects {
+    async fn run(&self, req: EffectReq) -> Result<EffectOut, TransportError> {
+        self.outs
+            .get(req.key())
+            .cloned()
+            .ok_or_else(|| TransportError::Other(format!("no canned output for {}", req.key())))
+    }
 }
 
 impl EffectEngine {
     #[must_use]
-    pub fn new() -> Arc<Self> {
+    pub fn new(rate_overrides: HashMap<String, crate::machine::http::RateLimitSpec>) -> Arc<Self> {
         Arc::new(Self {
             http: Mutex::default(),
             clients: crate::machine::http::ClientPool::default(),
             procs: Mutex::default(),
+            runs: Mutex::default(),
             fs: Mutex::default(),
             dirs: Mutex::default(),
             http_log: Self::open_http_log(),
+            rate_overrides,
         })
     }
 
-    fn open_http_log() -> Option<std::sync::Mutex<std::fs::File>> {
+    fn open_http_log() -> Option<std::sync::Mutex<HttpLog>> {
         let bd = xdg::BaseDirectories::with_prefix("empty-status");
         let log_dir = bd.get_state_home()?;
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_dir.join("http.log"))
-            .ok()?;
-        Some(std::sync::Mutex::new(file))
+        let log = HttpLog::open(log_dir.join("http.log")).ok()?;
+        Some(std::sync::Mutex::new(log))
     }
 
     fn log_http(&self, line: &str) {
         let Some(log) = self.http_log.as_ref() else {
             return;
         };
-        if let Ok(mut file) = log.lock() {
+        if let Ok(mut log) = log.lock() {
+            let _ = log.rotate_if_needed();
             let ts = chrono::Utc::now().to_rfc3339();
-            let _ = writeln!(file, "{ts} {line}");
+            let _ = writeln!(log.file, "{ts} {line}");
         }
     }
 
@@ -237,6 +425,7 @@ impl EffectEngine {
         match req {
             EffectReq::HttpGet(get) => self.http_get(get).await.map(EffectOut::Http),
             EffectReq::ProcBatch(pb) => self.proc_batch(pb).await.map(EffectOut::ProcLines),
+            EffectReq::ProcRun(pr) => self.proc_run(pr).await.map(EffectOut::ProcOutput),
             EffectReq::FsRead(fr) => self.fs_read(fr).await.map(EffectOut::FsBytes),
             EffectReq::FsListDir(fr) => self.fs_list_dir(fr).await.map(EffectOut::DirEntries),
         }
@@ -256,7 +445,7 @@ impl EffectEngine {
         let bytes = tokio::fs::read(fr.path)
             .await
             .map(bytes::Bytes::from)
-            .map_err(|e| TransportError::Transport(e.to_string()))?;
+            .map_err(|e| TransportError::Io(e.to_string()))?;
 
         let mut cache = self.fs.lock().await;
         cache.insert(
@@ -284,11 +473,11 @@ impl EffectEngine {
         let mut out = Vec::new();
         let mut dir = tokio::fs::read_dir(&fr.path)
             .await
-            .map_err(|e| TransportError::Transport(e.to_string()))?;
+            .map_err(|e| TransportError::Io(e.to_string()))?;
         while let Some(entry) = dir
             .next_entry()
             .await
-            .map_err(|e| TransportError::Transport(e.to_string()))?
+            .map_err(|e| TransportError::Io(e.to_string()))?
         {
             out.push(entry.file_name().to_string_lossy().to_string());
         }
@@ -311,47 +500,75 @@ impl EffectEngine {
             let mut it = pb.cmd.iter();
             let exe = it
                 .next()
-                .ok_or_else(|| TransportError::Transport("empty command".into()))?;
+                .ok_or_else(|| TransportError::Other("empty command".into()))?;
             let mut cmd = tokio::process::Command::new(exe);
             for arg in it {
                 cmd.arg(arg);
             }
             cmd.stdin(std::process::Stdio::null())
                 .stderr(std::process::Stdio::null())
-                .stdout(std::process::Stdio::piped());
+                .stdout(std::process::Stdio::piped())
+                .kill_on_drop(true);
 
-            let mut child = cmd
-                .spawn()
-                .map_err(|e| TransportError::Transport(e.to_string()))?;
+            let mut child = cmd.spawn().map_err(|e| TransportError::Io(e.to_string()))?;
             let stdout = child
                 .stdout
                 .take()
-                .ok_or_else(|| TransportError::Transport("missing stdout".into()))?;
+                .ok_or_else(|| TransportError::Other("missing stdout".into()))?;
 
             let reader = tokio::io::BufReader::new(stdout);
             let mut lines = reader.lines();
             let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
+            // The reader owns the child, so a command that hangs is killed
+            // even while its unit is not polling. Dropping `rx` ends it too.
+            let deadline = pb.max_runtime.map(|max| tokio::time::Instant::now() + max);
+            let max_idle = pb.max_idle;
+            let cmd_line = pb.cmd.join(" ");
             tokio::spawn(async move {
-                while let Ok(Some(line)) = lines.next_line().await {
-                    let _ = tx.send(line);
+                let exceeded = loop {
+                    tokio::select! {
+                        line = lines.next_line() => {
+                            let Ok(Some(line)) = line else { break None };
+                            if tx.send(Ok(line)).is_err() {
+                                break None;
+                            }
+                        }
+                        () = tokio::time::sleep(max_idle.unwrap_or_default()),
+                            if max_idle.is_some() => break Some("max_idle"),
+                        () = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)),
+                            if deadline.is_some() => break Some("max_runtime"),
+                        () = tx.closed() => break None,
+                    }
+                };
+                if let Some(limit) = exceeded {
+                    let _ = child.kill().await;
+                    tracing::warn!("killed `{cmd_line}`: exceeded {limit}");
+                    let _ = tx.send(Err(limit));
                 }
             });
 
-            procs.insert(pb.key.clone(), ProcState { _child: child, rx });
+            procs.insert(pb.key.clone(), ProcState { rx });
         }
 
         let st = procs
             .get_mut(&pb.key)
-            .ok_or_else(|| TransportError::Transport("proc missing".into()))?;
+            .ok_or_else(|| TransportError::Other("proc missing".into()))?;
 
         let mut out = Vec::new();
         for _ in 0..pb.max_lines {
             match st.rx.try_recv() {
-                Ok(line) => out.push(line),
+                Ok(Ok(line)) => out.push(line),
+                Ok(Err(limit)) => {
+                    procs.remove(&pb.key);
+                    return Err(TransportError::Other(format!(
+                        "{} killed: exceeded {limit}",
+                        pb.cmd.first().map_or("command", String::as_str)
+                    )));
+                }
                 Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
                 Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
-                    return Err(TransportError::Transport("proc disconnected".into()))
+                    return Err(TransportError::Other("proc disconnected".into()))
                 }
             }
         }
@@ -359,6 +576,52 @@ impl EffectEngine {
         Ok(out)
     }
 
+    async fn proc_run(&self, pr: ProcRun) -> Result<ProcOutput, TransportError> {
+        let now = Instant::now();
+        {
+            let cache = self.runs.lock().await;
+            if let Some(ent) = cache.get(&pr.key) {
+                if now < ent.ran_at + pr.cache_fresh_for {
+                    return Ok(ent.output.clone());
+                }
+            }
+        }
+
+        let mut it = pr.cmd.iter();
+        let exe = it
+            .next()
+            .ok_or_else(|| TransportError::Other("empty command".into()))?;
+        let mut cmd = tokio::process::Command::new(exe);
+        for arg in it {
+            cmd.arg(arg);
+        }
+        cmd.envs(pr.env.iter().map(|(k, v)| (k, v)));
+        cmd.stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        let child = cmd.spawn().map_err(|e| TransportError::Io(e.to_string()))?;
+        let out = tokio::time::timeout(pr.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| TransportError::Timeout)?
+            .map_err(|e| TransportError::Io(e.to_string()))?;
+        let output = ProcOutput {
+            success: out.status.success(),
+            stdout: bytes::Bytes::from(out.stdout),
+        };
+
+        let mut cache = self.runs.lock().await;
+        cache.insert(
+            pr.key,
+            ProcRunCacheEntry {
+                ran_at: now,
+                output: output.clone(),
+            },
+        );
+        Ok(output)
+    }
+
     async fn http_get(&self, get: HttpGet) -> Result<HttpResponse, TransportError> {
         let now = Instant::now();
 
@@ -371,19 +634,36 @@ impl EffectEngine {
             }
         }
 
+        let host = get.url.host_str().unwrap_or_default();
+        let rate = self
+            .rate_overrides
+            .get(host)
+            .copied()
+            .unwrap_or(get.policy.rate);
         let client = self
             .clients
-            .client_for_host(get.url.host_str().unwrap_or_default(), get.policy.rate)
-            .map_err(|e| TransportError::Transport(e.to_string()))?;
+            .client_for_host(host, rate)
+            .map_err(|e| TransportError::Other(e.to_string()))?;
 
         let url_str = get.url.to_string();
         self.log_http(&format!("REQ GET {url_str}"));
         let start = Instant::now();
+        let host = host.to_string();
         let res = client.get(get.url).send().await.map_err(|e| {
             self.log_http(&format!("ERR {e} {url_str}"));
-            TransportError::Transport(e.to_string())
+            match e {
+                reqwest_middleware::Error::Reqwest(e) => classify_reqwest_error(&host, &e),
+                reqwest_middleware::Error::Middleware(e) => TransportError::Other(e.to_string()),
+            }
         })?;
         let status = res.status().as_u16();
+        let headers: HashMap<String, String> = res
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
         if !(200..300).contains(&status) {
             self.log_http(&format!(
                 "RES {} {}ms {}",
@@ -391,11 +671,14 @@ impl EffectEngine {
                 start.elapsed().as_millis(),
                 url_str
             ));
-            return Err(TransportError::Http { status });
+            return Err(TransportError::Http {
+                status,
+                retry_after: retry_after(&headers),
+            });
         }
         let body = res.bytes().await.map_err(|e| {
             self.log_http(&format!("ERR {e} {url_str}"));
-            TransportError::Transport(e.to_string())
+            classify_reqwest_error(&host, &e)
         })?;
         self.log_http(&format!(
             "RES {} {}ms {}",
@@ -403,7 +686,11 @@ impl EffectEngine {
             start.elapsed().as_millis(),
             url_str
         ));
-        let response = HttpResponse { body };
+        let response = HttpResponse {
+            status,
+            headers,
+            body,
+        };
 
         let mut st = self.http.lock().await;
         st.cache.insert(
@@ -416,3 +703,152 @@ impl EffectEngine {
         Ok(response)
     }
 }
+
+/// Sorts a failed request by cause. reqwest reports DNS and TLS failures as
+/// generic connect errors, so those are told apart by the types in the source
+/// chain: our resolver's [`DnsError`](crate::machine::http::DnsError) and
+/// native-tls's error.
+fn classify_reqwest_error(host: &str, e: &reqwest::Error) -> TransportError {
+    if e.is_timeout() {
+        return TransportError::Timeout;
+    }
+    if e.is_decode() || e.is_body() {
+        return TransportError::Decode(e.to_string());
+    }
+    if !e.is_connect() {
+        return TransportError::Other(e.to_string());
+    }
+    let mut chain = std::iter::successors(Some(e as &(dyn std::error::Error + 'static)), |e| {
+        e.source()
+    });
+    let host = host.to_string();
+    match chain.find_map(|e| {
+        if e.is::<crate::machine::http::DnsError>() {
+            Some(TransportError::Dns { host: host.clone() })
+        } else if e.is::<native_tls::Error>() {
+            Some(TransportError::Tls { host: host.clone() })
+        } else {
+            None
+        }
+    }) {
+        Some(err) => err,
+        None => TransportError::Connect { host },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        classify_reqwest_error, retry_after, EffectEngine, EffectOut, EffectReq, ProcBatch, ProcKey,
+    };
+    use crate::machine::http::DnsError;
+    use crate::machine::types::TransportError;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[test]
+    fn retry_after_reads_rate_limit_headers() {
+        let headers = |pairs: &[(&str, String)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), v.clone()))
+                .collect()
+        };
+        let secs = |d: Option<Duration>| d.map(|d| d.as_secs());
+        assert_eq!(
+            secs(retry_after(&headers(&[("retry-after", "120".into())]))),
+            Some(120)
+        );
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let wait = secs(retry_after(&headers(&[("retry-after", soon)]))).unwrap();
+        assert!((88..=90).contains(&wait), "{wait}");
+        // A reset alone is informational until the quota runs out.
+        let reset = ("x-ratelimit-reset", "30".to_string());
+        assert_eq!(retry_after(&headers(std::slice::from_ref(&reset))), None);
+        assert_eq!(
+            secs(retry_after(&headers(&[
+                reset,
+                ("x-ratelimit-remaining", "0".into())
+            ]))),
+            Some(30)
+        );
+        assert_eq!(retry_after(&HashMap::new()), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a loopback TCP stack"]
+    async fn refused_connection_is_classified() {
+        // Nothing listens on port 1; the refusal never leaves the host.
+        let err = reqwest::get("http://127.0.0.1:1/").await.unwrap_err();
+        let err = classify_reqwest_error("127.0.0.1", &err);
+        assert_eq!(
+            err,
+            TransportError::Connect {
+                host: "127.0.0.1".into()
+            }
+        );
+        assert!(!err.is_persistent());
+        assert_eq!(err.to_string(), "cannot connect to 127.0.0.1");
+    }
+
+    /// Fails every lookup without touching the network.
+    struct NoDns;
+
+    impl reqwest::dns::Resolve for NoDns {
+        fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+            let err = DnsError {
+                host: name.as_str().to_string(),
+                source: std::io::Error::other("no such host"),
+            };
+            Box::pin(async move { Err(Box::new(err) as _) })
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_lookup_is_classified_by_type() {
+        let client = reqwest::Client::builder()
+            .dns_resolver(std::sync::Arc::new(NoDns))
+            .no_proxy()
+            .build()
+            .unwrap();
+        let err = client.get("http://ssl.example/").send().await.unwrap_err();
+        let err = classify_reqwest_error("ssl.example", &err);
+        assert_eq!(
+            err,
+            TransportError::Dns {
+                host: "ssl.example".into()
+            }
+        );
+        assert!(err.is_persistent());
+    }
+
+    #[tokio::test]
+    async fn silent_proc_batch_is_killed_and_restarted() {
+        let effects = EffectEngine::new(std::collections::HashMap::new());
+        let req = || {
+            EffectReq::ProcBatch(ProcBatch {
+                key: ProcKey::new("test:idle"),
+                cmd: vec!["sh".into(), "-c".into(), "echo $$; sleep 10".into()],
+                max_lines: 8,
+                max_runtime: None,
+                max_idle: Some(Duration::from_millis(200)),
+            })
+        };
+        let _ = effects.run(req()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let Ok(EffectOut::ProcLines(pid)) = effects.run(req()).await else {
+            panic!("no pid");
+        };
+        // Killed without another poll.
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(!std::path::Path::new(&format!("/proc/{}", pid[0])).exists());
+        let err = effects.run(req()).await.err().map(|e| e.to_string());
+        assert_eq!(err.as_deref(), Some("sh killed: exceeded max_idle"));
+        // The next poll starts it afresh.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let Ok(EffectOut::ProcLines(lines)) = effects.run(req()).await else {
+            panic!("restart failed");
+        };
+        assert!(lines.len() <= 1);
+    }
+}
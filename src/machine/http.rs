@@ -11,7 +11,13 @@ pub struct RateLimitSpec {
 }
 
 pub fn make_http_client(host: &str, spec: RateLimitSpec) -> anyhow::Result<ClientWithMiddleware> {
-    let client = reqwest::Client::builder().build()?;
+    // Bodies are decompressed transparently, so cached responses are stored decoded.
+    let client = reqwest::Client::builder()
+        .gzip(true)
+        .brotli(true)
+        .deflate(true)
+        .dns_resolver(std::sync::Arc::new(Resolver))
+        .build()?;
 
     let mw = RateLimitMiddleware::builder()
         .host(host, |h| {
@@ -25,6 +31,42 @@ pub fn make_http_client(host: &str, spec: RateLimitSpec) -> anyhow::Result<Clien
     Ok(ClientBuilder::new(client).with(mw).build())
 }
 
+/// A failed name lookup, found in a request error's source chain.
+#[derive(Debug)]
+pub struct DnsError {
+    pub(crate) host: String,
+    pub(crate) source: std::io::Error,
+}
+
+impl std::fmt::Display for DnsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot resolve {}: {}", self.host, self.source)
+    }
+}
+
+impl std::error::Error for DnsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The system resolver, like reqwest's default, but failing with a
+/// [`DnsError`] so DNS failures can be told apart by type.
+struct Resolver;
+
+impl reqwest::dns::Resolve for Resolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let looked_up = tokio::net::lookup_host((host.clone(), 0)).await;
+            match looked_up {
+                Ok(addrs) => Ok(Box::new(addrs) as reqwest::dns::Addrs),
+                Err(source) => Err(Box::new(DnsError { host, source }) as _),
+            }
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ClientPool {
     inner: Mutex<HashMap<String, ClientWithMiddleware>>,
@@ -0,0 +1,61 @@
+//! node_exporter textfile export of the units' raw readings (`View::metrics`),
+//! enabled by `[metrics] path`.
+
+use crate::machine::types::View;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const METRIC_PREFIX: &str = "empty_status_";
+
+/// Prometheus text format: one gauge family per metric name, each sample
+/// labeled with its unit's i3bar name (`Cpu::0`).
+pub fn render(units: &[(&str, &View)]) -> String {
+    let mut families: BTreeMap<&str, Vec<(&str, f64)>> = BTreeMap::new();
+    for (unit, view) in units {
+        for (name, value) in &view.metrics {
+            families.entry(name).or_default().push((unit, *value));
+        }
+    }
+    let mut out = String::new();
+    for (name, samples) in families {
+        out.push_str(&format!("# TYPE {METRIC_PREFIX}{name} gauge\n"));
+        for (unit, value) in samples {
+            out.push_str(&format!(
+                "{METRIC_PREFIX}{name}{{unit=\"{unit}\"}} {value}\n"
+            ));
+        }
+    }
+    out
+}
+
+/// Replaces `path` atomically, so the collector never reads a partial file.
+pub fn write(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::machine::types::View;
+    use crate::render::markup::Markup;
+
+    #[test]
+    fn samples_are_grouped_by_family() {
+        let cpu = View::ok(Markup::text("cpu"))
+            .with_metric("cpu_usage_percent", 12.5)
+            .with_metric("cpu_temp_celsius", 48.0);
+        let other = View::ok(Markup::text("cpu")).with_metric("cpu_usage_percent", 3.0);
+        let plain = View::ok(Markup::text("12:00"));
+        let text = super::render(&[("Cpu::0", &cpu), ("Time::1", &plain), ("Cpu::2", &other)]);
+        assert_eq!(
+            text,
+            "# TYPE empty_status_cpu_temp_celsius gauge\n\
+             empty_status_cpu_temp_celsius{unit=\"Cpu::0\"} 48\n\
+             # TYPE empty_status_cpu_usage_percent gauge\n\
+             empty_status_cpu_usage_percent{unit=\"Cpu::0\"} 12.5\n\
+             empty_status_cpu_usage_percent{unit=\"Cpu::2\"} 3\n"
+        );
+    }
+}
@@ -1,5 +1,7 @@
+pub mod dump;
 pub mod effects;
 pub mod http;
+pub mod metrics;
 pub mod runtime;
 pub mod types;
 pub mod units;
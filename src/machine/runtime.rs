@@ -1,58 +1,231 @@
-use crate::config::GlobalConfig;
-use crate::core::{OutputChunk, RED, YELLOW};
+use crate::config::{
+    ClickModifier, DecorationCfg, FlushMode, GlobalConfig, MetricsConfig, PollMode, ThemeConfig,
+};
+use crate::core::OutputChunk;
+use crate::i3bar::{self, status_line, write_line, HEADER};
 use crate::machine::types::{
     Availability, Health, PollError, TransportError, UnitDecision, UnitMachine, View,
 };
+use crate::render::color::Srgb8;
+use crate::render::markup::Markup;
+use crate::render::pango::to_pango;
+use futures::StreamExt;
 use std::collections::HashMap;
-use std::io::{self, Write};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::sync::watch;
+use tracing::Instrument;
+
+/// Least wait before retrying after a persistent transport error (a DNS or TLS
+/// failure), however short the unit's poll interval.
+const PERSISTENT_ERROR_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Wait before restarting a crashed unit, doubling per crash up to the max.
+const MIN_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A unit's recent poll durations and timeouts, reported on each poll's
+/// debug event so a slow unit shows up in the log.
+#[derive(Debug, Default)]
+struct PollStats {
+    recent: std::collections::VecDeque<Duration>,
+    timeouts: u64,
+}
+
+impl PollStats {
+    /// Polls the percentile is taken over.
+    const WINDOW: usize = 64;
+
+    fn record(&mut self, elapsed: Duration, timed_out: bool) {
+        if self.recent.len() == Self::WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(elapsed);
+        self.timeouts += u64::from(timed_out);
+    }
+
+    /// Nearest-rank 95th percentile of the recent polls.
+    fn p95(&self) -> Duration {
+        let mut sorted: Vec<Duration> = self.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (sorted.len() * 95).div_ceil(100);
+        sorted
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// What asks an actor for a poll; its `PollMode` decides which are heeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollTrigger {
+    /// `poll_interval` elapsed.
+    Interval,
+    /// The unit returned `PollNow` from a tick or an event.
+    Unit,
+    /// The unit returned `PollNow` from a click.
+    Click,
+}
+
+fn mode_allows(mode: PollMode, trigger: PollTrigger) -> bool {
+    match (mode, trigger) {
+        (_, PollTrigger::Click) | (PollMode::Interval, _) => true,
+        (PollMode::OnClick, PollTrigger::Unit) => true,
+        (PollMode::OnClick | PollMode::Manual, _) => false,
+    }
+}
 
 pub struct MachineWrapper {
     pub i3_name: String,
     pub handle: usize,
+    pub decor: DecorationCfg,
+    /// Shown as `decor.collapsed_glyph` instead of its view; toggled by the output loop.
+    pub collapsed: bool,
     pub view_rx: watch::Receiver<View>,
+    /// The unit actor's clicks; the output loop routes each click here, or
+    /// keeps it for collapsing.
+    pub click_tx: broadcast::Sender<crate::core::ClickEvent>,
 }
 
-fn make_chunk(i3_name: &str, padding: i32, view: &View) -> OutputChunk {
-    let mut chunk = OutputChunk::new(i3_name, view.body.to_string());
-    let pad = " ".repeat(padding.max(0) as usize);
-    chunk.full_text = format!("{pad}{}{pad}", chunk.full_text);
-    match view.health {
-        Health::Ok => {}
-        Health::Degraded => chunk.border = YELLOW.to_string(),
-        Health::Error => chunk.border = RED.to_string(),
+impl MachineWrapper {
+    /// Collapse clicks toggle the glyph, and any click on a collapsed unit
+    /// only expands it: neither reaches the hidden unit. Other clicks go to the
+    /// actor. Returns whether the collapse state changed.
+    fn route_click(&mut self, click: crate::core::ClickEvent, modifier: ClickModifier) -> bool {
+        if self.collapsed || click.has_modifier(modifier) {
+            self.collapsed = !self.collapsed;
+            return true;
+        }
+        let _ = self.click_tx.send(click);
+        false
     }
-    chunk
+}
+
+/// One block per view segment: `body`, then each of `extra`. Blocks share the
+/// unit's name and carry their index as `instance` when there is more than one.
+/// The decoration prefix opens the first block and the suffix closes the last.
+/// The configured `label` replaces the unit's own on `body` and `short` only;
+/// `compact` strips labels everywhere. Colors resolve against `theme` here.
+fn make_chunks(
+    w: &MachineWrapper,
+    padding: i32,
+    view: &View,
+    theme: &ThemeConfig,
+) -> Vec<OutputChunk> {
+    let compact = w.decor.compact.unwrap_or(false);
+    let labeled = |m: &Markup| match &w.decor.label {
+        _ if compact => m.without_labels(),
+        Some(label) => m.relabeled(label),
+        None => m.clone(),
+    };
+    // A collapsed unit keeps a non-empty glyph: i3bar drops empty segments,
+    // which would leave nothing to click to restore it.
+    let segments: Vec<Markup> = if w.collapsed {
+        vec![Markup::text(w.decor.collapsed_glyph.as_str())]
+    } else {
+        let extra = view.extra.iter().map(|m| {
+            if compact {
+                m.without_labels()
+            } else {
+                m.clone()
+            }
+        });
+        std::iter::once(labeled(&view.body)).chain(extra).collect()
+    };
+    let last = segments.len() - 1;
+    let pad = " ".repeat(padding.max(0) as usize);
+    segments
+        .into_iter()
+        .enumerate()
+        .map(|(ix, segment)| {
+            let decorate = |inner: &Markup| {
+                let prefix = if ix == 0 { w.decor.prefix.as_str() } else { "" };
+                let suffix = if ix == last {
+                    w.decor.suffix.as_str()
+                } else {
+                    ""
+                };
+                Markup::text(prefix)
+                    .append(inner.clone())
+                    .append(Markup::text(suffix))
+            };
+            let body = if w.collapsed {
+                segment
+            } else {
+                decorate(&segment)
+            };
+            let body = to_pango(&body, &theme.palette);
+            let mut chunk = OutputChunk::new(&w.i3_name, format!("{pad}{body}{pad}"));
+            if last > 0 {
+                chunk.instance = Some(ix.to_string());
+            }
+            if !w.collapsed && ix == 0 {
+                chunk.short_text = view.short.as_ref().map(|short| {
+                    let short = to_pango(&decorate(&labeled(short)), &theme.palette);
+                    format!("{pad}{short}{pad}")
+                });
+            }
+            chunk.urgent = view.urgent || view.health == Health::Error;
+            chunk.background = view
+                .background
+                .map(|paint| paint.resolve(&theme.palette).to_hex());
+            chunk.color = w.decor.text_color.map(Srgb8::to_hex);
+            // The glyph stands in for the unit, not its state.
+            match view.health {
+                _ if w.collapsed => {}
+                Health::Ok => {}
+                Health::Degraded => chunk.border = theme.degraded.to_hex(),
+                Health::Error => chunk.border = theme.error.to_hex(),
+            }
+            chunk
+        })
+        .collect()
+}
+
+/// `init` followed by `validate`, the only way the runtime starts a unit.
+pub(crate) fn init_validated<M: UnitMachine>(
+    machine: &M,
+) -> anyhow::Result<(M::State, View, UnitDecision)> {
+    let (mut state, view, decision) = machine.init();
+    machine.validate(&mut state)?;
+    Ok((state, view, decision))
+}
+
+/// The view of a unit whose config `validate` rejected.
+pub(crate) fn render_config_error<M: UnitMachine>(machine: &M, err: &anyhow::Error) -> View {
+    let name = machine.name().to_ascii_lowercase();
+    View::error(
+        crate::render::markup::Markup::text(format!("{name}: {err}"))
+            .fg(crate::core::theme().error),
+    )
 }
 
 fn render_poll_error<M: UnitMachine>(machine: &M, err: &PollError<M::UnitError>) -> View {
     let name = machine.name().to_ascii_lowercase();
     let (health, body) = match err {
-        PollError::Transport(TransportError::Timeout) => (
-            Health::Error,
-            crate::render::markup::Markup::text(format!("{name}: timeout")).fg(crate::core::RED),
-        ),
-        PollError::Transport(TransportError::Http { status }) => (
-            Health::Error,
-            crate::render::markup::Markup::text(format!("{name}: HTTP {status}"))
-                .fg(crate::core::RED),
-        ),
-        PollError::Transport(TransportError::Transport(msg)) => (
+        PollError::Transport(t) => (
             Health::Error,
-            crate::render::markup::Markup::text(format!("{name}: {msg}")).fg(crate::core::RED),
+            crate::render::markup::Markup::text(format!("{name}: {t}"))
+                .fg(crate::core::theme().error),
         ),
         PollError::Unit(e) => (
             Health::Error,
             crate::render::markup::Markup::text(format!("{name}: "))
-                .fg(crate::core::RED)
-                .append(machine.render_unit_error(e).fg(crate::core::RED)),
+                .fg(crate::core::theme().error)
+                .append(machine.render_unit_error(e).fg(crate::core::theme().error)),
         ),
     };
 
-    View { body, health }
+    View {
+        body,
+        health,
+        short: None,
+        urgent: false,
+        background: None,
+        extra: Vec::new(),
+        metrics: Vec::new(),
+    }
 }
 
 fn render_availability<M: UnitMachine>(
@@ -60,191 +233,1226 @@ fn render_availability<M: UnitMachine>(
     availability: Availability<crate::render::markup::Markup, PollError<M::UnitError>>,
 ) -> View {
     match availability {
-        Availability::Loading => View {
-            body: crate::render::markup::Markup::text(format!(
+        Availability::Loading => View::degraded(
+            crate::render::markup::Markup::text(format!(
                 "{} loading",
                 machine.name().to_ascii_lowercase()
             ))
-            .fg(crate::core::VIOLET),
-            health: Health::Degraded,
-        },
+            .fg(crate::core::theme().loading),
+        ),
         Availability::Ready(body) => View::ok(body),
+        Availability::ReadyView(view) => view,
         Availability::Failed(err) => render_poll_error(machine, &err),
     }
 }
 
-fn i3bar_order(handles: &[usize]) -> Vec<usize> {
-    let mut out = handles.to_vec();
-    out.reverse();
-    out
+/// Runs `init` and a single poll outside the actor loop, for one-shot dumps.
+/// Returns the view the bar would show and the poll error, if any.
+pub async fn poll_once<M: UnitMachine>(
+    machine: &M,
+    effects: &impl crate::machine::effects::Effects,
+) -> (View, Option<String>) {
+    let (mut state, _view0, _decision0) = match init_validated(machine) {
+        Ok(started) => started,
+        Err(e) => return (render_config_error(machine, &e), Some(e.to_string())),
+    };
+    let out = match tokio::time::timeout(Duration::from_secs(10), machine.poll(effects, &mut state))
+        .await
+    {
+        Ok(out) => out,
+        Err(_) => Err(PollError::Transport(TransportError::Timeout)),
+    };
+    match out {
+        Ok(v) => {
+            let (availability, _decision) = machine.on_poll_ok(&mut state, v);
+            (render_availability(machine, availability), None)
+        }
+        Err(e) => {
+            let msg = match &e {
+                PollError::Transport(t) => t.to_string(),
+                PollError::Unit(u) => u.to_string(),
+            };
+            (render_poll_error(machine, &e), Some(msg))
+        }
+    }
+}
+
+/// The config sections the output loop renders with.
+pub struct BarConfig {
+    pub global: GlobalConfig,
+    pub theme: ThemeConfig,
+    pub metrics: MetricsConfig,
 }
 
 pub async fn run_empty_status_machines(
+    wrappers: Vec<MachineWrapper>,
+    bar: BarConfig,
+    click_tx: broadcast::Sender<crate::core::ClickEvent>,
+    paused_rx: watch::Receiver<bool>,
+) {
+    let mut sigterm =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).ok();
+    let mut sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt()).ok();
+    let shutdown = async move {
+        tokio::select! {
+            Some(()) = async { sigterm.as_mut()?.recv().await } => {}
+            Some(()) = async { sigint.as_mut()?.recv().await } => {}
+            else => std::future::pending().await,
+        }
+    };
+    run_output_loop(
+        wrappers,
+        bar,
+        click_tx.subscribe(),
+        paused_rx,
+        shutdown,
+        write_line,
+    )
+    .await;
+}
+
+/// The output loop behind [`run_empty_status_machines`]: every status line
+/// goes through `write`, and `shutdown` resolving ends the stream.
+async fn run_output_loop(
     mut wrappers: Vec<MachineWrapper>,
-    cfg: GlobalConfig,
-    _click_tx: broadcast::Sender<crate::core::ClickEvent>,
+    bar: BarConfig,
+    mut click_rx: broadcast::Receiver<crate::core::ClickEvent>,
+    mut paused_rx: watch::Receiver<bool>,
+    shutdown: impl std::future::Future<Output = ()>,
+    mut write: impl FnMut(&str),
 ) {
-    println!("{{\"version\":1,\"click_events\":true}}\n[");
+    let BarConfig {
+        global: cfg,
+        theme,
+        metrics,
+    } = bar;
+    write(HEADER);
 
-    let mut latest: HashMap<usize, OutputChunk> = HashMap::new();
+    let mut latest: HashMap<usize, Vec<OutputChunk>> = HashMap::new();
     for w in &wrappers {
         let view = w.view_rx.borrow().clone();
-        latest.insert(w.handle, make_chunk(&w.i3_name, cfg.padding, &view));
+        latest.insert(w.handle, make_chunks(w, cfg.padding, &view, &theme));
     }
 
     let handles: Vec<usize> = wrappers.iter().map(|w| w.handle).collect();
-    let handles = i3bar_order(&handles);
+    let handles = i3bar::order(&handles, cfg.order);
 
-    // Periodic output loop. Pure periodic: no reactive flush.
-    let mut interval = tokio::time::interval(Duration::from_secs_f64(cfg.min_polling_interval));
+    // Periodic mode writes every tick; reactive mode writes `min_interval`
+    // after the first display change since the last write, so a burst of
+    // changes is one write, or after the latency cap.
+    let min_interval = Duration::from_secs_f64(cfg.min_polling_interval);
+    let reactive = cfg.flush == FlushMode::Reactive;
+    let max_latency =
+        Duration::from_secs_f64(cfg.max_flush_latency_sec.max(cfg.min_polling_interval));
+    let mut interval = tokio::time::interval(min_interval);
     interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // Separate receivers so waiting on them does not borrow `wrappers`.
+    let mut wake_rxs: Vec<watch::Receiver<View>> =
+        wrappers.iter().map(|w| w.view_rx.clone()).collect();
+    // When the pending reactive write is due; `None` while nothing changed.
+    let mut flush_at: Option<tokio::time::Instant> = None;
 
     // Emit an initial line so i3bar has content immediately.
-    {
-        let mut chunks = Vec::with_capacity(handles.len());
-        for h in &handles {
-            if let Some(chunk) = latest.get(h) {
-                chunks.push(serde_json::to_string(chunk).unwrap_or_default());
-            }
-        }
-        let line = format!("[{}],\n", chunks.join(","));
-        let _ = io::stdout().write_all(line.as_bytes());
-        let _ = io::stdout().flush();
-    }
+    write(&status_line(&handles, &latest, false));
+    let mut last_write = tokio::time::Instant::now();
+
+    tokio::pin!(shutdown);
+    // Logs a failing export once, not on every write.
+    let mut metrics_failing = false;
 
     loop {
-        interval.tick().await;
+        // While i3bar has us stopped, write nothing until it resumes us.
+        let paused = *paused_rx.borrow();
+        tokio::select! {
+            _ = interval.tick(), if !reactive && !paused => {}
+            () = any_view_changed(&mut wake_rxs), if reactive && flush_at.is_none() => {
+                flush_at = Some(tokio::time::Instant::now() + min_interval);
+                continue;
+            }
+            Some(()) = async { tokio::time::sleep_until(flush_at?).await; Some(()) }, if !paused => {}
+            () = tokio::time::sleep_until(last_write + max_latency), if reactive && !paused => {}
+            Ok(()) = paused_rx.changed() => {
+                // Resuming falls through to an immediate write.
+                if *paused_rx.borrow_and_update() {
+                    continue;
+                }
+            }
+            Ok(click) = click_rx.recv() => {
+                let Some(w) = wrappers.iter_mut().find(|w| w.i3_name == click.name) else {
+                    continue;
+                };
+                if w.route_click(click, cfg.collapse_modifier) {
+                    let view = w.view_rx.borrow().clone();
+                    latest.insert(w.handle, make_chunks(w, cfg.padding, &view, &theme));
+                    // Periodic mode shows it on the next tick.
+                    if reactive {
+                        flush_at = flush_at.or(Some(tokio::time::Instant::now() + min_interval));
+                    }
+                }
+                continue;
+            }
+            () = &mut shutdown => break,
+        }
 
+        let mut changed = false;
         for w in &mut wrappers {
             if w.view_rx.has_changed().unwrap_or(false) {
                 let _ = w.view_rx.borrow_and_update();
                 let view = w.view_rx.borrow().clone();
-                latest.insert(w.handle, make_chunk(&w.i3_name, cfg.padding, &view));
+                latest.insert(w.handle, make_chunks(w, cfg.padding, &view, &theme));
+                changed = true;
             }
         }
+        if let (true, Some(path)) = (changed, &metrics.path) {
+            export_metrics(&wrappers, path, &mut metrics_failing);
+        }
 
-        let mut chunks = Vec::with_capacity(handles.len());
-        for h in &handles {
-            if let Some(chunk) = latest.get(h) {
-                chunks.push(serde_json::to_string(chunk).unwrap_or_default());
-            }
+        write(&status_line(&handles, &latest, false));
+        last_write = tokio::time::Instant::now();
+        // This write shows every change so far; only later ones need another.
+        flush_at = None;
+        for rx in &mut wake_rxs {
+            rx.borrow_and_update();
+        }
+    }
+
+    // Close the endless array so non-i3 consumers see well-formed JSON.
+    write(&status_line(&handles, &latest, true));
+}
+
+/// Rewrites the `[metrics]` textfile from every unit's current view.
+fn export_metrics(wrappers: &[MachineWrapper], path: &std::path::Path, failing: &mut bool) {
+    let views: Vec<(&str, View)> = wrappers
+        .iter()
+        .map(|w| (w.i3_name.as_str(), w.view_rx.borrow().clone()))
+        .collect();
+    let units: Vec<(&str, &View)> = views.iter().map(|(name, view)| (*name, view)).collect();
+    match crate::machine::metrics::write(path, &crate::machine::metrics::render(&units)) {
+        Ok(()) => *failing = false,
+        Err(e) if !*failing => {
+            tracing::warn!("writing metrics to {}: {e}", path.display());
+            *failing = true;
         }
-        let line = format!("[{}],\n", chunks.join(","));
-        let _ = io::stdout().write_all(line.as_bytes());
-        let _ = io::stdout().flush();
+        Err(_) => {}
     }
 }
 
-pub fn spawn_machine_actor<M: UnitMachine>(
+/// Resolves once any of `rxs` sees a new view. A unit whose actor is gone
+/// never wakes it.
+async fn any_view_changed(rxs: &mut [watch::Receiver<View>]) {
+    if rxs.is_empty() {
+        return std::future::pending().await;
+    }
+    let waits = rxs.iter_mut().map(|rx| {
+        Box::pin(async move {
+            if rx.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        })
+    });
+    futures::future::select_all(waits).await;
+}
+
+/// Bar-wide inputs every unit actor listens to.
+pub struct ActorInputs {
+    /// `true` while i3bar has the bar stopped.
+    pub paused_rx: watch::Receiver<bool>,
+}
+
+pub fn spawn_machine_actor<M: UnitMachine, E: crate::machine::effects::Effects + 'static>(
     machine: Arc<M>,
-    effects: std::sync::Arc<crate::machine::effects::EffectEngine>,
+    effects: Arc<E>,
     cfg: crate::config::SchedulingCfg,
+    mut decor: DecorationCfg,
     gcfg: GlobalConfig,
     handle: usize,
-    click_tx: &broadcast::Sender<crate::core::ClickEvent>,
+    inputs: ActorInputs,
 ) -> MachineWrapper {
+    let ActorInputs { paused_rx } = inputs;
     let i3_name = format!("{}::{}", machine.name(), handle);
-    let i3_name_task = i3_name.clone();
-    let (state0, view0, decision0) = machine.init();
+    decor.text_color = decor.text_color.or(gcfg.text_color);
+    decor.compact = decor.compact.or(Some(gcfg.compact));
+    let (start, view0) = match init_validated(&*machine) {
+        Ok((state, view, decision)) => (Some((state, decision)), view),
+        Err(e) => (None, render_config_error(&*machine, &e)),
+    };
 
     let (view_tx, view_rx) = watch::channel(view0);
-    let mut click_rx = click_tx.subscribe();
+    let (click_tx, click_rx) = broadcast::channel(16);
+    let ctx = Arc::new(ActorCtx {
+        machine,
+        effects,
+        cfg,
+        gcfg,
+        i3_name: i3_name.clone(),
+        view_tx,
+    });
 
+    // Supervisor: each run is its own task, so a panic in the unit surfaces
+    // through the `JoinHandle` instead of freezing the unit's last view.
+    // A rejected config leaves the unit idle: no actor runs at all.
     tokio::spawn(async move {
-        let mut state = state0;
+        let Some(start) = start else {
+            return;
+        };
+        let mut start = Some(start);
+        let mut restart_delay = MIN_RESTART_BACKOFF;
+        loop {
+            let started = tokio::time::Instant::now();
+            let run = tokio::spawn(run_actor(
+                ctx.clone(),
+                click_rx.resubscribe(),
+                paused_rx.clone(),
+                start.take(),
+            ));
+            let panic = match run.await {
+                Err(e) if e.is_panic() => e.into_panic(),
+                _ => return,
+            };
+            let name = ctx.machine.name().to_ascii_lowercase();
+            let msg = panic_message(panic.as_ref());
+            if !cfg.restart_on_crash {
+                tracing::error!("{} crashed: {msg}", ctx.i3_name);
+                let _ = ctx.view_tx.send(View::error(
+                    Markup::text(format!("{name} crashed")).fg(crate::core::theme().error),
+                ));
+                return;
+            }
+            // A run that stayed up a while starts the backoff over.
+            if started.elapsed() >= MAX_RESTART_BACKOFF {
+                restart_delay = MIN_RESTART_BACKOFF;
+            }
+            tracing::error!(
+                "{} crashed: {msg}; restarting in {restart_delay:?}",
+                ctx.i3_name
+            );
+            let _ = ctx.view_tx.send(View::error(
+                Markup::text(format!(
+                    "{name} crashed, restarting in {}s",
+                    restart_delay.as_secs()
+                ))
+                .fg(crate::core::theme().error),
+            ));
+            tokio::time::sleep(restart_delay).await;
+            restart_delay = (restart_delay * 2).min(MAX_RESTART_BACKOFF);
+        }
+    });
 
-        let poll_timeout = Duration::from_secs(10);
-        let poll_backoff =
-            Duration::from_secs_f64(cfg.poll_interval.max(gcfg.min_polling_interval));
-        let mut next_poll = tokio::time::Instant::now();
+    MachineWrapper {
+        i3_name,
+        handle,
+        collapsed: decor.start_collapsed,
+        decor,
+        view_rx,
+        click_tx,
+    }
+}
 
-        // Always poll immediately if init requested it.
-        if decision0 == UnitDecision::PollNow {
-            next_poll = tokio::time::Instant::now();
-        }
+/// What every run of a unit's actor shares; restarts reuse it.
+struct ActorCtx<M: UnitMachine, E> {
+    machine: Arc<M>,
+    effects: Arc<E>,
+    cfg: crate::config::SchedulingCfg,
+    gcfg: GlobalConfig,
+    i3_name: String,
+    view_tx: watch::Sender<View>,
+}
 
-        let poll_interval =
-            Duration::from_secs_f64(cfg.poll_interval.max(gcfg.min_polling_interval));
-        let tick_interval = Duration::from_secs_f64(gcfg.min_polling_interval);
+/// The text of a panic payload, for logs.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown panic"
+    }
+}
 
-        let mut poll_tick = tokio::time::interval(poll_interval);
-        poll_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-        poll_tick.tick().await;
+/// One run of a unit's event loop. `start` is the state from the first
+/// `init`; restarts pass `None` and re-initialize here, so a panicking
+/// `init` is supervised too. A restart that fails `validate` ends the run.
+async fn run_actor<M: UnitMachine, E: crate::machine::effects::Effects>(
+    ctx: Arc<ActorCtx<M, E>>,
+    mut click_rx: broadcast::Receiver<crate::core::ClickEvent>,
+    mut paused_rx: watch::Receiver<bool>,
+    start: Option<(M::State, UnitDecision)>,
+) {
+    let (mut state, decision0) = match start {
+        Some(start) => start,
+        None => match init_validated(&*ctx.machine) {
+            Ok((state, view, decision)) => {
+                let _ = ctx.view_tx.send(view);
+                (state, decision)
+            }
+            Err(e) => {
+                let _ = ctx.view_tx.send(render_config_error(&*ctx.machine, &e));
+                return;
+            }
+        },
+    };
 
-        let mut tick = tokio::time::interval(tick_interval);
-        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-        tick.tick().await;
+    let poll_timeout = Duration::from_secs(10);
+    let poll_interval_sec = ctx.cfg.poll_interval.max(ctx.gcfg.min_polling_interval);
+    let poll_backoff = Duration::from_secs_f64(poll_interval_sec);
+    let mut next_poll = tokio::time::Instant::now();
 
-        let mut pending_click: Option<crate::core::ClickEvent> = None;
+    // Always poll immediately if init requested it.
+    if decision0 == UnitDecision::PollNow {
+        next_poll = tokio::time::Instant::now();
+    }
 
-        loop {
-            tokio::select! {
-                _ = tick.tick() => {
-                    let (maybe_view, decision) = machine.on_tick(&mut state);
-                    if let Some(view) = maybe_view {
-                        let _ = view_tx.send(view);
-                    }
-                    if decision == UnitDecision::PollNow {
-                        next_poll = tokio::time::Instant::now();
-                    }
+    let poll_interval = Duration::from_secs_f64(poll_interval_sec);
+    let tick_interval = Duration::from_secs_f64(ctx.gcfg.min_polling_interval);
+
+    let mut poll_tick = tokio::time::interval(poll_interval);
+    poll_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    poll_tick.tick().await;
+
+    let mut tick = tokio::time::interval(tick_interval);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    tick.tick().await;
+
+    let mut pending_click: Option<crate::core::ClickEvent> = None;
+
+    // Clicks re-render from `on_click` at once, or re-show the last polled
+    // view when it has none, but their polls wait out the cooldown since the
+    // last poll. A failed poll's error stays up until the next poll rather
+    // than being covered by a view from older data.
+    let click_cooldown = Duration::from_secs_f64(ctx.cfg.click_poll_cooldown_sec.max(0.0));
+    let mut last_poll: Option<tokio::time::Instant> = None;
+    let mut last_poll_failed = false;
+    let mut last_polled: Option<View> = None;
+    let mut poll_stats = PollStats::default();
+    let click_poll_at = |last_poll: Option<tokio::time::Instant>| {
+        let now = tokio::time::Instant::now();
+        last_poll.map_or(now, |t| (t + click_cooldown).max(now))
+    };
+
+    // Outside `interval` mode, or for event-driven units, nothing re-arms
+    // `next_poll` on its own.
+    let auto_poll = mode_allows(ctx.cfg.mode, PollTrigger::Interval) && ctx.machine.wants_polling();
+    let mut events = ctx.machine.subscribe(&mut state);
+    let never = || tokio::time::Instant::now() + Duration::from_secs(365 * 24 * 60 * 60);
+
+    loop {
+        // Stopped by i3bar: no ticks or polls until resumed.
+        let paused = *paused_rx.borrow();
+        tokio::select! {
+            Ok(()) = paused_rx.changed() => {
+                if !*paused_rx.borrow_and_update() && auto_poll {
+                    next_poll = tokio::time::Instant::now();
                 }
-                _ = poll_tick.tick() => {
+            }
+            _ = tick.tick(), if !paused => {
+                let (maybe_view, decision) = ctx.machine.on_tick(&mut state);
+                if let Some(view) = maybe_view {
+                    let _ = ctx.view_tx.send(view);
+                }
+                if decision == UnitDecision::PollNow && mode_allows(ctx.cfg.mode, PollTrigger::Unit) {
                     next_poll = tokio::time::Instant::now();
                 }
-                Ok(click) = click_rx.recv() => {
-                    if click.name != i3_name_task {
-                        continue;
-                    }
-                    if pending_click.is_some() {
-                        pending_click = Some(click);
-                        continue;
-                    }
+            }
+            _ = poll_tick.tick(), if auto_poll && !paused => {
+                next_poll = tokio::time::Instant::now();
+            }
+            event = async { events.as_mut()?.next().await }, if events.is_some() => {
+                let Some(event) = event else {
+                    // The source ended; stop waiting on it.
+                    events = None;
+                    continue;
+                };
+                let (maybe_view, decision) = ctx.machine.on_event(&mut state, event);
+                if let Some(view) = maybe_view {
+                    let _ = ctx.view_tx.send(view);
+                }
+                if decision == UnitDecision::PollNow && mode_allows(ctx.cfg.mode, PollTrigger::Unit) {
+                    next_poll = tokio::time::Instant::now();
+                }
+            }
+            Ok(click) = click_rx.recv() => {
+                if pending_click.is_some() {
+                    pending_click = Some(click);
+                    continue;
+                }
 
-                    let (maybe_view, decision) = machine.on_click(&mut state, click);
-                    if let Some(view) = maybe_view {
-                        let _ = view_tx.send(view);
-                    }
-                    if decision == UnitDecision::PollNow {
-                        next_poll = tokio::time::Instant::now();
-                    }
+                let (maybe_view, decision) = ctx.machine.on_click(&mut state, click);
+                let maybe_view = maybe_view.or_else(|| last_polled.clone());
+                if let Some(view) = maybe_view.filter(|_| !last_poll_failed) {
+                    let _ = ctx.view_tx.send(view);
+                }
+                if decision == UnitDecision::PollNow && mode_allows(ctx.cfg.mode, PollTrigger::Click) {
+                    next_poll = next_poll.min(click_poll_at(last_poll));
                 }
-                () = tokio::time::sleep_until(next_poll) => {
-                    // Poll inline. (Clicks cannot interleave in this arm anyway.)
-                    let out = match tokio::time::timeout(poll_timeout, machine.poll(&effects, &mut state)).await {
-                        Ok(Ok(v)) => Ok(v),
-                        Ok(Err(e)) => Err(e),
-                        Err(_) => Err(PollError::Transport(TransportError::Timeout)),
-                    };
-
-                    next_poll = tokio::time::Instant::now() + poll_backoff;
-
-                    let (availability, decision) = match out {
-                        Ok(v) => machine.on_poll_ok(&mut state, v),
-                        Err(e) => (Availability::Failed(e), UnitDecision::Idle),
-                    };
-                    let view = render_availability(&*machine, availability);
-                    let _ = view_tx.send(view);
-                    if decision == UnitDecision::PollNow {
-                        next_poll = tokio::time::Instant::now();
+            }
+            () = tokio::time::sleep_until(next_poll), if !paused => {
+                // Poll inline. (Clicks cannot interleave in this arm anyway.)
+                let started = tokio::time::Instant::now();
+                last_poll = Some(started);
+                let span = tracing::debug_span!("poll", unit = %ctx.i3_name);
+                let polled = tokio::time::timeout(poll_timeout, ctx.machine.poll(&*ctx.effects, &mut state))
+                    .instrument(span.clone())
+                    .await;
+                let elapsed = started.elapsed();
+                poll_stats.record(elapsed, polled.is_err());
+                let out = match polled {
+                    Ok(Ok(v)) => Ok(v),
+                    Ok(Err(e)) => Err(e),
+                    Err(_) => Err(PollError::Transport(TransportError::Timeout)),
+                };
+                tracing::debug!(
+                    parent: &span,
+                    elapsed_ms = elapsed.as_secs_f64() * 1e3,
+                    p95_ms = poll_stats.p95().as_secs_f64() * 1e3,
+                    timeouts = poll_stats.timeouts,
+                    ok = out.is_ok(),
+                    "polled"
+                );
+
+                let backoff = match &out {
+                    Err(PollError::Transport(t)) if t.is_persistent() => {
+                        poll_backoff.max(PERSISTENT_ERROR_BACKOFF)
                     }
+                    _ => poll_backoff,
+                };
+                next_poll = if auto_poll {
+                    tokio::time::Instant::now() + backoff
+                } else {
+                    never()
+                };
+
+                last_poll_failed = out.is_err();
+                let (availability, decision) = match out {
+                    Ok(v) => ctx.machine.on_poll_ok(&mut state, v),
+                    Err(e) => (Availability::Failed(e), UnitDecision::Idle),
+                };
+                let view = render_availability(&*ctx.machine, availability);
+                if !last_poll_failed {
+                    last_polled = Some(view.clone());
+                }
+                let _ = ctx.view_tx.send(view);
+                if decision == UnitDecision::PollNow {
+                    next_poll = tokio::time::Instant::now();
+                }
 
-                    if let Some(click) = pending_click.take() {
-                        let (maybe_view, decision) = machine.on_click(&mut state, click);
-                        if let Some(view) = maybe_view {
-                            let _ = view_tx.send(view);
-                        }
-                        if decision == UnitDecision::PollNow {
-                            next_poll = tokio::time::Instant::now();
-                        }
+                if let Some(click) = pending_click.take() {
+                    let (maybe_view, decision) = ctx.machine.on_click(&mut state, click);
+                    let maybe_view = maybe_view.or_else(|| last_polled.clone());
+                    if let Some(view) = maybe_view.filter(|_| !last_poll_failed) {
+                        let _ = ctx.view_tx.send(view);
+                    }
+                    if decision == UnitDecision::PollNow && mode_allows(ctx.cfg.mode, PollTrigger::Click) {
+                        next_poll = next_poll.min(click_poll_at(last_poll));
                     }
                 }
             }
         }
-    });
+    }
+}
 
-    MachineWrapper {
-        i3_name,
-        handle,
-        view_rx,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{RED, YELLOW};
+    use crate::machine::effects::{DirEntries, EffectOut, MockEffects};
+
+    /// [`make_chunks`] with the default theme.
+    fn chunks_for(w: &MachineWrapper, padding: i32, view: &View) -> Vec<OutputChunk> {
+        make_chunks(w, padding, view, &ThemeConfig::default())
+    }
+
+    #[test]
+    fn poll_modes_heed_their_triggers() {
+        use PollTrigger::{Click, Interval, Unit};
+        let heeded = |mode| {
+            [Interval, Unit, Click]
+                .into_iter()
+                .filter(|&t| mode_allows(mode, t))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(heeded(PollMode::Interval), [Interval, Unit, Click]);
+        assert_eq!(heeded(PollMode::OnClick), [Unit, Click]);
+        assert_eq!(heeded(PollMode::Manual), [Click]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reactive_flush_writes_once_per_burst_and_on_collapse() {
+        let (view_tx, view_rx) = watch::channel(View::ok(Markup::text("0")));
+        let w = MachineWrapper {
+            i3_name: "a::0".into(),
+            handle: 0,
+            decor: toml::from_str("").unwrap(),
+            collapsed: false,
+            view_rx,
+            click_tx: broadcast::channel(1).0,
+        };
+        let cfg = GlobalConfig {
+            min_polling_interval: 0.1,
+            flush: FlushMode::Reactive,
+            ..GlobalConfig::default()
+        };
+        let (click_tx, click_rx) = broadcast::channel(4);
+        let (_paused_tx, paused_rx) = watch::channel(false);
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let lines = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let out = lines.clone();
+        let output = tokio::spawn(run_output_loop(
+            vec![w],
+            BarConfig {
+                global: cfg,
+                theme: ThemeConfig::default(),
+                metrics: MetricsConfig::default(),
+            },
+            click_rx,
+            paused_rx,
+            async {
+                let _ = stop_rx.await;
+            },
+            move |line: &str| out.lock().unwrap().push(line.to_string()),
+        ));
+        let written = || lines.lock().unwrap().len();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        // The header and the initial line.
+        assert_eq!(written(), 2);
+
+        for n in 1..=5 {
+            view_tx.send_replace(View::ok(Markup::text(n.to_string())));
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(written(), 3);
+        assert!(lines.lock().unwrap()[2].contains("\"full_text\":\" 5 \""));
+
+        let mut collapse = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        collapse.name = "a::0".into();
+        collapse.modifiers = vec!["Shift".into()];
+        click_tx.send(collapse).unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(written(), 4);
+        assert!(lines.lock().unwrap()[3].contains("\"full_text\":\" · \""));
+
+        stop_tx.send(()).unwrap();
+        output.await.unwrap();
+        assert!(lines.lock().unwrap()[4].ends_with("]\n"));
+    }
+
+    #[test]
+    fn poll_p95_is_nearest_rank_over_the_window() {
+        let mut stats = PollStats::default();
+        assert_eq!(stats.p95(), Duration::ZERO);
+        for ms in 1..=100 {
+            stats.record(Duration::from_millis(ms), ms % 50 == 0);
+        }
+        // Only the last 64 polls (37..=100 ms) count; two of all of them timed out.
+        assert_eq!(stats.p95(), Duration::from_millis(97));
+        assert_eq!(stats.timeouts, 2);
+    }
+
+    #[test]
+    fn short_text_is_decorated_unless_collapsed() {
+        let decor: DecorationCfg = toml::from_str("prefix = \"<\"").unwrap();
+        let view = View::ok(Markup::text("long")).with_short(Markup::text("s"));
+        let (_tx, view_rx) = watch::channel(view.clone());
+        let mut w = MachineWrapper {
+            i3_name: "a::0".into(),
+            handle: 0,
+            decor,
+            collapsed: false,
+            view_rx,
+            click_tx: broadcast::channel(1).0,
+        };
+        let chunk = chunks_for(&w, 1, &view).remove(0);
+        assert_eq!(chunk.full_text, " &lt;long ");
+        assert_eq!(chunk.short_text.as_deref(), Some(" &lt;s "));
+        assert!(!chunk.urgent);
+        w.collapsed = true;
+        assert_eq!(chunks_for(&w, 1, &view)[0].short_text, None);
+        assert!(chunks_for(&w, 1, &View::error(Markup::text("x")))[0].urgent);
+        let flashed = View::ok(Markup::text("x")).with_background(Some("#CC6666"));
+        assert_eq!(
+            chunks_for(&w, 1, &flashed)[0].background.as_deref(),
+            Some("#CC6666")
+        );
+    }
+
+    #[test]
+    fn extra_segments_become_numbered_blocks() {
+        let decor: DecorationCfg = toml::from_str("prefix = \"<\"\nsuffix = \">\"").unwrap();
+        let view = View::degraded(Markup::text("up"))
+            .with_short(Markup::text("u"))
+            .with_extra(vec![Markup::text("down")]);
+        let (_tx, view_rx) = watch::channel(view.clone());
+        let mut w = MachineWrapper {
+            i3_name: "Net::0".into(),
+            handle: 0,
+            decor,
+            collapsed: false,
+            view_rx,
+            click_tx: broadcast::channel(1).0,
+        };
+        w.decor.text_color = Some(Srgb8::from("#C5C8C6"));
+        let chunks = chunks_for(&w, 0, &view);
+        assert!(chunks.iter().all(|c| c.color.as_deref() == Some("#C5C8C6")));
+        let fields: Vec<_> = chunks
+            .iter()
+            .map(|c| {
+                (
+                    c.full_text.as_str(),
+                    c.instance.as_deref(),
+                    c.border.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("&lt;up", Some("0"), YELLOW),
+                ("down&gt;", Some("1"), YELLOW)
+            ]
+        );
+        assert_eq!(chunks[1].short_text, None);
+        assert_eq!(
+            chunks_for(&w, 0, &View::ok(Markup::text("x")))[0].instance,
+            None
+        );
+        w.collapsed = true;
+        assert_eq!(chunks_for(&w, 0, &view).len(), 1);
+    }
+
+    #[test]
+    fn collapsed_units_only_expand_on_click() {
+        let view = View::error(Markup::text("bat 3%"));
+        let (_tx, view_rx) = watch::channel(view.clone());
+        let (click_tx, mut unit_rx) = broadcast::channel(4);
+        let mut w = MachineWrapper {
+            i3_name: "Bat::0".into(),
+            handle: 0,
+            decor: toml::from_str("").unwrap(),
+            collapsed: true,
+            view_rx,
+            click_tx,
+        };
+        let chunk = chunks_for(&w, 0, &view).remove(0);
+        assert_eq!(chunk.full_text, "·");
+        assert_eq!(chunk.border, crate::core::DARK_GREY);
+
+        let plain_click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        assert!(w.route_click(plain_click.clone(), ClickModifier::Shift));
+        assert!(!w.collapsed);
+        assert!(unit_rx.try_recv().is_err());
+        assert_eq!(chunks_for(&w, 0, &view)[0].border, RED);
+
+        assert!(!w.route_click(plain_click, ClickModifier::Shift));
+        assert_eq!(
+            unit_rx.try_recv().map(|c| c.button).ok(),
+            Some(crate::core::ClickEvent::BUTTON_LEFT)
+        );
+
+        let mut shift_click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        shift_click.modifiers = vec!["Shift".into()];
+        assert!(w.route_click(shift_click, ClickModifier::Shift));
+        assert!(w.collapsed);
+        assert!(unit_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn actor_polls_through_the_effects_it_is_given() {
+        let machine = Arc::new(crate::machine::units::locks::LocksMachine::new(
+            toml::from_str("").unwrap(),
+        ));
+        let effects = MockEffects::default()
+            .with(
+                "sys/class/leds",
+                EffectOut::DirEntries(DirEntries(vec!["input3::capslock".into()])),
+            )
+            .with(
+                "sys/class/leds/input3::capslock/brightness",
+                EffectOut::FsBytes("1\n".into()),
+            );
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine,
+            Arc::new(effects),
+            toml::from_str("").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        let shown = tokio::time::timeout(
+            Duration::from_secs(1),
+            w.view_rx
+                .wait_for(|v| crate::units::testing::plain(v) == "locks [CAPS NUM]"),
+        )
+        .await
+        .is_ok();
+        assert!(shown);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejected_config_stays_idle_through_clicks() {
+        use crate::machine::units::http::HttpMachine;
+        let machine = Arc::new(HttpMachine::new(
+            toml::from_str("url = \"not a url\"").unwrap(),
+        ));
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine,
+            Arc::new(MockEffects::default()),
+            toml::from_str("").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        let rejected = w.view_rx.borrow().clone();
+        assert!(crate::units::testing::plain(&rejected).starts_with("http: "));
+        assert_eq!(rejected.health, Health::Error);
+        let click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        assert!(!w.route_click(click, ClickModifier::Shift));
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        // Nothing polled: a poll would replace the config error.
+        assert_eq!(
+            crate::units::testing::plain(&*w.view_rx.borrow()),
+            crate::units::testing::plain(&rejected)
+        );
+    }
+
+    #[test]
+    fn compact_drops_labels() {
+        let view = View::ok(Markup::label("cpu ") + Markup::bracketed(Markup::text("5%")));
+        let (_tx, view_rx) = watch::channel(view.clone());
+        let mut w = MachineWrapper {
+            i3_name: "Cpu::0".into(),
+            handle: 0,
+            decor: toml::from_str("prefix = \"|\"").unwrap(),
+            collapsed: false,
+            view_rx,
+            click_tx: broadcast::channel(1).0,
+        };
+        assert_eq!(chunks_for(&w, 0, &view)[0].full_text, "|cpu [5%]");
+        w.decor.compact = Some(true);
+        assert_eq!(chunks_for(&w, 0, &view)[0].full_text, "|[5%]");
+    }
+
+    #[test]
+    fn label_replaces_the_unit_name() {
+        let view = View::ok(Markup::label("net eth0 ") + Markup::text("up").fg(crate::core::GREEN));
+        let (_tx, view_rx) = watch::channel(view.clone());
+        let mut w = MachineWrapper {
+            i3_name: "Net::0".into(),
+            handle: 0,
+            decor: toml::from_str("label = \"wan\"").unwrap(),
+            collapsed: false,
+            view_rx,
+            click_tx: broadcast::channel(1).0,
+        };
+        let text = |w: &MachineWrapper, view: &View| chunks_for(w, 0, view)[0].full_text.clone();
+        assert_eq!(text(&w, &view), "wan <span color='#B5BD68'>up</span>");
+        // Units without a label of their own get one in front.
+        assert_eq!(text(&w, &View::ok(Markup::text("12:00"))), "wan 12:00");
+        w.decor.compact = Some(true);
+        assert_eq!(text(&w, &view), "<span color='#B5BD68'>up</span>");
+
+        // Only the first block is relabeled; the short form follows `body`.
+        let split = View::ok(Markup::label("net eth0 ") + Markup::text("u 1"))
+            .with_extra(vec![Markup::label("down ") + Markup::text("d 2")])
+            .with_short(Markup::label("net ") + Markup::text("u1 d2"));
+        let chunks = |w: &MachineWrapper| {
+            chunks_for(w, 0, &split)
+                .into_iter()
+                .map(|c| (c.full_text, c.short_text))
+                .collect::<Vec<_>>()
+        };
+        w.decor.compact = None;
+        assert_eq!(
+            chunks(&w),
+            [
+                ("wan u 1".into(), Some("wan u1 d2".into())),
+                ("down d 2".into(), None)
+            ]
+        );
+        w.decor.compact = Some(true);
+        assert_eq!(
+            chunks(&w),
+            [("u 1".into(), Some("u1 d2".into())), ("d 2".into(), None)]
+        );
+    }
+
+    /// Panics on every poll, counting its `init`s.
+    #[derive(Debug)]
+    struct Panicky(std::sync::atomic::AtomicUsize);
+
+    impl UnitMachine for Panicky {
+        type PollOut = ();
+        type State = ();
+        type UnitError = std::io::Error;
+
+        fn name(&self) -> &'static str {
+            "Panicky"
+        }
+
+        fn init(&self) -> (Self::State, View, UnitDecision) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            ((), View::ok(Markup::text("up")), UnitDecision::PollNow)
+        }
+
+        fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+            (None, UnitDecision::Idle)
+        }
+
+        fn on_click(
+            &self,
+            _state: &mut Self::State,
+            _click: crate::core::ClickEvent,
+        ) -> (Option<View>, UnitDecision) {
+            (None, UnitDecision::Idle)
+        }
+
+        async fn poll(
+            &self,
+            _effects: &impl crate::machine::effects::Effects,
+            _state: &mut Self::State,
+        ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+            panic!("boom")
+        }
+
+        fn on_poll_ok(
+            &self,
+            _state: &mut Self::State,
+            (): Self::PollOut,
+        ) -> (
+            Availability<Markup, PollError<Self::UnitError>>,
+            UnitDecision,
+        ) {
+            (Availability::Ready(Markup::text("up")), UnitDecision::Idle)
+        }
+    }
+
+    #[tokio::test]
+    async fn panicked_unit_shows_crashed_and_restarts() {
+        let machine = Arc::new(Panicky(std::sync::atomic::AtomicUsize::new(0)));
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine.clone(),
+            Arc::new(MockEffects::default()),
+            toml::from_str("").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        let crashed = w
+            .view_rx
+            .wait_for(|v| crate::units::testing::plain(v).contains("crashed"))
+            .await
+            .unwrap()
+            .clone();
+        assert_eq!(
+            crate::units::testing::plain(&crashed),
+            "panicky crashed, restarting in 1s"
+        );
+        assert_eq!(crashed.health, Health::Error);
+
+        // After the backoff the unit is initialized again, and crashes again.
+        let restarted = tokio::time::timeout(
+            Duration::from_secs(5),
+            w.view_rx
+                .wait_for(|v| crate::units::testing::plain(v).ends_with("2s")),
+        )
+        .await;
+        assert!(restarted.is_ok());
+        assert_eq!(machine.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Event-driven: renders each pushed event, counting its polls.
+    #[derive(Debug)]
+    struct Pushed(std::sync::atomic::AtomicUsize);
+
+    impl UnitMachine for Pushed {
+        type PollOut = ();
+        type State = ();
+        type UnitError = std::io::Error;
+
+        fn name(&self) -> &'static str {
+            "Pushed"
+        }
+
+        fn init(&self) -> (Self::State, View, UnitDecision) {
+            ((), View::ok(Markup::text("init")), UnitDecision::PollNow)
+        }
+
+        fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+            (None, UnitDecision::Idle)
+        }
+
+        fn on_click(
+            &self,
+            _state: &mut Self::State,
+            _click: crate::core::ClickEvent,
+        ) -> (Option<View>, UnitDecision) {
+            (None, UnitDecision::Idle)
+        }
+
+        async fn poll(
+            &self,
+            _effects: &impl crate::machine::effects::Effects,
+            _state: &mut Self::State,
+        ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn on_poll_ok(
+            &self,
+            _state: &mut Self::State,
+            (): Self::PollOut,
+        ) -> (
+            Availability<Markup, PollError<Self::UnitError>>,
+            UnitDecision,
+        ) {
+            (
+                Availability::Ready(Markup::text("polled")),
+                UnitDecision::Idle,
+            )
+        }
+
+        fn wants_polling(&self) -> bool {
+            false
+        }
+
+        fn subscribe(&self, _state: &mut Self::State) -> Option<crate::machine::types::UnitEvents> {
+            let delayed = |ms, event: &str| {
+                let event = event.to_string();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                    event
+                }
+            };
+            Some(
+                futures::stream::iter([delayed(100, "a"), delayed(100, "b")])
+                    .then(|f| f)
+                    .boxed(),
+            )
+        }
+
+        fn on_event(
+            &self,
+            _state: &mut Self::State,
+            event: String,
+        ) -> (Option<View>, UnitDecision) {
+            (Some(View::ok(Markup::text(event))), UnitDecision::Idle)
+        }
+    }
+
+    #[tokio::test]
+    async fn event_driven_unit_renders_events_without_a_poll_timer() {
+        let machine = Arc::new(Pushed(std::sync::atomic::AtomicUsize::new(0)));
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine.clone(),
+            Arc::new(MockEffects::default()),
+            toml::from_str("poll_interval = 0.1").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig {
+                min_polling_interval: 0.05,
+                ..GlobalConfig::default()
+            },
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        let last = tokio::time::timeout(
+            Duration::from_secs(5),
+            w.view_rx
+                .wait_for(|v| crate::units::testing::plain(v) == "b"),
+        )
+        .await;
+        assert!(last.is_ok());
+        // Several poll intervals later, only the initial poll has run.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(machine.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    /// Counts its polls; every click re-renders at once and asks for one.
+    #[derive(Debug)]
+    struct Clicked(std::sync::atomic::AtomicUsize);
+
+    impl UnitMachine for Clicked {
+        type PollOut = ();
+        type State = usize;
+        type UnitError = std::io::Error;
+
+        fn name(&self) -> &'static str {
+            "Clicked"
+        }
+
+        fn init(&self) -> (Self::State, View, UnitDecision) {
+            (0, View::ok(Markup::text("init")), UnitDecision::PollNow)
+        }
+
+        fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+            (None, UnitDecision::Idle)
+        }
+
+        fn on_click(
+            &self,
+            clicks: &mut Self::State,
+            _click: crate::core::ClickEvent,
+        ) -> (Option<View>, UnitDecision) {
+            *clicks += 1;
+            (
+                Some(View::ok(Markup::text(format!("click {clicks}")))),
+                UnitDecision::PollNow,
+            )
+        }
+
+        async fn poll(
+            &self,
+            _effects: &impl crate::machine::effects::Effects,
+            _state: &mut Self::State,
+        ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn on_poll_ok(
+            &self,
+            _state: &mut Self::State,
+            (): Self::PollOut,
+        ) -> (
+            Availability<Markup, PollError<Self::UnitError>>,
+            UnitDecision,
+        ) {
+            (
+                Availability::Ready(Markup::text("polled")),
+                UnitDecision::Idle,
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn click_spam_shows_at_once_but_polls_after_the_cooldown() {
+        let machine = Arc::new(Clicked(std::sync::atomic::AtomicUsize::new(0)));
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine.clone(),
+            Arc::new(MockEffects::default()),
+            toml::from_str("mode = \"on_click\"\nclick_poll_cooldown_sec = 0.3").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        let polls = || machine.0.load(std::sync::atomic::Ordering::SeqCst);
+        w.view_rx
+            .wait_for(|v| crate::units::testing::plain(v) == "polled")
+            .await
+            .unwrap();
+        assert_eq!(polls(), 1);
+
+        let click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        for _ in 0..5 {
+            w.click_tx.send(click.clone()).unwrap();
+        }
+        let shown = tokio::time::timeout(
+            Duration::from_millis(100),
+            w.view_rx
+                .wait_for(|v| crate::units::testing::plain(v) == "click 5"),
+        )
+        .await
+        .is_ok();
+        assert!(shown);
+        assert_eq!(polls(), 1);
+
+        // The five requests collapse into one poll once the cooldown passes.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(polls(), 2);
+    }
+
+    #[tokio::test]
+    async fn mode_click_rerenders_a_real_unit_before_its_poll() {
+        let machine = Arc::new(crate::machine::units::load::LoadMachine::new(
+            toml::from_str("").unwrap(),
+        ));
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine,
+            Arc::new(MockEffects::default()),
+            toml::from_str("mode = \"on_click\"\nclick_poll_cooldown_sec = 60.0").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        w.view_rx
+            .wait_for(|v| v.health == crate::machine::types::Health::Ok)
+            .await
+            .unwrap();
+
+        // The poll this asks for is a minute away; the new mode is not.
+        let click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        w.click_tx.send(click).unwrap();
+        let shown = tokio::time::timeout(
+            Duration::from_millis(100),
+            w.view_rx
+                .wait_for(|v| crate::units::testing::plain(v).starts_with("load/core ")),
+        )
+        .await
+        .is_ok();
+        assert!(shown);
+    }
+
+    #[tokio::test]
+    async fn click_without_a_view_reshows_the_last_poll() {
+        let machine = Arc::new(crate::machine::units::locks::LocksMachine::new(
+            toml::from_str("").unwrap(),
+        ));
+        let effects = MockEffects::default()
+            .with(
+                "sys/class/leds",
+                EffectOut::DirEntries(DirEntries(vec!["input3::numlock".into()])),
+            )
+            .with(
+                "sys/class/leds/input3::numlock/brightness",
+                EffectOut::FsBytes("1\n".into()),
+            );
+        let (paused_tx, _) = watch::channel(false);
+        let mut w = spawn_machine_actor(
+            machine,
+            Arc::new(effects),
+            toml::from_str("mode = \"on_click\"\nclick_poll_cooldown_sec = 60.0").unwrap(),
+            toml::from_str("").unwrap(),
+            GlobalConfig::default(),
+            0,
+            ActorInputs {
+                paused_rx: paused_tx.subscribe(),
+            },
+        );
+        w.view_rx
+            .wait_for(|v| v.health == crate::machine::types::Health::Ok)
+            .await
+            .unwrap();
+        let polled = crate::units::testing::plain(&*w.view_rx.borrow_and_update());
+
+        // `LocksMachine::on_click` emits nothing; the runtime re-sends the poll.
+        let click = crate::units::testing::click(crate::core::ClickEvent::BUTTON_LEFT);
+        w.click_tx.send(click).unwrap();
+        let resent = tokio::time::timeout(Duration::from_millis(100), w.view_rx.changed())
+            .await
+            .is_ok();
+        assert!(resent);
+        assert_eq!(crate::units::testing::plain(&*w.view_rx.borrow()), polled);
     }
 }
@@ -1,22 +1,97 @@
+use crate::render::color::Paint;
 use crate::render::markup::Markup;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Ordered by severity, so `max` picks the worse of two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Health {
     Ok,
     Degraded,
     Error,
 }
 
+/// Config-driven `warn`/`error` levels that turn a healthy reading into
+/// `Degraded`/`Error` before anything actually fails.
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+pub struct HealthThresholds {
+    #[serde(default)]
+    pub warn: Option<f64>,
+    #[serde(default)]
+    pub error: Option<f64>,
+}
+
+impl HealthThresholds {
+    /// For readings where higher is worse (temperature, usage).
+    pub fn above(&self, value: f64) -> Health {
+        self.classify(|level| value >= level)
+    }
+
+    /// For readings where lower is worse (charge left).
+    pub fn below(&self, value: f64) -> Health {
+        self.classify(|level| value <= level)
+    }
+
+    /// Config check for [`Self::above`]: `warn` must lie below `error`.
+    pub fn validate_above(&self, name: &str) -> anyhow::Result<()> {
+        self.validate_order(name, |warn, error| warn < error, "below")
+    }
+
+    /// Config check for [`Self::below`]: `warn` must lie above `error`.
+    pub fn validate_below(&self, name: &str) -> anyhow::Result<()> {
+        self.validate_order(name, |warn, error| warn > error, "above")
+    }
+
+    fn validate_order(
+        &self,
+        name: &str,
+        ordered: impl Fn(f64, f64) -> bool,
+        relation: &str,
+    ) -> anyhow::Result<()> {
+        match (self.warn, self.error) {
+            (Some(warn), Some(error)) if !ordered(warn, error) => Err(anyhow::anyhow!(
+                "`{name}`: warn ({warn}) must be {relation} error ({error})"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn classify(&self, crossed: impl Fn(f64) -> bool) -> Health {
+        if self.error.is_some_and(&crossed) {
+            Health::Error
+        } else if self.warn.is_some_and(&crossed) {
+            Health::Degraded
+        } else {
+            Health::Ok
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct View {
     pub body: Markup,
     pub health: Health,
+    /// Condensed form i3bar falls back to when the bar runs out of room.
+    pub short: Option<Markup>,
+    /// Critical beyond its health (e.g. a nearly empty battery); sent as i3bar's
+    /// `urgent`, which `Health::Error` also sets.
+    pub urgent: bool,
+    /// Segment background, sent as the chunk's `background`.
+    pub background: Option<Paint>,
+    /// Further segments after `body`, each sent as its own i3bar block with the
+    /// same health and styling. Empty for the usual single-block unit.
+    pub extra: Vec<Markup>,
+    /// Raw readings behind `body` (e.g. `("cpu_usage_percent", 12.5)`), for
+    /// the metrics exporter; never rendered.
+    pub metrics: Vec<(&'static str, f64)>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Availability<T, E> {
     Loading,
     Ready(T),
+    /// Ready with a full view from the unit: a health other than `Ok` (e.g.
+    /// `Error` for a vanished device) or a short form.
+    ReadyView(View),
     Failed(E),
 }
 
@@ -35,18 +110,91 @@ impl View {
         Self {
             body,
             health: Health::Ok,
+            short: None,
+            urgent: false,
+            background: None,
+            extra: Vec::new(),
+            metrics: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn degraded(body: Markup) -> Self {
+        Self {
+            body,
+            health: Health::Degraded,
+            short: None,
+            urgent: false,
+            background: None,
+            extra: Vec::new(),
+            metrics: Vec::new(),
         }
     }
 
-    // kept for symmetry with `ok`, but runtime owns error rendering now
-    #[allow(dead_code)]
+    /// For a unit that read successfully but found its device missing or
+    /// broken; transport/poll failures are framed by the runtime instead.
     #[must_use]
     pub fn error(body: Markup) -> Self {
         Self {
             body,
             health: Health::Error,
+            short: None,
+            urgent: false,
+            background: None,
+            extra: Vec::new(),
+            metrics: Vec::new(),
+        }
+    }
+}
+
+impl View {
+    #[must_use]
+    pub fn with_short(self, short: Markup) -> Self {
+        Self {
+            short: Some(short),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_urgent(self, urgent: bool) -> Self {
+        Self { urgent, ..self }
+    }
+
+    /// Splits the view into `body` followed by `extra` as separate blocks.
+    #[must_use]
+    pub fn with_extra(self, extra: Vec<Markup>) -> Self {
+        Self { extra, ..self }
+    }
+
+    #[must_use]
+    pub fn with_background(self, background: Option<impl Into<Paint>>) -> Self {
+        Self {
+            background: background.map(Into::into),
+            ..self
         }
     }
+
+    #[must_use]
+    pub fn with_metric(mut self, name: &'static str, value: f64) -> Self {
+        self.metrics.push((name, value));
+        self
+    }
+
+    /// Worsens health to at least `health`; never improves it.
+    #[must_use]
+    pub fn with_health(self, health: Health) -> Self {
+        Self {
+            health: self.health.max(health),
+            ..self
+        }
+    }
+}
+
+impl AsRef<Markup> for View {
+    fn as_ref(&self) -> &Markup {
+        &self.body
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,16 +206,48 @@ pub enum UnitDecision {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransportError {
     Timeout,
-    Http { status: u16 },
-    Transport(String),
+    /// A non-2xx status, with the server's requested wait if it sent one.
+    Http {
+        status: u16,
+        retry_after: Option<std::time::Duration>,
+    },
+    /// The host name did not resolve.
+    Dns {
+        host: String,
+    },
+    /// No connection to the host (refused, reset, unreachable).
+    Connect {
+        host: String,
+    },
+    /// TLS handshake or certificate failure.
+    Tls {
+        host: String,
+    },
+    /// Local IO: files, directories, pipes, spawning commands.
+    Io(String),
+    /// A response arrived but its body could not be read.
+    Decode(String),
+    Other(String),
+}
+
+impl TransportError {
+    /// Whether retrying soon is unlikely to help, e.g. a misspelt host name or
+    /// a bad certificate. The runtime backs off further on these.
+    pub fn is_persistent(&self) -> bool {
+        matches!(self, Self::Dns { .. } | Self::Tls { .. })
+    }
 }
 
 impl std::fmt::Display for TransportError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Timeout => f.write_str("timeout"),
-            Self::Http { status } => write!(f, "HTTP {status}"),
-            Self::Transport(s) => f.write_str(s),
+            Self::Http { status, .. } => write!(f, "HTTP {status}"),
+            Self::Dns { host } => write!(f, "cannot resolve {host}"),
+            Self::Connect { host } => write!(f, "cannot connect to {host}"),
+            Self::Tls { host } => write!(f, "TLS error with {host}"),
+            Self::Io(s) | Self::Other(s) => f.write_str(s),
+            Self::Decode(s) => write!(f, "bad response: {s}"),
         }
     }
 }
@@ -82,7 +262,7 @@ pub enum PollError<E> {
 
 impl<E> From<String> for PollError<E> {
     fn from(value: String) -> Self {
-        Self::Transport(TransportError::Transport(value))
+        Self::Transport(TransportError::Other(value))
     }
 }
 
@@ -94,10 +274,14 @@ impl<E> From<TransportError> for PollError<E> {
 
 impl<E> From<anyhow::Error> for PollError<E> {
     fn from(value: anyhow::Error) -> Self {
-        Self::Transport(TransportError::Transport(value.to_string()))
+        Self::Transport(TransportError::Other(value.to_string()))
     }
 }
 
+/// A unit's own source of external events (a D-Bus signal, inotify, i3 IPC
+/// ...), one item per event; see [`UnitMachine::subscribe`].
+pub(crate) type UnitEvents = futures::stream::BoxStream<'static, String>;
+
 pub(crate) trait UnitMachine: Send + Sync + std::fmt::Debug + 'static {
     type State: Send + std::fmt::Debug + 'static;
     type PollOut: Send + std::fmt::Debug + 'static;
@@ -107,6 +291,13 @@ pub(crate) trait UnitMachine: Send + Sync + std::fmt::Debug + 'static {
 
     fn init(&self) -> (Self::State, View, UnitDecision);
 
+    /// Checks (and fixes up) the config held in `state`, once, right after
+    /// `init`. On `Err` the runtime shows the message and the unit stays idle
+    /// for good: no ticks, events, clicks or polls.
+    fn validate(&self, _state: &mut Self::State) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     fn on_tick(&self, state: &mut Self::State) -> (Option<View>, UnitDecision);
 
     fn on_click(
@@ -117,7 +308,7 @@ pub(crate) trait UnitMachine: Send + Sync + std::fmt::Debug + 'static {
 
     fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> impl std::future::Future<Output = Result<Self::PollOut, PollError<Self::UnitError>>> + Send;
 
@@ -125,6 +316,24 @@ pub(crate) trait UnitMachine: Send + Sync + std::fmt::Debug + 'static {
         Markup::text(err.to_string())
     }
 
+    /// Whether the runtime polls this unit on a timer. Event-driven units
+    /// return `false`: they still get the initial poll and any `PollNow`
+    /// they ask for, but `poll_interval` never fires.
+    fn wants_polling(&self) -> bool {
+        true
+    }
+
+    /// Called once per actor run, after `init`. The actor awaits the stream
+    /// next to its timers and hands every item to `on_event`; once the stream
+    /// ends it is dropped.
+    fn subscribe(&self, _state: &mut Self::State) -> Option<UnitEvents> {
+        None
+    }
+
+    fn on_event(&self, _state: &mut Self::State, _event: String) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
     fn on_poll_ok(
         &self,
         state: &mut Self::State,
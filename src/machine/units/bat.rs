@@ -1,9 +1,15 @@
-use crate::machine::effects::{EffectReq, FsRead};
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::effects::{
+    DirEntries, DirKey, EffectReq, FsListDir, FsRead, ProcKey, ProcOutput, ProcRun,
+};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
-use crate::units::bat::{Bat, BatConfig};
+use crate::units::bat::{Bat, BatBackend, BatConfig};
 use std::time::Duration;
 
+/// How long one `upower -i` run is reused; upower itself refreshes far less
+/// often than the bar polls, so spawning it every poll buys nothing.
+const UPOWER_REFRESH: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone)]
 pub struct BatMachine {
     cfg: BatConfig,
@@ -32,7 +38,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for BatMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -42,14 +48,16 @@ impl UnitMachine for BatMachine {
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
         let unit = Bat::from_cfg(self.cfg.clone());
-        Bat::fix_up_and_validate();
-        let view = View {
-            body: Markup::text("bat ") + Markup::text("loading").fg(crate::core::VIOLET),
-            health: Health::Degraded,
-        };
+        let view = View::degraded(
+            Markup::label("bat ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
         (State { unit }, view, UnitDecision::PollNow)
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         (None, UnitDecision::Idle)
     }
@@ -60,14 +68,61 @@ impl UnitMachine for BatMachine {
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
         state.unit.handle_click(click);
-        (None, UnitDecision::PollNow)
+        (state.unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        // Desktops have no mains adapter node; its absence is not an error.
+        if let Some(entries) = effects
+            .run(EffectReq::FsListDir(FsListDir {
+                key: DirKey::new("sys/class/power_supply"),
+                path: "/sys/class/power_supply".into(),
+                cache_fresh_for: Duration::from_secs(60),
+            }))
+            .await
+            .ok()
+            .and_then(|out| out.expect::<DirEntries>().ok())
+        {
+            state.unit.select_ac_supply(&entries.0);
+        }
+        let online = match state.unit.ac_online_path() {
+            Some(path) => effects
+                .run(EffectReq::FsRead(FsRead {
+                    key: crate::machine::effects::FsKey::new(format!("power/{path}")),
+                    path: path.into(),
+                    cache_fresh_for: Duration::from_millis(200),
+                }))
+                .await
+                .ok()
+                .and_then(|out| out.expect::<bytes::Bytes>().ok()),
+            None => None,
+        };
+        state.unit.set_ac_online_from_bytes(online.as_deref());
+
+        if state.unit.backend() == BatBackend::Upower {
+            let cmd = state.unit.upower_cmd();
+            let out = effects
+                .run(EffectReq::ProcRun(ProcRun {
+                    key: ProcKey::new(format!("upower:{}", cmd.join(" "))),
+                    cmd,
+                    env: Vec::new(),
+                    timeout: Duration::from_secs(2),
+                    cache_fresh_for: UPOWER_REFRESH,
+                }))
+                .await?
+                .expect::<ProcOutput>()?;
+            if !out.success {
+                return Err(crate::machine::types::PollError::Unit(UnitErr(
+                    "upower failed".into(),
+                )));
+            }
+            return Ok(state.unit.read_markup_from_upower(&out.stdout));
+        }
+
         let out = effects
             .run(EffectReq::FsRead(FsRead {
                 key: crate::machine::effects::FsKey::new(format!(
@@ -79,17 +134,35 @@ impl UnitMachine for BatMachine {
             }))
             .await?;
         let bytes = out.expect::<bytes::Bytes>()?;
+
+        // Only some vendors expose a charge cap; its absence is not an error.
+        let threshold = effects
+            .run(EffectReq::FsRead(FsRead {
+                key: crate::machine::effects::FsKey::new(format!(
+                    "power/{}",
+                    state.unit.threshold_path()
+                )),
+                path: state.unit.threshold_path().into(),
+                cache_fresh_for: Duration::from_secs(30),
+            }))
+            .await
+            .ok()
+            .and_then(|out| out.expect::<bytes::Bytes>().ok());
+        state
+            .unit
+            .set_charge_threshold_from_bytes(threshold.as_deref());
+
         Ok(state.unit.read_markup_from_bytes(&bytes))
     }
 
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
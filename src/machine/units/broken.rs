@@ -0,0 +1,83 @@
+use crate::machine::types::{Availability, PollError, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+
+/// Stands in for a unit whose config could not be loaded, so the bar shows
+/// `bad cfg: <type>` at its position instead of silently dropping it.
+#[derive(Debug, Clone)]
+pub struct BrokenMachine {
+    kind: String,
+}
+
+impl BrokenMachine {
+    pub fn new(kind: String) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for BrokenMachine {
+    type PollOut = ();
+    type State = ();
+    type UnitError = UnitErr;
+
+    // The runtime renders failures as "<name>: <error>", i.e. "bad cfg: <type>".
+    fn name(&self) -> &'static str {
+        "Bad cfg"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let view = View::error(
+            Markup::text(format!("bad cfg: {}", self.kind)).fg(crate::core::theme().error),
+        );
+        ((), view, UnitDecision::Idle)
+    }
+
+    /// Never passes, so the runtime keeps the placeholder idle.
+    fn validate(&self, _state: &mut Self::State) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("{}", self.kind))
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    async fn poll(
+        &self,
+        _effects: &impl crate::machine::effects::Effects,
+        _state: &mut Self::State,
+    ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+        Err(PollError::Unit(UnitErr(self.kind.clone())))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        (): Self::PollOut,
+    ) -> (
+        Availability<Markup, PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (
+            Availability::Failed(PollError::Unit(UnitErr(self.kind.clone()))),
+            UnitDecision::Idle,
+        )
+    }
+}
@@ -0,0 +1,111 @@
+use crate::machine::effects::{EffectReq, ProcKey, ProcOutput, ProcRun};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::clipboard::{Clipboard, ClipboardConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct ClipboardMachine {
+    cfg: ClipboardConfig,
+}
+
+impl ClipboardMachine {
+    pub fn new(cfg: ClipboardConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Clipboard,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for ClipboardMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Clipboard"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Clipboard::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("clip ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        state: &mut Self::State,
+        click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        state.unit.handle_click(click);
+        (state.unit.render().map(View::ok), UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        let mut cache_fresh_for = Duration::from_secs_f64(state.unit.refresh_interval_sec());
+        if state.unit.take_pending_clear() {
+            effects
+                .run(EffectReq::ProcRun(ProcRun {
+                    key: ProcKey::new("clipboard:clear"),
+                    cmd: state.unit.clear_cmd(),
+                    env: Vec::new(),
+                    timeout: Duration::from_secs(2),
+                    cache_fresh_for: Duration::ZERO,
+                }))
+                .await?;
+            // The cached read is stale now.
+            cache_fresh_for = Duration::ZERO;
+        }
+
+        let out = effects
+            .run(EffectReq::ProcRun(ProcRun {
+                key: ProcKey::new("clipboard:read"),
+                cmd: state.unit.read_cmd(),
+                env: Vec::new(),
+                timeout: Duration::from_secs(2),
+                cache_fresh_for,
+            }))
+            .await?
+            .expect::<ProcOutput>()?;
+        Ok(state.unit.read_markup_from_output(out.success, out.stdout))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::machine::effects::{EffectReq, ProcKey, ProcOutput, ProcRun};
+use crate::machine::types::{Availability, Health, PollError, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::command::{Command, CommandConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct CommandMachine {
+    cfg: CommandConfig,
+}
+
+impl CommandMachine {
+    pub fn new(cfg: CommandConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Command,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for CommandMachine {
+    type PollOut = View;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Command"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Command::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("cmd ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        state: &mut Self::State,
+        click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        state.unit.handle_click(click);
+        (None, UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+        // A click always runs the command, so it sees its button.
+        let (env, cache_fresh_for) = match state.unit.take_click_env() {
+            Some(env) => (env, Duration::ZERO),
+            None => (
+                Vec::new(),
+                Duration::from_secs_f64(state.unit.refresh_interval_sec()),
+            ),
+        };
+        let out = effects
+            .run(EffectReq::ProcRun(ProcRun {
+                key: ProcKey::new(format!("command:{}", self.cfg.command)),
+                cmd: state.unit.cmd(),
+                env,
+                timeout: Duration::from_secs_f64(state.unit.timeout_sec()),
+                cache_fresh_for,
+            }))
+            .await?
+            .expect::<ProcOutput>()?;
+        let block = Command::parse_output(&out.stdout);
+        let mut view = View::ok(state.unit.read_markup(&block));
+        if let Some(short) = state.unit.read_short_markup(&block) {
+            view = view.with_short(short);
+        }
+        if !out.success {
+            // Like i3blocks, a failing command still shows its output.
+            view.health = Health::Error;
+        }
+        Ok(view)
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        view: Self::PollOut,
+    ) -> (
+        Availability<Markup, PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::ReadyView(view), UnitDecision::Idle)
+    }
+}
@@ -1,6 +1,8 @@
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
-use crate::units::cpu::{Cpu, CpuConfig};
+use crate::units::cpu::{
+    Cpu, CpuConfig, ThrottleInputs, CUR_FREQ_PATH, MAX_FREQ_PATH, THROTTLE_COUNT_PATH,
+};
 
 #[derive(Debug, Clone)]
 pub struct CpuMachine {
@@ -30,7 +32,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for CpuMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -40,15 +42,16 @@ impl UnitMachine for CpuMachine {
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
         let unit = Cpu::from_cfg(self.cfg.clone());
-        Cpu::fix_up_and_validate();
-        let view = View {
-            body: Markup::text("cpu ") + Markup::text("loading").fg(crate::core::VIOLET),
-            health: Health::Degraded,
-        };
-
+        let view = View::degraded(
+            Markup::label("cpu ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
         (State { unit }, view, UnitDecision::PollNow)
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         (None, UnitDecision::Idle)
     }
@@ -59,12 +62,12 @@ impl UnitMachine for CpuMachine {
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
         state.unit.handle_click(click);
-        (None, UnitDecision::PollNow)
+        (state.unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         let out = effects
@@ -87,17 +90,46 @@ impl UnitMachine for CpuMachine {
             }
         };
 
-        Ok(state.unit.read_markup_from_proc_stat(&bytes))
+        if state.unit.show_throttle() {
+            let cur_freq = read_optional(effects, CUR_FREQ_PATH).await;
+            let max_freq = read_optional(effects, MAX_FREQ_PATH).await;
+            let throttle_count = read_optional(effects, THROTTLE_COUNT_PATH).await;
+            state.unit.set_throttle_inputs(ThrottleInputs {
+                cur_freq: cur_freq.as_deref(),
+                max_freq: max_freq.as_deref(),
+                throttle_count: throttle_count.as_deref(),
+            });
+        }
+
+        Ok(state.unit.read_markup_from_bytes(&bytes))
     }
 
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
+
+/// Nodes vary by driver/vendor; a missing one just disables its check.
+async fn read_optional(
+    effects: &impl crate::machine::effects::Effects,
+    path: &str,
+) -> Option<bytes::Bytes> {
+    effects
+        .run(crate::machine::effects::EffectReq::FsRead(
+            crate::machine::effects::FsRead {
+                key: crate::machine::effects::FsKey::new(path.trim_start_matches('/')),
+                path: path.into(),
+                cache_fresh_for: std::time::Duration::from_millis(150),
+            },
+        ))
+        .await
+        .ok()
+        .and_then(|out| out.expect::<bytes::Bytes>().ok())
+}
@@ -1,5 +1,7 @@
-use crate::machine::effects::{DirEntries, EffectReq, FsListDir, FsRead};
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::effects::{
+    DirEntries, EffectReq, FsListDir, FsRead, ProcKey, ProcOutput, ProcRun,
+};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
 use crate::units::disk::{Disk, DiskConfig};
 use std::time::Duration;
@@ -32,7 +34,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for DiskMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -42,25 +44,14 @@ impl UnitMachine for DiskMachine {
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
         let unit = Disk::from_cfg(self.cfg.clone());
-        Disk::fix_up_and_validate();
-        let (view, decision) = match self.cfg.validate() {
-            Ok(()) => (
-                View {
-                    body: Markup::text("disk ") + Markup::text("loading").fg(crate::core::VIOLET),
-                    health: Health::Degraded,
-                },
-                UnitDecision::PollNow,
-            ),
-            Err(msg) => (
-                View {
-                    body: Markup::text("disk ") + Markup::text(msg).fg(crate::core::RED),
-                    health: Health::Error,
-                },
-                UnitDecision::Idle,
-            ),
-        };
+        let view = View::degraded(
+            Markup::label("disk ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
 
-        (State { unit }, view, decision)
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
     }
 
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
@@ -69,18 +60,21 @@ impl UnitMachine for DiskMachine {
 
     fn on_click(
         &self,
-        _state: &mut Self::State,
+        state: &mut Self::State,
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
-        Disk::handle_click(click);
-        (None, UnitDecision::PollNow)
+        state.unit.handle_click(click);
+        (state.unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        if state.unit.is_worst() {
+            return read_worst_mount(effects, &state.unit).await;
+        }
         if state.unit.disk_name().is_none() {
             if let Some(name) = resolve_disk_name(effects, &state.unit).await? {
                 state.unit.set_disk_name(name);
@@ -135,17 +129,48 @@ impl UnitMachine for DiskMachine {
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
 
+/// Filters `/proc/mounts` by type first, then statvfs's only the kept mounts
+/// through a `stat` child, so a hung network mount cannot stall the actor.
+async fn read_worst_mount(
+    effects: &impl crate::machine::effects::Effects,
+    unit: &Disk,
+) -> Result<View, crate::machine::types::PollError<UnitErr>> {
+    let mounts = effects
+        .run(EffectReq::FsRead(FsRead {
+            key: crate::machine::effects::FsKey::new("proc/mounts"),
+            path: "/proc/mounts".into(),
+            cache_fresh_for: Duration::from_secs(5),
+        }))
+        .await?;
+    let mount_points = unit.worst_mount_points(&mounts.expect::<bytes::Bytes>()?);
+    if mount_points.is_empty() {
+        return Ok(unit.read_worst_mount(&[]));
+    }
+    let stat = effects
+        .run(EffectReq::ProcRun(ProcRun {
+            key: ProcKey::new("disk:worst"),
+            cmd: Disk::worst_stat_cmd(&mount_points),
+            env: Vec::new(),
+            timeout: Duration::from_secs(2),
+            cache_fresh_for: Duration::ZERO,
+        }))
+        .await?
+        .expect::<ProcOutput>()?;
+    // `stat` fails as a whole if any one mount vanished; the rest still print.
+    Ok(unit.read_worst_mount(&stat.stdout))
+}
+
 async fn resolve_disk_name(
-    _effects: &crate::machine::effects::EffectEngine,
+    _effects: &impl crate::machine::effects::Effects,
     unit: &Disk,
 ) -> Result<Option<String>, crate::machine::types::PollError<UnitErr>> {
     if let Some(label) = unit.selector_partlabel() {
@@ -166,3 +191,15 @@ async fn resolve_disk_name(
     }
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DiskMachine;
+
+    #[test]
+    fn missing_selector_fails_init() {
+        let machine = DiskMachine::new(toml::from_str("").unwrap());
+        let err = crate::machine::runtime::init_validated(&machine).err();
+        assert!(err.is_some_and(|e| e.to_string().contains("missing selector")));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::machine::effects::{EffectReq, FsKey, FsRead};
+use crate::machine::types::{Availability, PollError, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::file::{File, FileConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct FileMachine {
+    cfg: FileConfig,
+}
+
+impl FileMachine {
+    pub fn new(cfg: FileConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: File,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for FileMachine {
+    type PollOut = View;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "File"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = File::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("file ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+        let path = state.unit.path();
+        // A missing or unreadable file is shown as `n/a`, not as a poll error.
+        let bytes = effects
+            .run(EffectReq::FsRead(FsRead {
+                key: FsKey::new(format!("file/{}", path.display())),
+                path: path.to_path_buf(),
+                cache_fresh_for: Duration::from_secs_f64(state.unit.refresh_interval_sec()),
+            }))
+            .await
+            .ok()
+            .and_then(|out| out.expect::<bytes::Bytes>().ok());
+        Ok(state.unit.read_markup_from_bytes(bytes.as_deref()))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        view: Self::PollOut,
+    ) -> (
+        Availability<Markup, PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::ReadyView(view), UnitDecision::Idle)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::machine::effects::{EffectReq, HttpCacheKey, HttpGet, HttpPolicy, HttpResponse};
+use crate::machine::types::{Availability, PollError, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::http::{Http, HttpUnitConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct HttpMachine {
+    cfg: HttpUnitConfig,
+}
+
+impl HttpMachine {
+    pub fn new(cfg: HttpUnitConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Http,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for HttpMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Http"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Http::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label(format!("{} ", self.cfg.name))
+                + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, PollError<Self::UnitError>> {
+        let url = reqwest::Url::parse(state.unit.url())
+            .map_err(|e| PollError::Unit(UnitErr(e.to_string())))?;
+        let every = Duration::from_secs_f64(state.unit.refresh_interval_sec());
+        let policy = HttpPolicy {
+            rate: crate::machine::http::RateLimitSpec {
+                per: every,
+                burst: 1,
+            },
+            cache_fresh_for: every,
+        };
+        let response = effects
+            .run(EffectReq::HttpGet(HttpGet {
+                key: HttpCacheKey::new(format!("http-unit:{url}")),
+                url,
+                policy,
+            }))
+            .await?
+            .expect::<HttpResponse>()?;
+        if response.status == 204 {
+            return Ok(Markup::label(format!("{} ", self.cfg.name))
+                + Markup::text("no content").fg(crate::core::Color::Grey));
+        }
+        state
+            .unit
+            .read_markup_from_body(&response.body)
+            .map_err(|e| PollError::Unit(UnitErr(e)))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpMachine;
+
+    #[test]
+    fn bad_url_fails_validation() {
+        let machine = HttpMachine::new(toml::from_str("url = \"not a url\"").unwrap());
+        let err = crate::machine::runtime::init_validated(&machine).err();
+        assert!(err.is_some_and(|e| e.to_string().contains("url")));
+    }
+}
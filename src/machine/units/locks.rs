@@ -0,0 +1,152 @@
+use crate::machine::effects::{DirEntries, DirKey, EffectReq, FsKey, FsListDir, FsRead};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::locks::{Locks, LocksConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct LocksMachine {
+    cfg: LocksConfig,
+}
+
+impl LocksMachine {
+    pub fn new(cfg: LocksConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Locks,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for LocksMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Locks"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Locks::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("locks ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        // LEDs appear and disappear with keyboards; relist occasionally.
+        let entries = effects
+            .run(EffectReq::FsListDir(FsListDir {
+                key: DirKey::new("sys/class/leds"),
+                path: "/sys/class/leds".into(),
+                cache_fresh_for: Duration::from_secs(10),
+            }))
+            .await?
+            .expect::<DirEntries>()?
+            .0;
+
+        let mut lit = Vec::with_capacity(state.unit.locks().len());
+        for &lock in state.unit.locks() {
+            let mut on = false;
+            for led in Locks::leds_for(lock, &entries) {
+                let path = format!("/sys/class/leds/{led}/brightness");
+                // A keyboard unplugged mid-poll is not an error; skip its LED.
+                let Ok(out) = effects
+                    .run(EffectReq::FsRead(FsRead {
+                        key: FsKey::new(path.trim_start_matches('/')),
+                        path: path.clone().into(),
+                        cache_fresh_for: Duration::from_millis(100),
+                    }))
+                    .await
+                else {
+                    continue;
+                };
+                if Locks::parse_brightness(&out.expect::<bytes::Bytes>()?) {
+                    on = true;
+                    break;
+                }
+            }
+            lit.push(on);
+        }
+
+        Ok(state.unit.read_markup(&lit))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocksMachine;
+    use crate::machine::effects::{DirEntries, EffectOut, MockEffects};
+    use crate::machine::runtime::poll_once;
+    use crate::units::testing::plain;
+
+    #[tokio::test]
+    async fn any_lit_keyboard_lights_the_lock() {
+        let machine = LocksMachine::new(toml::from_str("").unwrap());
+        let leds = ["input3::capslock", "input7::capslock", "input3::numlock"].map(String::from);
+        let effects = MockEffects::default()
+            .with(
+                "sys/class/leds",
+                EffectOut::DirEntries(DirEntries(leds.to_vec())),
+            )
+            .with(
+                "sys/class/leds/input3::capslock/brightness",
+                EffectOut::FsBytes("0\n".into()),
+            )
+            .with(
+                "sys/class/leds/input7::capslock/brightness",
+                EffectOut::FsBytes("1\n".into()),
+            );
+        // input3::numlock has no canned read, like a keyboard unplugged mid-poll.
+        let (view, error) = poll_once(&machine, &effects).await;
+        assert_eq!(error, None);
+        assert_eq!(plain(&view), "locks [CAPS NUM]");
+        assert!(crate::units::testing::pango(&view).contains("#F0C674'>CAPS"));
+    }
+}
@@ -1,13 +1,15 @@
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
 use crate::units::mem::{Mem, MemConfig};
 
 #[derive(Debug, Clone, Copy)]
-pub struct MemMachine;
+pub struct MemMachine {
+    cfg: MemConfig,
+}
 
 impl MemMachine {
-    pub fn new(_cfg: MemConfig) -> Self {
-        Self
+    pub fn new(cfg: MemConfig) -> Self {
+        Self { cfg }
     }
 }
 
@@ -28,7 +30,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for MemMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -37,16 +39,17 @@ impl UnitMachine for MemMachine {
     }
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
-        let unit = Mem::from_cfg(MemConfig {});
-        Mem::fix_up_and_validate();
-        let view = View {
-            body: Markup::text("mem ") + Markup::text("loading").fg(crate::core::VIOLET),
-            health: Health::Degraded,
-        };
-
+        let unit = Mem::from_cfg(self.cfg);
+        let view = View::degraded(
+            Markup::label("mem ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
         (State { unit }, view, UnitDecision::PollNow)
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         (None, UnitDecision::Idle)
     }
@@ -57,12 +60,12 @@ impl UnitMachine for MemMachine {
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
         state.unit.handle_click(click);
-        (None, UnitDecision::PollNow)
+        (state.unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        _effects: &crate::machine::effects::EffectEngine,
+        _effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         Ok(state.unit.read_markup())
@@ -71,11 +74,11 @@ impl UnitMachine for MemMachine {
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
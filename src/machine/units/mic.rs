@@ -0,0 +1,192 @@
+use crate::machine::effects::{EffectReq, ProcKey, ProcOutput, ProcRun};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::mic::{Mic, MicConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct MicMachine {
+    cfg: MicConfig,
+}
+
+impl MicMachine {
+    pub fn new(cfg: MicConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Mic,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for MicMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Mic"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Mic::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("mic ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        state: &mut Self::State,
+        click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        state.unit.handle_click(click);
+        (None, UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        let mut cache_fresh_for = Duration::from_secs_f64(state.unit.refresh_interval_sec());
+        // The queue is already drained, so one failing `wpctl` must not drop
+        // the clicks behind it: run them all and report the first failure.
+        let mut failed = None;
+        for action in state.unit.take_pending() {
+            let ran = effects
+                .run(EffectReq::ProcRun(ProcRun {
+                    key: ProcKey::new("mic:action"),
+                    cmd: state.unit.action_cmd(action),
+                    env: Vec::new(),
+                    timeout: Duration::from_secs(2),
+                    cache_fresh_for: Duration::ZERO,
+                }))
+                .await;
+            if let Err(e) = ran {
+                failed.get_or_insert(e);
+            }
+            cache_fresh_for = Duration::ZERO;
+        }
+        if let Some(e) = failed {
+            return Err(e.into());
+        }
+
+        let volume = effects
+            .run(EffectReq::ProcRun(ProcRun {
+                key: ProcKey::new("mic:volume"),
+                cmd: Mic::volume_cmd(),
+                env: Vec::new(),
+                timeout: Duration::from_secs(2),
+                cache_fresh_for,
+            }))
+            .await?
+            .expect::<ProcOutput>()?;
+        let source = volume
+            .success
+            .then(|| Mic::parse_volume(&volume.stdout))
+            .flatten();
+
+        // `pactl` may be absent; treat that as "not in use" rather than failing.
+        let in_use = effects
+            .run(EffectReq::ProcRun(ProcRun {
+                key: ProcKey::new("mic:in-use"),
+                cmd: Mic::in_use_cmd(),
+                env: Vec::new(),
+                timeout: Duration::from_secs(2),
+                cache_fresh_for: Duration::from_secs_f64(state.unit.refresh_interval_sec()),
+            }))
+            .await
+            .ok()
+            .and_then(|out| out.expect::<ProcOutput>().ok())
+            .is_some_and(|out| out.success && !out.stdout.trim_ascii().is_empty());
+
+        Ok(state.unit.read_markup(source, in_use))
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MicMachine;
+    use crate::core::ClickEvent;
+    use crate::machine::effects::{EffectOut, EffectReq, Effects};
+    use crate::machine::types::{PollError, TransportError, UnitMachine};
+    use crate::units::testing::click;
+    use std::sync::Mutex;
+
+    /// Fails the first `wpctl` action and records every action command.
+    #[derive(Default)]
+    struct FlakyWpctl {
+        actions: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl Effects for FlakyWpctl {
+        async fn run(&self, req: EffectReq) -> Result<EffectOut, TransportError> {
+            if req.key() != "mic:action" {
+                return Err(TransportError::Other("not under test".into()));
+            }
+            let EffectReq::ProcRun(run) = req else {
+                unreachable!("mic only runs processes");
+            };
+            let mut actions = self.actions.lock().unwrap();
+            actions.push(run.cmd);
+            if actions.len() == 1 {
+                return Err(TransportError::Other("wpctl hung up".into()));
+            }
+            Ok(EffectOut::ProcOutput(crate::machine::effects::ProcOutput {
+                success: true,
+                stdout: bytes::Bytes::new(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failed_action_does_not_drop_the_rest() {
+        let machine = MicMachine::new(toml::from_str("").unwrap());
+        let (mut state, _, _) = machine.init();
+        machine.on_click(&mut state, click(ClickEvent::BUTTON_LEFT));
+        machine.on_click(&mut state, click(ClickEvent::BUTTON_SCROLL_UP));
+        let effects = FlakyWpctl::default();
+
+        let out = machine.poll(&effects, &mut state).await;
+        assert!(matches!(
+            out,
+            Err(PollError::Transport(TransportError::Other(msg))) if msg == "wpctl hung up"
+        ));
+        let actions = effects.actions.into_inner().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1][1], "set-volume");
+    }
+}
@@ -1,8 +1,19 @@
 pub mod bat;
+pub mod broken;
+pub mod clipboard;
+pub mod command;
 pub mod cpu;
 pub mod disk;
+pub mod file;
+pub mod http;
+pub mod load;
+pub mod locks;
 pub mod mem;
+pub mod mic;
 pub mod net;
+pub mod spacer;
 pub mod time;
+pub mod uptime;
 pub mod weather;
 pub mod wifi;
+pub mod window;
@@ -1,6 +1,6 @@
-use crate::core::{ClickEvent, VIOLET};
+use crate::core::{theme, ClickEvent};
 use crate::machine::effects::{EffectReq, FsRead, ProcBatch, ProcKey};
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
 use crate::units::net::{Net, NetConfig};
 
@@ -32,7 +32,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for NetMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -41,17 +41,14 @@ impl UnitMachine for NetMachine {
     }
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
-        let view = View {
-            body: Markup::text("net ") + Markup::text("loading").fg(VIOLET),
-            health: Health::Degraded,
-        };
-        (
-            State {
-                unit: Some(Net::from_cfg(self.cfg.clone())),
-            },
-            view,
-            UnitDecision::PollNow,
-        )
+        let unit = Net::from_cfg(self.cfg.clone());
+        let view =
+            View::degraded(Markup::label("net ") + Markup::text("loading").fg(theme().loading));
+        (State { unit: Some(unit) }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.as_mut().map_or(Ok(()), Net::fix_up_and_validate)
     }
 
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
@@ -66,12 +63,12 @@ impl UnitMachine for NetMachine {
 
         // Mode transitions own background task lifetime.
         // In practice, `Net` already starts/stops ping in `handle_click`.
-        (None, UnitDecision::PollNow)
+        (unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         let Some(unit) = state.unit.as_mut() else {
@@ -85,19 +82,15 @@ impl UnitMachine for NetMachine {
                 "ping:{}:{}",
                 unit.cfg.interface, unit.cfg.ping_server
             ));
-            let cmd = vec![
-                "ping".to_string(),
-                "-n".to_string(),
-                "-O".to_string(),
-                "-I".to_string(),
-                unit.cfg.interface.clone(),
-                unit.cfg.ping_server.clone(),
-            ];
+            let cmd = unit.ping_cmd();
             let lines = match effects
                 .run(EffectReq::ProcBatch(ProcBatch {
                     key,
                     cmd,
                     max_lines: 64,
+                    max_runtime: None,
+                    // `ping -O` prints every interval, answered or not.
+                    max_idle: Some(unit.ping_max_idle()),
                 }))
                 .await
             {
@@ -128,11 +121,11 @@ impl UnitMachine for NetMachine {
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
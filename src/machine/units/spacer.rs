@@ -0,0 +1,94 @@
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::spacer::{Spacer, SpacerConfig};
+
+#[derive(Debug, Clone)]
+pub struct SpacerMachine {
+    cfg: SpacerConfig,
+}
+
+impl SpacerMachine {
+    pub fn new(cfg: SpacerConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Spacer,
+}
+
+impl UnitMachine for SpacerMachine {
+    type PollOut = ();
+    type State = State;
+    type UnitError = std::convert::Infallible;
+
+    fn name(&self) -> &'static str {
+        "Spacer"
+    }
+
+    /// The text never changes, so the first view is final and no poll is asked for.
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Spacer::from_cfg(self.cfg.clone());
+        let view = View::ok(unit.read_markup());
+        (State { unit }, view, UnitDecision::Idle)
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    /// Only the runtime's one initial poll gets here; it reads nothing.
+    async fn poll(
+        &self,
+        _effects: &impl crate::machine::effects::Effects,
+        _state: &mut Self::State,
+    ) -> Result<(), crate::machine::types::PollError<Self::UnitError>> {
+        Ok(())
+    }
+
+    fn on_poll_ok(
+        &self,
+        state: &mut Self::State,
+        (): (),
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (
+            Availability::Ready(state.unit.read_markup()),
+            UnitDecision::Idle,
+        )
+    }
+
+    fn wants_polling(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpacerMachine;
+    use crate::machine::types::{Health, UnitDecision, UnitMachine};
+    use crate::units::testing::{pango, plain};
+
+    #[test]
+    fn shows_its_text_without_polling() {
+        let machine = SpacerMachine::new(toml::from_str("").unwrap());
+        let (_, view, decision) = machine.init();
+        assert_eq!((plain(&view), view.health), ("│".into(), Health::Ok));
+        assert_eq!(decision, UnitDecision::Idle);
+        assert!(!machine.wants_polling());
+
+        let machine = SpacerMachine::new(toml::from_str("text = \" <> \"").unwrap());
+        assert_eq!(pango(&machine.init().1), " &lt;&gt; ");
+    }
+}
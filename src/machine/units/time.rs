@@ -40,30 +40,31 @@ impl UnitMachine for TimeMachine {
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
         let unit = Time::from_cfg(self.cfg.clone());
-        Time::fix_up_and_validate();
-        let view = View {
-            body: Markup::text("time ") + Markup::text("loading").fg(crate::core::VIOLET),
-            health: crate::machine::types::Health::Degraded,
-        };
+        let view = View::degraded(
+            Markup::label("time ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
         (State { unit }, view, UnitDecision::PollNow)
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         (None, UnitDecision::Idle)
     }
 
     fn on_click(
         &self,
-        state: &mut Self::State,
-        click: crate::core::ClickEvent,
+        _state: &mut Self::State,
+        _click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
-        state.unit.handle_click(click);
-        (None, UnitDecision::PollNow)
+        (None, UnitDecision::Idle)
     }
 
     async fn poll(
         &self,
-        _effects: &crate::machine::effects::EffectEngine,
+        _effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         Ok(state.unit.read_markup())
@@ -0,0 +1,84 @@
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::uptime::{Uptime, UptimeConfig};
+
+#[derive(Debug, Clone)]
+pub struct UptimeMachine {
+    cfg: UptimeConfig,
+}
+
+impl UptimeMachine {
+    pub fn new(cfg: UptimeConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Uptime,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for UptimeMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Uptime"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Uptime::from_cfg(self.cfg);
+        let view = View::degraded(
+            Markup::label("uptime ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        (None, UnitDecision::Idle)
+    }
+
+    fn on_click(
+        &self,
+        state: &mut Self::State,
+        click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        state.unit.handle_click(click);
+        (state.unit.render().map(View::ok), UnitDecision::PollNow)
+    }
+
+    async fn poll(
+        &self,
+        _effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        Ok(state.unit.read_markup())
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
 use crate::units::weather::{Weather, WeatherConfig, WeatherError};
 
@@ -52,20 +52,14 @@ impl UnitMachine for WeatherMachine {
     }
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
-        let mut unit = Weather::from_cfg(self.cfg.clone());
-        // validate once at startup
-        let view = match unit.fix_up_and_validate() {
-            Ok(()) => View {
-                body: Markup::text("weather ") + Markup::text("loading").fg(crate::core::VIOLET),
-                health: Health::Degraded,
-            },
-            Err(e) => View {
-                body: Markup::text("weather ") + Markup::text(e.to_string()).fg(crate::core::RED),
-                health: Health::Error,
-            },
-        };
-        let now = Markup::text("weather ") + Markup::text("loading").fg(crate::core::VIOLET);
-        let forecast = Markup::text("weather ") + Markup::text("loading").fg(crate::core::VIOLET);
+        let unit = Weather::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("weather ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        let now =
+            Markup::label("weather ") + Markup::text("loading").fg(crate::core::theme().loading);
+        let forecast =
+            Markup::label("weather ") + Markup::text("loading").fg(crate::core::theme().loading);
         (
             State {
                 unit,
@@ -77,6 +71,10 @@ impl UnitMachine for WeatherMachine {
         )
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         // No render-only ticking for weather.
         (None, UnitDecision::Idle)
@@ -87,24 +85,37 @@ impl UnitMachine for WeatherMachine {
         state: &mut Self::State,
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
+        let prev_mode = state.unit.mode;
+        let prev_location = state.unit.location;
         state.unit.handle_click(click);
+        if state.unit.location != prev_location {
+            // The per-mode views show the old place.
+            state.last_view_now = None;
+            state.last_view_forecast = None;
+        }
+        if state.unit.mode == prev_mode {
+            // Forecast paging or a location switch: the cached view is stale, so
+            // re-render from the cached response instead.
+            return (None, UnitDecision::PollNow);
+        }
         let view = match state.unit.mode {
             crate::units::weather::DisplayMode::Now => match &state.last_view_now {
-                Some(m) => View::ok(m.clone()),
-                None => View {
-                    body: Markup::text("weather ")
-                        + Markup::text("loading").fg(crate::core::VIOLET),
-                    health: Health::Degraded,
-                },
+                Some(m) => with_short(&state.unit, m.clone()),
+                None => View::degraded(
+                    Markup::label("weather ")
+                        + Markup::text("loading").fg(crate::core::theme().loading),
+                ),
             },
             crate::units::weather::DisplayMode::Forecast => match &state.last_view_forecast {
-                Some(m) => View::ok(m.clone()),
-                None => View {
-                    body: Markup::text("weather ")
-                        + Markup::text("loading").fg(crate::core::VIOLET),
-                    health: Health::Degraded,
-                },
+                Some(m) => with_short(&state.unit, m.clone()),
+                None => View::degraded(
+                    Markup::label("weather ")
+                        + Markup::text("loading").fg(crate::core::theme().loading),
+                ),
             },
+            crate::units::weather::DisplayMode::Sun => {
+                View::ok(state.unit.format_sun(state.unit.now()))
+            }
         };
 
         // Allow a pure UI toggle without waiting for the next poll.
@@ -114,7 +125,7 @@ impl UnitMachine for WeatherMachine {
 
     async fn poll(
         &self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         state.unit.read_markup(effects).await.map_err(|e| match e {
@@ -146,8 +157,161 @@ impl UnitMachine for WeatherMachine {
             crate::units::weather::DisplayMode::Forecast => {
                 state.last_view_forecast = Some(body.clone());
             }
+            crate::units::weather::DisplayMode::Sun => {}
         }
 
-        (Availability::Ready(body), UnitDecision::Idle)
+        (
+            Availability::ReadyView(with_short(&state.unit, body)),
+            UnitDecision::Idle,
+        )
+    }
+}
+
+/// `body` with the unit's compact form as i3bar's `short_text`, if it has one,
+/// and degraded while the last refresh failed.
+fn with_short(unit: &Weather, body: Markup) -> View {
+    let mut view = View::ok(body);
+    if unit.fetch_failed() {
+        view = view.with_health(crate::machine::types::Health::Degraded);
+    }
+    match unit.short_markup() {
+        Some(short) => view.with_short(short),
+        None => view,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WeatherMachine;
+    use crate::machine::effects::{EffectOut, HttpResponse, MockEffects};
+    use crate::machine::runtime::poll_once;
+    use crate::units::testing::plain;
+
+    #[tokio::test]
+    async fn recorded_forecast_renders_without_network() {
+        let machine = WeatherMachine::new(toml::from_str("lat = 52.52\nlon = 13.405").unwrap());
+        let body = include_bytes!("../../../tests/fixtures/open_meteo_berlin.json");
+        let effects = MockEffects::default().with(
+            "open-meteo:52.5200:13.4050",
+            EffectOut::Http(HttpResponse {
+                status: 200,
+                headers: std::collections::HashMap::new(),
+                body: bytes::Bytes::from_static(body),
+            }),
+        );
+        let (view, error) = poll_once(&machine, &effects).await;
+        assert_eq!(error, None);
+        assert_eq!(plain(&view), "weather [☁️10°C▲]");
+
+        let (view, error) = poll_once(&machine, &MockEffects::default()).await;
+        assert!(error.is_some_and(|e| e.contains("no canned output")));
+        assert!(plain(&view).starts_with("weather: "));
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_shows_cached_data_as_stale() {
+        use crate::machine::types::{Availability, Health, UnitMachine};
+        let machine = WeatherMachine::new(toml::from_str("lat = 52.52\nlon = 13.405").unwrap());
+        let body = include_bytes!("../../../tests/fixtures/open_meteo_berlin.json");
+        let effects = MockEffects::default().with(
+            "open-meteo:52.5200:13.4050",
+            EffectOut::Http(HttpResponse {
+                status: 200,
+                headers: std::collections::HashMap::new(),
+                body: bytes::Bytes::from_static(body),
+            }),
+        );
+        let (mut state, _, _) = machine.init();
+        machine.poll(&effects, &mut state).await.unwrap();
+        let ten_min_ago = std::time::Instant::now() - std::time::Duration::from_secs(600);
+        state.unit.last_successful_poll = Some(ten_min_ago);
+        state.unit.last_attempt_poll = Some(ten_min_ago);
+        // The refresh fails (nothing canned), but the old forecast stays up.
+        let stale = machine
+            .poll(&MockEffects::default(), &mut state)
+            .await
+            .unwrap();
+        let (Availability::ReadyView(view), _) = machine.on_poll_ok(&mut state, stale) else {
+            panic!("expected a view");
+        };
+        assert_eq!(plain(&view), "weather [☁️10°C▲] (10m)");
+        assert_eq!(view.health, Health::Degraded);
+    }
+
+    #[tokio::test]
+    async fn middle_click_cycles_locations() {
+        use crate::core::ClickEvent;
+        use crate::machine::types::UnitMachine;
+        use crate::units::testing::click;
+        let machine = WeatherMachine::new(
+            toml::from_str(
+                r#"
+                locations = [
+                    { lat = 52.52, lon = 13.405, label = "Home" },
+                    { lat = 52.5, lon = 13.4, label = "Work" },
+                ]
+                "#,
+            )
+            .unwrap(),
+        );
+        let body = bytes::Bytes::from_static(include_bytes!(
+            "../../../tests/fixtures/open_meteo_berlin.json"
+        ));
+        let effects = MockEffects::default()
+            .with(
+                "open-meteo:52.5200:13.4050",
+                EffectOut::Http(HttpResponse {
+                    status: 200,
+                    headers: std::collections::HashMap::new(),
+                    body: body.clone(),
+                }),
+            )
+            .with(
+                "open-meteo:52.5000:13.4000",
+                EffectOut::Http(HttpResponse {
+                    status: 200,
+                    headers: std::collections::HashMap::new(),
+                    body,
+                }),
+            );
+        let (mut state, _, _) = machine.init();
+        let home = machine.poll(&effects, &mut state).await.unwrap();
+        assert_eq!(plain(&home), "weather [Home ☁️10°C▲]");
+        machine.on_click(&mut state, click(ClickEvent::BUTTON_MIDDLE));
+        let work = machine.poll(&effects, &mut state).await.unwrap();
+        assert_eq!(plain(&work), "weather [Work ☁️10°C▲]");
+        machine.on_click(&mut state, click(ClickEvent::BUTTON_MIDDLE));
+        // Back home from the parked response, without another fetch.
+        let again = machine
+            .poll(&MockEffects::default(), &mut state)
+            .await
+            .unwrap();
+        assert_eq!(plain(&again), "weather [Home ☁️10°C▲]");
+    }
+
+    #[tokio::test]
+    async fn retry_after_defers_the_next_fetch() {
+        use crate::machine::types::UnitMachine;
+        let machine = WeatherMachine::new(toml::from_str("lat = 52.52\nlon = 13.405").unwrap());
+        let body = include_bytes!("../../../tests/fixtures/open_meteo_berlin.json");
+        let effects = MockEffects::default().with(
+            "open-meteo:52.5200:13.4050",
+            EffectOut::Http(HttpResponse {
+                status: 200,
+                headers: [("retry-after".to_string(), "600".to_string())].into(),
+                body: bytes::Bytes::from_static(body),
+            }),
+        );
+        let (mut state, _, _) = machine.init();
+        machine.poll(&effects, &mut state).await.unwrap();
+        // Due for a refresh, but the server asked for a pause: an empty mock
+        // would fail any fetch, so this renders from the kept response.
+        state.unit.last_successful_poll = None;
+        state.unit.last_attempt_poll = None;
+        let again = machine
+            .poll(&MockEffects::default(), &mut state)
+            .await
+            .unwrap();
+        assert_eq!(plain(&again), "weather [☁️10°C▲]");
     }
 }
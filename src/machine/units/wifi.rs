@@ -1,4 +1,4 @@
-use crate::machine::types::{Availability, Health, UnitDecision, UnitMachine, View};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
 use crate::render::markup::Markup;
 use crate::units::wifi::{Wifi, WifiConfig};
 
@@ -30,7 +30,7 @@ impl std::fmt::Display for UnitErr {
 impl std::error::Error for UnitErr {}
 
 impl UnitMachine for WifiMachine {
-    type PollOut = Markup;
+    type PollOut = View;
     type State = State;
     type UnitError = UnitErr;
 
@@ -40,15 +40,16 @@ impl UnitMachine for WifiMachine {
 
     fn init(&self) -> (Self::State, View, UnitDecision) {
         let unit = Wifi::from_cfg(self.cfg.clone());
-        Wifi::fix_up_and_validate();
-        let view = View {
-            body: Markup::text("wifi ") + Markup::text("loading").fg(crate::core::VIOLET),
-            health: Health::Degraded,
-        };
-
+        let view = View::degraded(
+            Markup::label("wifi ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
         (State { unit }, view, UnitDecision::PollNow)
     }
 
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
     fn on_tick(&self, _state: &mut Self::State) -> (Option<View>, UnitDecision) {
         (None, UnitDecision::Idle)
     }
@@ -59,12 +60,12 @@ impl UnitMachine for WifiMachine {
         click: crate::core::ClickEvent,
     ) -> (Option<View>, UnitDecision) {
         state.unit.handle_click(click);
-        (None, UnitDecision::PollNow)
+        (state.unit.render(), UnitDecision::PollNow)
     }
 
     async fn poll(
         &self,
-        _effects: &crate::machine::effects::EffectEngine,
+        _effects: &impl crate::machine::effects::Effects,
         state: &mut Self::State,
     ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
         Ok(state.unit.read_markup())
@@ -73,11 +74,11 @@ impl UnitMachine for WifiMachine {
     fn on_poll_ok(
         &self,
         _state: &mut Self::State,
-        body: Self::PollOut,
+        view: Self::PollOut,
     ) -> (
         Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
         UnitDecision,
     ) {
-        (Availability::Ready(body), UnitDecision::Idle)
+        (Availability::ReadyView(view), UnitDecision::Idle)
     }
 }
@@ -0,0 +1,121 @@
+use crate::machine::effects::{EffectReq, ProcBatch, ProcKey, ProcOutput, ProcRun};
+use crate::machine::types::{Availability, UnitDecision, UnitMachine, View};
+use crate::render::markup::Markup;
+use crate::units::window::{Window, WindowConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct WindowMachine {
+    cfg: WindowConfig,
+}
+
+impl WindowMachine {
+    pub fn new(cfg: WindowConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    unit: Window,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitErr(String);
+
+impl std::fmt::Display for UnitErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnitErr {}
+
+impl UnitMachine for WindowMachine {
+    type PollOut = Markup;
+    type State = State;
+    type UnitError = UnitErr;
+
+    fn name(&self) -> &'static str {
+        "Window"
+    }
+
+    fn init(&self) -> (Self::State, View, UnitDecision) {
+        let unit = Window::from_cfg(self.cfg.clone());
+        let view = View::degraded(
+            Markup::label("win ") + Markup::text("loading").fg(crate::core::theme().loading),
+        );
+        (State { unit }, view, UnitDecision::PollNow)
+    }
+
+    fn validate(&self, state: &mut Self::State) -> anyhow::Result<()> {
+        state.unit.fix_up_and_validate()
+    }
+
+    fn on_tick(&self, state: &mut Self::State) -> (Option<View>, UnitDecision) {
+        // Draining a live subscription is cheap, so follow focus changes at tick
+        // rate. Until it is up (e.g. no i3/sway), fall back to `poll_interval`.
+        if state.unit.is_primed() {
+            (None, UnitDecision::PollNow)
+        } else {
+            (None, UnitDecision::Idle)
+        }
+    }
+
+    fn on_click(
+        &self,
+        _state: &mut Self::State,
+        click: crate::core::ClickEvent,
+    ) -> (Option<View>, UnitDecision) {
+        Window::handle_click(click);
+        (None, UnitDecision::Idle)
+    }
+
+    async fn poll(
+        &self,
+        effects: &impl crate::machine::effects::Effects,
+        state: &mut Self::State,
+    ) -> Result<Self::PollOut, crate::machine::types::PollError<Self::UnitError>> {
+        let lines = effects
+            .run(EffectReq::ProcBatch(ProcBatch {
+                key: ProcKey::new("window:subscribe"),
+                cmd: state.unit.subscribe_cmd(),
+                max_lines: 64,
+                max_runtime: None,
+                max_idle: None,
+            }))
+            .await?
+            .expect::<Vec<String>>()?;
+
+        // Query after subscribing so no event between the two is lost.
+        if !state.unit.is_primed() {
+            let out = effects
+                .run(EffectReq::ProcRun(ProcRun {
+                    key: ProcKey::new("window:initial"),
+                    cmd: state.unit.initial_cmd(),
+                    env: Vec::new(),
+                    timeout: Duration::from_secs(2),
+                    cache_fresh_for: Duration::ZERO,
+                }))
+                .await?
+                .expect::<ProcOutput>()?;
+            state.unit.apply_initial(&out.stdout);
+        }
+
+        for line in &lines {
+            state.unit.apply_event(line);
+        }
+        Ok(state.unit.read_markup())
+    }
+
+    fn on_poll_ok(
+        &self,
+        _state: &mut Self::State,
+        body: Self::PollOut,
+    ) -> (
+        Availability<Markup, crate::machine::types::PollError<Self::UnitError>>,
+        UnitDecision,
+    ) {
+        (Availability::Ready(body), UnitDecision::Idle)
+    }
+}
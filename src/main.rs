@@ -4,12 +4,15 @@ mod config;
 mod config_tests;
 mod core;
 mod display;
+mod i3bar;
 mod machine;
 mod render;
 mod units;
 mod util;
 
 use anyhow::Result;
+use std::ffi::OsString;
+use std::path::PathBuf;
 use tracing::{info, level_filters::LevelFilter};
 use tracing_appender::{
     non_blocking,
@@ -17,7 +20,7 @@ use tracing_appender::{
 };
 use tracing_subscriber::{fmt, EnvFilter};
 
-use crate::config::load_status_from_cfg;
+use crate::config::{check_cfg, dump_units_from_cfg, init_cfg, load_status_from_cfg};
 
 fn init_file_logger() -> Option<non_blocking::WorkerGuard> {
     let bd = xdg::BaseDirectories::with_prefix("empty-status");
@@ -40,11 +43,110 @@ fn init_file_logger() -> Option<non_blocking::WorkerGuard> {
     Some(guard) // must be held for the lifetime of the program so it can flush
 }
 
+const USAGE: &str = "usage: empty-status [--config <path>] [--init | --check | --dump]";
+
+/// Command-line arguments, parsed in one pass so that `--config <path>`
+/// always consumes its path, whatever it looks like.
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    /// From `--config <path>` (or `--config=<path>`); bypasses the XDG lookup.
+    config: Option<PathBuf>,
+    init: bool,
+    check: bool,
+    dump: bool,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = OsString>) -> std::result::Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--config" {
+                let path = args.next().ok_or("--config needs a path")?;
+                parsed.config = Some(path.into());
+            } else if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--config=")) {
+                parsed.config = Some(path.into());
+            } else if arg == "--init" {
+                parsed.init = true;
+            } else if arg == "--check" {
+                parsed.check = true;
+            } else if arg == "--dump" {
+                parsed.dump = true;
+            } else {
+                return Err(format!("unknown argument {arg:?}"));
+            }
+        }
+        Ok(parsed)
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let _guard = init_file_logger();
+    let args = Args::parse(std::env::args_os().skip(1)).unwrap_or_else(|e| {
+        eprintln!("empty-status: {e}\n{USAGE}");
+        std::process::exit(2);
+    });
+    let config = args
+        .config
+        .or_else(|| std::env::var_os("EMPTY_STATUS_CONFIG").map(PathBuf::from));
+    let config = config.as_deref();
+    let dump = args.dump || std::env::var_os("EMPTY_STATUS_DUMP").is_some();
+    if args.init {
+        let path = init_cfg(config)?;
+        eprintln!("wrote sample config to {}", path.display());
+        return Ok(());
+    }
+    if args.check {
+        let (path, problems) = check_cfg(config)?;
+        for problem in &problems {
+            eprintln!("{}: {problem}", path.display());
+        }
+        if !problems.is_empty() {
+            std::process::exit(1);
+        }
+        eprintln!("{}: ok", path.display());
+        return Ok(());
+    }
+    if dump {
+        info!("Dumping every unit once");
+        let dumps = dump_units_from_cfg(config).await?;
+        println!("{}", serde_json::to_string_pretty(&dumps)?);
+        return Ok(());
+    }
+
     info!("Starting empty-status!");
-    let status = load_status_from_cfg()?;
+    let status = load_status_from_cfg(config)?;
     status.run().await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Args;
+    use std::ffi::OsString;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn config_always_takes_the_next_argument() {
+        let args = parse(&["--config", "--check"]).unwrap();
+        assert_eq!(args.config.as_deref(), Some("--check".as_ref()));
+        assert!(!args.check);
+
+        let args = parse(&["--check", "--config=a.toml"]).unwrap();
+        assert_eq!(args.config.as_deref(), Some("a.toml".as_ref()));
+        assert!(args.check);
+    }
+
+    #[test]
+    fn unknown_arguments_are_rejected() {
+        assert_eq!(
+            parse(&["--chek"]),
+            Err("unknown argument \"--chek\"".to_string())
+        );
+        assert!(parse(&["--config"]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::{Color, Theme};
 use palette::{Clamp, FromColor, Oklab, Srgb};
 
 #[allow(dead_code)]
@@ -8,22 +9,31 @@ pub struct Srgb8 {
     pub b: u8,
 }
 
-impl From<&str> for Srgb8 {
-    fn from(value: &str) -> Self {
+impl Srgb8 {
+    /// Parses `#RRGGBB` (the `#` is optional).
+    fn parse_hex(value: &str) -> Option<Self> {
         let value = value.strip_prefix('#').unwrap_or(value);
         if value.len() != 6 {
-            return Self::new(0, 0, 0);
+            return None;
         }
-        let Ok(r) = u8::from_str_radix(&value[0..2], 16) else {
-            return Self::new(0, 0, 0);
-        };
-        let Ok(g) = u8::from_str_radix(&value[2..4], 16) else {
-            return Self::new(0, 0, 0);
-        };
-        let Ok(b) = u8::from_str_radix(&value[4..6], 16) else {
-            return Self::new(0, 0, 0);
-        };
-        Self::new(r, g, b)
+        let channel = |ix: usize| u8::from_str_radix(value.get(ix..ix + 2)?, 16).ok();
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// Unparseable input maps to black; config goes through `Deserialize`, which
+/// rejects it instead.
+impl From<&str> for Srgb8 {
+    fn from(value: &str) -> Self {
+        Self::parse_hex(value).unwrap_or(Self::new(0, 0, 0))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Srgb8 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse_hex(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("expected a #RRGGBB color, got {s:?}")))
     }
 }
 
@@ -40,6 +50,42 @@ impl Srgb8 {
     }
 }
 
+/// A color as a unit asks for it: a palette slot, looked up in the theme
+/// only when rendered, or an exact value (gradients, user config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paint {
+    Palette(Color),
+    Rgb(Srgb8),
+}
+
+impl Paint {
+    #[must_use]
+    pub fn resolve(self, palette: &Theme) -> Srgb8 {
+        match self {
+            Self::Palette(color) => palette.get(color),
+            Self::Rgb(rgb) => rgb,
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Self::Palette(color)
+    }
+}
+
+impl From<Srgb8> for Paint {
+    fn from(rgb: Srgb8) -> Self {
+        Self::Rgb(rgb)
+    }
+}
+
+impl From<&str> for Paint {
+    fn from(hex: &str) -> Self {
+        Self::Rgb(hex.into())
+    }
+}
+
 #[allow(dead_code)]
 impl Srgb8 {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
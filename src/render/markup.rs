@@ -1,3 +1,5 @@
+use crate::render::color::Paint;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Markup {
     spans: Vec<Span>,
@@ -16,6 +18,24 @@ impl Markup {
         }
     }
 
+    /// A unit's name ahead of its value, e.g. `cpu `. Rendered like
+    /// [`Markup::text`], but dropped in compact mode.
+    #[must_use]
+    pub fn label(text: impl Into<String>) -> Self {
+        Self {
+            spans: vec![Span::Label(text.into())],
+        }
+    }
+
+    /// Pango markup passed through unescaped. Only for trusted sources: a stray
+    /// `<` breaks the whole status line.
+    #[must_use]
+    pub fn trusted_pango(pango: impl Into<String>) -> Self {
+        Self {
+            spans: vec![Span::TrustedPango(pango.into())],
+        }
+    }
+
     #[must_use]
     pub fn styled(style: Style, inner: Self) -> Self {
         Self {
@@ -24,11 +44,14 @@ impl Markup {
     }
 
     #[must_use]
-    pub fn fg(self, fg: impl Into<crate::render::color::Srgb8>) -> Self {
+    pub fn fg(self, fg: impl Into<Paint>) -> Self {
         Self::styled(Style::default().fg(fg), self)
     }
 
-    // Intentionally omitted for now: we have no background use-sites yet.
+    #[must_use]
+    pub fn bg(self, bg: impl Into<Paint>) -> Self {
+        Self::styled(Style::default().bg(bg), self)
+    }
 
     #[must_use]
     pub fn append(mut self, other: Self) -> Self {
@@ -63,6 +86,78 @@ impl Markup {
     pub fn spans(&self) -> &[Span] {
         &self.spans
     }
+
+    /// This markup with every [`Markup::label`] span removed.
+    #[must_use]
+    pub fn without_labels(&self) -> Self {
+        let spans = self
+            .spans
+            .iter()
+            .filter_map(|span| match span {
+                Span::Label(_) => None,
+                Span::Styled(style, inner) => Some(Span::Styled(*style, inner.without_labels())),
+                other => Some(other.clone()),
+            })
+            .collect();
+        Self { spans }
+    }
+
+    /// This markup with its first [`Markup::label`] span replaced by `label`
+    /// and a separating space (nothing for an empty `label`). Markup without a
+    /// label gets one in front.
+    #[must_use]
+    pub fn relabeled(&self, label: &str) -> Self {
+        let replacement = if label.is_empty() {
+            String::new()
+        } else {
+            format!("{label} ")
+        };
+        let mut pending = Some(replacement);
+        let out = self.relabel_first(&mut pending);
+        match pending {
+            Some(label) => Self::label(label).append(out),
+            None => out,
+        }
+    }
+
+    fn relabel_first(&self, pending: &mut Option<String>) -> Self {
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| match span {
+                Span::Label(_) if pending.is_some() => {
+                    Span::Label(pending.take().unwrap_or_default())
+                }
+                Span::Styled(style, inner) if pending.is_some() => {
+                    Span::Styled(*style, inner.relabel_first(pending))
+                }
+                other => other.clone(),
+            })
+            .collect();
+        Self { spans }
+    }
+
+    /// The text content, with all styling (and trusted pango's tags) dropped.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        let mut out = String::new();
+        for span in &self.spans {
+            match span {
+                Span::Text(text) | Span::Label(text) => out.push_str(text),
+                Span::TrustedPango(pango) => {
+                    out.push_str(&crate::render::pango::strip_pango(pango));
+                }
+                Span::Styled(_, inner) => out.push_str(&inner.plain_text()),
+            }
+        }
+        out
+    }
+}
+
+impl AsRef<Markup> for Markup {
+    fn as_ref(&self) -> &Markup {
+        self
+    }
 }
 
 impl Default for Markup {
@@ -74,25 +169,33 @@ impl Default for Markup {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Span {
     Text(String),
+    Label(String),
+    TrustedPango(String),
     Styled(Style, Markup),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub struct Style {
-    pub fg: Option<crate::render::color::Srgb8>,
-    pub bg: Option<crate::render::color::Srgb8>,
+    pub fg: Option<Paint>,
+    pub bg: Option<Paint>,
 }
 
 impl Style {
     #[must_use]
-    pub fn fg(self, fg: impl Into<crate::render::color::Srgb8>) -> Self {
+    pub fn fg(self, fg: impl Into<Paint>) -> Self {
         Self {
             fg: Some(fg.into()),
             ..self
         }
     }
 
-    // Intentionally omitted for now: we have no background use-sites yet.
+    #[must_use]
+    pub fn bg(self, bg: impl Into<Paint>) -> Self {
+        Self {
+            bg: Some(bg.into()),
+            ..self
+        }
+    }
 }
 
 impl From<&str> for Markup {
@@ -114,9 +217,3 @@ impl std::ops::Add for Markup {
         self.append(rhs)
     }
 }
-
-impl std::fmt::Display for Markup {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&crate::render::pango::to_pango(self))
-    }
-}
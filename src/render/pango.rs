@@ -1,26 +1,27 @@
+use crate::core::Theme;
 use crate::render::markup::{Markup, Span, Style};
 
-#[allow(dead_code)]
-pub fn to_pango(markup: &Markup) -> String {
-    let mut out = String::new();
-    for span in markup.spans() {
-        match span {
-            Span::Text(text) => out.push_str(&render_text(text, Style::default())),
-            Span::Styled(style, inner) => {
-                out.push_str(&render_styled(*style, inner));
-            }
-        }
-    }
-    out
+/// `markup` as pango, with palette colors looked up in `palette`.
+pub fn to_pango(markup: &Markup, palette: &Theme) -> String {
+    render_styled(Style::default(), markup, palette)
 }
 
-fn render_styled(style: Style, inner: &Markup) -> String {
+fn render_styled(style: Style, inner: &Markup, palette: &Theme) -> String {
     let mut out = String::new();
     for span in inner.spans() {
         match span {
-            Span::Text(text) => out.push_str(&render_text(text, style)),
+            Span::Text(text) | Span::Label(text) => {
+                out.push_str(&render_text(text, style, palette));
+            }
+            Span::TrustedPango(pango) => {
+                out.push_str(&render_attrs(pango.clone(), style, palette));
+            }
             Span::Styled(child_style, child_inner) => {
-                out.push_str(&render_styled(merge(style, *child_style), child_inner));
+                out.push_str(&render_styled(
+                    merge(style, *child_style),
+                    child_inner,
+                    palette,
+                ));
             }
         }
     }
@@ -34,14 +35,17 @@ fn merge(outer: Style, inner: Style) -> Style {
     }
 }
 
-fn render_text(text: &str, style: Style) -> String {
-    let text = escape_pango(text);
+fn render_text(text: &str, style: Style, palette: &Theme) -> String {
+    render_attrs(escape_pango(text), style, palette)
+}
+
+fn render_attrs(text: String, style: Style, palette: &Theme) -> String {
     let mut attrs = Vec::new();
     if let Some(fg) = style.fg {
-        attrs.push(format!("color='{}'", fg.to_hex()));
+        attrs.push(format!("color='{}'", fg.resolve(palette).to_hex()));
     }
     if let Some(bg) = style.bg {
-        attrs.push(format!("background='{}'", bg.to_hex()));
+        attrs.push(format!("background='{}'", bg.resolve(palette).to_hex()));
     }
     if attrs.is_empty() {
         text
@@ -65,15 +69,88 @@ fn escape_pango(text: &str) -> String {
     out
 }
 
+/// The text of trusted pango markup: tags dropped and the entities
+/// [`escape_pango`] produces decoded, so plain renderers don't leak `<b>`.
+pub fn strip_pango(pango: &str) -> String {
+    let mut out = String::with_capacity(pango.len());
+    let mut rest = pango;
+    while let Some(ix) = rest.find(['<', '&']) {
+        out.push_str(&rest[..ix]);
+        rest = &rest[ix..];
+        if rest.starts_with('<') {
+            match rest.find('>') {
+                Some(end) => rest = &rest[end + 1..],
+                // An unterminated tag: pango would reject it, show it as is.
+                None => break,
+            }
+            continue;
+        }
+        let entity = [
+            ("&amp;", '&'),
+            ("&lt;", '<'),
+            ("&gt;", '>'),
+            ("&apos;", '\''),
+            ("&quot;", '"'),
+        ]
+        .into_iter()
+        .find(|(name, _)| rest.starts_with(name));
+        match entity {
+            Some((name, c)) => {
+                out.push(c);
+                rest = &rest[name.len()..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 #[cfg(test)]
 mod tests {
-    use super::to_pango;
+    use super::{strip_pango, to_pango};
+    use crate::core::{Color, Theme};
     use crate::render::markup::Markup;
 
     #[test]
     fn escapes_text() {
         let m = Markup::text("<&>\"'");
-        let out = to_pango(&m);
+        let out = to_pango(&m, &Theme::default());
         assert_eq!(out, "&lt;&amp;&gt;&quot;&apos;");
     }
+
+    #[test]
+    fn trusted_pango_is_not_escaped() {
+        let m = Markup::text("<") + Markup::trusted_pango("<b>x</b>").fg(crate::core::RED);
+        let out = to_pango(&m, &Theme::default());
+        assert_eq!(out, "&lt;<span color='#CC6666'><b>x</b></span>");
+    }
+
+    #[test]
+    fn palette_colors_resolve_against_the_given_theme() {
+        let m = Markup::text("up").fg(Color::Green);
+        let neon = Theme {
+            green: "#00FF00".into(),
+            ..Theme::default()
+        };
+        assert_eq!(
+            to_pango(&m, &Theme::default()),
+            "<span color='#B5BD68'>up</span>"
+        );
+        assert_eq!(to_pango(&m, &neon), "<span color='#00FF00'>up</span>");
+    }
+
+    #[test]
+    fn stripped_pango_keeps_only_text() {
+        assert_eq!(
+            strip_pango("<b>up</b> <span color='red'>1 &lt; 2 &amp; x</span>"),
+            "up 1 < 2 & x"
+        );
+        assert_eq!(strip_pango("a & b <i"), "a & b <i");
+        let m = Markup::text("<") + Markup::trusted_pango("<b>x</b>");
+        assert_eq!(m.plain_text(), "<x");
+    }
 }
@@ -1,8 +1,22 @@
 pub mod bat;
+pub mod clipboard;
+pub mod command;
 pub mod cpu;
 pub mod disk;
+pub mod file;
+pub mod http;
+pub mod load;
+pub mod locks;
 pub mod mem;
+pub mod mic;
 pub mod net;
+pub mod spacer;
+#[cfg(test)]
+mod sysfs_tests;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod time;
+pub mod uptime;
 pub mod weather;
 pub mod wifi;
+pub mod window;
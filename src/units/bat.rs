@@ -1,5 +1,6 @@
-use crate::core::{BLUE, CYAN, GREEN, ORANGE, RED, VIOLET};
+use crate::core::{theme, Color};
 use crate::display::color_by_pct_rev;
+use crate::machine::types::{Health, HealthThresholds, View};
 use crate::mode_enum;
 use crate::render::markup::Markup;
 use crate::util::{Ema, Smoother};
@@ -28,29 +29,66 @@ impl BatStatus {
                 "charging" => Self::Charging,
                 "discharging" => Self::Discharging,
                 "full" => Self::Full,
+                // Plugged in but held by a charge threshold (ThinkPads et al.).
+                "not charging" => Self::Balanced,
                 "unknown" => Self::Unknown,
                 _ => Self::Other,
             },
             None => Self::Other,
         }
     }
+    /// upower's `state:` values.
+    fn from_upower(state: &str) -> Self {
+        match state {
+            "charging" => Self::Charging,
+            "discharging" | "pending-discharge" => Self::Discharging,
+            "fully-charged" => Self::Full,
+            "pending-charge" => Self::Balanced,
+            "unknown" => Self::Unknown,
+            _ => Self::Other,
+        }
+    }
+
     pub fn state_markup(self) -> Markup {
         match self {
-            Self::Discharging => Markup::text("DIS").fg(ORANGE),
-            Self::Charging => Markup::text("CHR").fg(GREEN),
-            Self::Full => Markup::text("FUL").fg(CYAN),
-            Self::Balanced => Markup::text("BAL").fg(BLUE),
-            _ => Markup::text("UNK").fg(VIOLET),
+            Self::Discharging => Markup::text("DIS").fg(Color::Orange),
+            Self::Charging => Markup::text("CHR").fg(Color::Green),
+            Self::Full => Markup::text("FUL").fg(Color::Cyan),
+            Self::Balanced => Markup::text("BAL").fg(Color::Blue),
+            _ => Markup::text("UNK").fg(Color::Violet),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatBackend {
+    /// `/sys/class/power_supply/BAT<bat_id>/uevent`.
+    Sysfs,
+    /// `upower -i <upower_device>`, which normalizes charge and time estimates.
+    Upower,
+}
+
 #[serde_inline_default]
 #[derive(Debug, Clone, Deserialize)]
 pub struct BatConfig {
     pub bat_id: usize,
+    #[serde_inline_default(BatBackend::Sysfs)]
+    pub backend: BatBackend,
+    /// upower object path; defaults to `battery_BAT<bat_id>`.
+    #[serde(default)]
+    pub upower_device: Option<String>,
     #[serde_inline_default(2.5)]
     pub power_smoothing_sec: f64,
+    /// Discharging at or below this charge (percent) marks the segment urgent.
+    #[serde_inline_default(5.0)]
+    pub critical_pct: f64,
+    /// While critical, alternate the segment background every poll.
+    #[serde_inline_default(false)]
+    pub flash_critical: bool,
+    /// Charge (percent) levels that mark a discharging battery degraded/error.
+    #[serde(default)]
+    pub level_health: HealthThresholds,
 }
 
 #[derive(Debug)]
@@ -59,18 +97,36 @@ pub struct Bat {
     mode: DisplayMode,
     cur_status: BatStatus,
     uevent_path: String,
+    threshold_path: String,
+    charge_threshold: Option<u8>,
+    /// Name of the mains adapter under `/sys/class/power_supply`, if any.
+    ac_supply: Option<String>,
+    ac_online: Option<bool>,
     power_ema: Ema<f64>,
+    /// Background phase of the critical flash, flipped each critical reading.
+    flash_on: bool,
+    last: Option<Reading>,
 }
 
 impl Bat {
     pub fn from_cfg(cfg: BatConfig) -> Self {
         // TODO seems fragile? use a crate etc.
         let uevent_path = format!("/sys/class/power_supply/BAT{}/uevent", cfg.bat_id);
+        let threshold_path = format!(
+            "/sys/class/power_supply/BAT{}/charge_control_end_threshold",
+            cfg.bat_id
+        );
         Self {
             mode: DisplayMode::CurCapacity,
             cur_status: BatStatus::Unknown,
             uevent_path,
+            threshold_path,
+            charge_threshold: None,
+            ac_supply: None,
+            ac_online: None,
             power_ema: Ema::new(cfg.power_smoothing_sec),
+            flash_on: false,
+            last: None,
             cfg,
         }
     }
@@ -94,6 +150,19 @@ impl Bat {
 
 const UH_TO_SI: f64 = 0.0036;
 
+/// Backend-independent inputs to [`Bat::render`], kept so a mode switch can
+/// re-render without a new reading.
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    charged_pct: f64,
+    /// Charge relative to the design (as-new) capacity.
+    design_pct: f64,
+    status: BatStatus,
+    /// Smoothed power draw (W).
+    power_w: f64,
+    sec_rem: Option<f64>,
+}
+
 pub struct BatteryInfo {
     pub charged_frac: f64,
     pub charged_frac_design: f64,
@@ -111,6 +180,12 @@ impl BatteryInfo {
         let voltage_min_design = u.get("voltage_min_design")?.parse::<i64>().ok()?;
         let current_now = u.get("current_now")?.parse::<i64>().ok()?;
 
+        // The model below divides by the current and full charges; a depleted or
+        // freshly reset battery reports zeros there.
+        if charge_now <= 0 || charge_full <= 0 || charge_full_design <= 0 {
+            return None;
+        }
+
         let q = UH_TO_SI * charge_now as f64;
         let qmx = UH_TO_SI * charge_full as f64;
         let qmxd = UH_TO_SI * charge_full_design as f64;
@@ -132,13 +207,14 @@ impl BatteryInfo {
         let charged_frac = energy / energy_max;
         let charged_frac_design = energy / energy_max_design;
 
-        Some(Self {
+        Self {
             charged_frac,
             charged_frac_design,
             power,
             energy,
             energy_max,
-        })
+        }
+        .finite()
     }
 
     pub fn from_energy(u: &HashMap<String, String>) -> Option<Self> {
@@ -147,6 +223,10 @@ impl BatteryInfo {
         let energy_full_design = u.get("energy_full_design")?.parse::<i64>().ok()?;
         let power_now = u.get("power_now")?.parse::<i64>().ok()?;
 
+        if energy_full <= 0 || energy_full_design <= 0 {
+            return None;
+        }
+
         let energy = UH_TO_SI * energy_now as f64;
         let energy_max = UH_TO_SI * energy_full as f64;
         let energy_max_design = UH_TO_SI * energy_full_design as f64;
@@ -155,18 +235,39 @@ impl BatteryInfo {
         let charged_frac = energy / energy_max;
         let charged_frac_design = energy / energy_max_design;
 
-        Some(Self {
+        Self {
             charged_frac,
             charged_frac_design,
             power,
             energy,
             energy_max,
-        })
+        }
+        .finite()
+    }
+
+    fn finite(self) -> Option<Self> {
+        [
+            self.charged_frac,
+            self.charged_frac_design,
+            self.power,
+            self.energy,
+            self.energy_max,
+        ]
+        .iter()
+        .all(|v| v.is_finite())
+        .then_some(self)
     }
 }
 
 impl Bat {
-    pub fn read_markup_from_bytes(&mut self, bytes: &[u8]) -> Markup {
+    /// Renders from raw `uevent` contents. Pure given bytes, apart from the
+    /// wall-clock timestamp fed to the power smoother.
+    pub fn read_markup_from_bytes(&mut self, bytes: &[u8]) -> View {
+        self.read_markup_from_bytes_at(bytes, Instant::now())
+    }
+
+    /// As [`Self::read_markup_from_bytes`], sampled at `now`.
+    pub fn read_markup_from_bytes_at(&mut self, bytes: &[u8], now: Instant) -> View {
         let mut missing = false;
         let uevent = if let Ok(map) = Self::parse_uevent_bytes(bytes) {
             map
@@ -176,39 +277,29 @@ impl Bat {
         };
 
         if missing || uevent.get("present").is_some_and(|v| v == "0") {
-            return Markup::text("No battery").fg(RED);
+            self.last = None;
+            return View::error(Markup::text("No battery").fg(theme().error));
         }
 
         let bi =
             match BatteryInfo::from_charge(&uevent).or_else(|| BatteryInfo::from_energy(&uevent)) {
                 Some(bi) => bi,
                 None => {
-                    return Markup::text("invalid data").fg(RED);
+                    self.last = None;
+                    return View::error(Markup::text("invalid data").fg(theme().error));
                 }
             };
 
         let p_smooth = *self
             .power_ema
-            .feed_and_read(bi.power, Instant::now())
+            .feed_and_read(bi.power, now)
             .unwrap_or(&bi.power);
 
-        let pct = if self.mode == DisplayMode::DesignCapacity {
-            100.0 * bi.charged_frac_design
-        } else {
-            100.0 * bi.charged_frac
-        };
-
-        let pct_str = Markup::text(format!("{pct:3.0}")).fg(color_by_pct_rev(pct));
-
         let mut bs = BatStatus::from_uevent(&uevent);
         if bs == BatStatus::Other && p_smooth == 0.0 {
             bs = BatStatus::Balanced;
         }
-
-        if bs != self.cur_status {
-            self.cur_status = bs;
-            self.power_ema = Ema::new(self.cfg.power_smoothing_sec);
-        }
+        self.settle_status(bs);
 
         let sec_rem: Option<f64> = match bs {
             BatStatus::Charging => {
@@ -227,6 +318,117 @@ impl Bat {
             }
             _ => None,
         };
+        self.show(Reading {
+            charged_pct: 100.0 * bi.charged_frac,
+            design_pct: 100.0 * bi.charged_frac_design,
+            status: bs,
+            power_w: p_smooth,
+            sec_rem,
+        })
+    }
+
+    /// Renders from `upower -i <device>` output. upower supplies the
+    /// percentage, state and time estimates; only the power draw is smoothed.
+    pub fn read_markup_from_upower(&mut self, stdout: &[u8]) -> View {
+        self.read_markup_from_upower_at(stdout, Instant::now())
+    }
+
+    /// As [`Self::read_markup_from_upower`], sampled at `now`.
+    pub fn read_markup_from_upower_at(&mut self, stdout: &[u8], now: Instant) -> View {
+        let props: HashMap<&str, &str> = std::str::from_utf8(stdout)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .collect();
+        if props.get("present").is_none_or(|v| *v != "yes") {
+            self.last = None;
+            return View::error(Markup::text("No battery").fg(theme().error));
+        }
+        let number = |key: &str, suffix: &str| {
+            props
+                .get(key)
+                .and_then(|v| v.strip_suffix(suffix))
+                .and_then(|v| v.trim().parse::<f64>().ok())
+        };
+        let Some(charged_pct) = number("percentage", "%") else {
+            self.last = None;
+            return View::error(Markup::text("invalid data").fg(theme().error));
+        };
+        let capacity_pct = number("capacity", "%").unwrap_or(100.0);
+        let power = number("energy-rate", "W").unwrap_or(0.0);
+
+        let bs = props
+            .get("state")
+            .map_or(BatStatus::Other, |s| BatStatus::from_upower(s));
+        self.settle_status(bs);
+        let p_smooth = *self.power_ema.feed_and_read(power, now).unwrap_or(&power);
+
+        let sec_rem = match bs {
+            BatStatus::Charging => props.get("time to full"),
+            BatStatus::Discharging => props.get("time to empty"),
+            _ => None,
+        }
+        .and_then(|v| Self::parse_upower_duration(v));
+        self.show(Reading {
+            charged_pct,
+            design_pct: charged_pct * capacity_pct / 100.0,
+            status: bs,
+            power_w: p_smooth,
+            sec_rem,
+        })
+    }
+
+    /// upower durations such as `3.6 hours` or `42.0 minutes`, in seconds.
+    fn parse_upower_duration(v: &str) -> Option<f64> {
+        let (n, unit) = v.split_once(' ')?;
+        let n = n.parse::<f64>().ok()?;
+        let scale = match unit {
+            "seconds" => 1.0,
+            "minutes" => 60.0,
+            "hours" => 3600.0,
+            "days" => 86_400.0,
+            _ => return None,
+        };
+        Some(n * scale)
+    }
+
+    /// A status change restarts power smoothing: charge and discharge rates
+    /// don't average meaningfully.
+    fn settle_status(&mut self, bs: BatStatus) {
+        if bs != self.cur_status {
+            self.cur_status = bs;
+            self.power_ema = Ema::new(self.cfg.power_smoothing_sec);
+        }
+    }
+
+    fn show(&mut self, r: Reading) -> View {
+        let critical = r.status == BatStatus::Discharging && r.charged_pct <= self.cfg.critical_pct;
+        self.flash_on = critical && self.cfg.flash_critical && !self.flash_on;
+        self.last = Some(r);
+        self.render_reading(r)
+    }
+
+    /// The last reading in the current mode; `None` before the first one or
+    /// after a failed one.
+    pub fn render(&self) -> Option<View> {
+        self.last.map(|r| self.render_reading(r))
+    }
+
+    fn render_reading(&self, r: Reading) -> View {
+        let Reading {
+            charged_pct,
+            design_pct,
+            status: bs,
+            power_w: p_smooth,
+            sec_rem,
+        } = r;
+        let pct = if self.mode == DisplayMode::DesignCapacity {
+            design_pct
+        } else {
+            charged_pct
+        };
+        let pct_str = Markup::text(format!("{pct:3.0}")).fg(color_by_pct_rev(pct));
         let rem_string = match sec_rem {
             Some(sec) => {
                 let mins = (sec / 60.0).round() as i64;
@@ -240,25 +442,275 @@ impl Bat {
             DisplayMode::CurCapacity => ("[", "]"),
             DisplayMode::DesignCapacity => ("&lt;", "&gt;"),
         };
-        Markup::text("bat ")
-            .append(Markup::delimited(
-                br0,
-                pct_str.append(Markup::text("%")),
-                br1,
-            ))
-            .append(Markup::text(" "))
-            .append(bs.state_markup())
-            .append(Markup::text(format!(" {p_smooth:2.2} W ")))
-            .append(Markup::bracketed(Markup::text(format!("{rem_string} rem"))))
+        let discharging = bs == BatStatus::Discharging;
+        let critical = discharging && charged_pct <= self.cfg.critical_pct;
+        let health = if discharging {
+            self.cfg.level_health.below(charged_pct)
+        } else {
+            Health::Ok
+        };
+        View::ok(
+            Markup::label("bat ")
+                .append(Markup::delimited(
+                    br0,
+                    pct_str.append(Markup::text("%")),
+                    br1,
+                ))
+                .append(Markup::text(" "))
+                .append(bs.state_markup())
+                .append(self.ac_markup())
+                .append(self.threshold_markup())
+                .append(Markup::text(format!(" {p_smooth:2.2} W ")))
+                .append(Markup::bracketed(Markup::text(format!("{rem_string} rem")))),
+        )
+        .with_urgent(critical)
+        .with_health(health)
+        .with_background(self.flash_on.then_some(Color::Red))
+        .with_metric("battery_charge_percent", charged_pct)
+        .with_metric("battery_power_watts", p_smooth)
+    }
+
+    fn threshold_markup(&self) -> Markup {
+        match self.charge_threshold {
+            Some(t) if t < 100 => Markup::text(format!(" cap {t}%")).fg(Color::Grey),
+            _ => Markup::empty(),
+        }
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    /// Sets the charge cap from raw `charge_control_end_threshold` contents.
+    pub fn set_charge_threshold_from_bytes(&mut self, bytes: Option<&[u8]>) {
+        self.charge_threshold = bytes
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .and_then(|s| s.trim().parse::<u8>().ok());
     }
 
-    pub fn fix_up_and_validate() {}
+    fn ac_markup(&self) -> Markup {
+        match self.ac_online {
+            Some(true) => Markup::text(" ⚡").fg(Color::Yellow),
+            _ => Markup::empty(),
+        }
+    }
+
+    /// Picks the mains adapter (`AC*` or `ADP*`) from a `/sys/class/power_supply`
+    /// listing. Desktops without one simply never show the AC marker.
+    pub fn select_ac_supply(&mut self, entries: &[String]) {
+        if self.ac_supply.is_some() {
+            return;
+        }
+        self.ac_supply = entries
+            .iter()
+            .filter(|name| name.starts_with("AC") || name.starts_with("ADP"))
+            .min()
+            .cloned();
+    }
+
+    pub fn ac_online_path(&self) -> Option<String> {
+        self.ac_supply
+            .as_ref()
+            .map(|name| format!("/sys/class/power_supply/{name}/online"))
+    }
+
+    /// Sets the mains state from raw `online` contents.
+    pub fn set_ac_online_from_bytes(&mut self, bytes: Option<&[u8]>) {
+        self.ac_online = bytes
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .and_then(|s| match s.trim() {
+                "1" => Some(true),
+                "0" => Some(false),
+                _ => None,
+            });
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        self.cfg.level_health.validate_below("level_health")
+    }
+
+    pub fn backend(&self) -> BatBackend {
+        self.cfg.backend
+    }
+
+    pub fn upower_cmd(&self) -> Vec<String> {
+        let device = self.cfg.upower_device.clone().unwrap_or_else(|| {
+            format!(
+                "/org/freedesktop/UPower/devices/battery_BAT{}",
+                self.cfg.bat_id
+            )
+        });
+        vec!["upower".to_string(), "-i".to_string(), device]
+    }
 
     pub fn uevent_path(&self) -> &str {
         &self.uevent_path
     }
+
+    pub fn threshold_path(&self) -> &str {
+        &self.threshold_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bat, BatBackend, BatConfig};
+    use crate::core::Color;
+    use crate::machine::types::{Health, HealthThresholds};
+    use crate::units::testing::plain;
+    use std::time::{Duration, Instant};
+
+    fn uevent(power_uw: u64) -> String {
+        format!(
+            "POWER_SUPPLY_STATUS=Discharging\n\
+             POWER_SUPPLY_PRESENT=1\n\
+             POWER_SUPPLY_POWER_NOW={power_uw}\n\
+             POWER_SUPPLY_ENERGY_FULL_DESIGN=50000000\n\
+             POWER_SUPPLY_ENERGY_FULL=50000000\n\
+             POWER_SUPPLY_ENERGY_NOW=25000000\n"
+        )
+    }
+
+    #[test]
+    fn power_smoothing_honors_config() {
+        // One smoothing window after a 10 W -> 20 W step, the estimate has moved
+        // (1 - 1/e) of the way: 10 / e + 20 * (1 - 1/e) = 16.32 W.
+        for window in [2.5, 10.0] {
+            let mut bat = Bat::from_cfg(BatConfig {
+                bat_id: 0,
+                backend: BatBackend::Sysfs,
+                upower_device: None,
+                power_smoothing_sec: window,
+                critical_pct: 5.0,
+                flash_critical: false,
+                level_health: HealthThresholds::default(),
+            });
+            let t0 = Instant::now();
+            // The first read settles the status, which resets the smoother.
+            let _ = bat.read_markup_from_bytes_at(uevent(10_000_000).as_bytes(), t0);
+            let _ = bat.read_markup_from_bytes_at(uevent(10_000_000).as_bytes(), t0);
+            let m = bat.read_markup_from_bytes_at(
+                uevent(20_000_000).as_bytes(),
+                t0 + Duration::from_secs_f64(window),
+            );
+            assert!(plain(&m).contains(" 16.32 W "), "{}", plain(&m));
+        }
+    }
+
+    #[test]
+    fn mains_adapter_marks_ac() {
+        let mut bat: Bat = Bat::from_cfg(toml::from_str("bat_id = 0").unwrap());
+        let full = uevent(0).replace("Discharging", "Full");
+        bat.select_ac_supply(&["BAT0".into(), "ucsi-source-psy-1".into(), "AC".into()]);
+        assert_eq!(
+            bat.ac_online_path().as_deref(),
+            Some("/sys/class/power_supply/AC/online")
+        );
+
+        bat.set_ac_online_from_bytes(Some(b"1\n"));
+        assert!(plain(&bat.read_markup_from_bytes(full.as_bytes())).contains("FUL ⚡"));
+        bat.set_ac_online_from_bytes(Some(b"0\n"));
+        assert!(!plain(&bat.read_markup_from_bytes(full.as_bytes())).contains('⚡'));
+
+        let mut desktop: Bat = Bat::from_cfg(toml::from_str("bat_id = 0").unwrap());
+        desktop.select_ac_supply(&["BAT0".into()]);
+        assert_eq!(desktop.ac_online_path(), None);
+    }
+
+    #[test]
+    fn discharging_below_critical_is_urgent() {
+        let read = |critical_pct| {
+            Bat::from_cfg(BatConfig {
+                bat_id: 0,
+                backend: BatBackend::Sysfs,
+                upower_device: None,
+                power_smoothing_sec: 2.5,
+                critical_pct,
+                flash_critical: false,
+                level_health: HealthThresholds::default(),
+            })
+            .read_markup_from_bytes(uevent(10_000_000).as_bytes())
+        };
+        assert!(read(50.0).urgent);
+        assert!(!read(49.0).urgent);
+    }
+
+    #[test]
+    fn critical_flash_alternates_background() {
+        let mut bat = Bat::from_cfg(
+            toml::from_str(
+                "bat_id = 0
+critical_pct = 50.0
+flash_critical = true",
+            )
+            .unwrap(),
+        );
+        let discharging = uevent(10_000_000);
+        let backgrounds: Vec<_> = (0..3)
+            .map(|_| {
+                bat.read_markup_from_bytes(discharging.as_bytes())
+                    .background
+            })
+            .collect();
+        assert_eq!(
+            backgrounds,
+            [Some(Color::Red.into()), None, Some(Color::Red.into())]
+        );
+        let charging = discharging.replace("Discharging", "Charging");
+        assert_eq!(
+            bat.read_markup_from_bytes(charging.as_bytes()).background,
+            None
+        );
+    }
+
+    #[test]
+    fn upower_output_is_read_directly() {
+        let mut bat = Bat::from_cfg(toml::from_str("bat_id = 0\nbackend = \"upower\"").unwrap());
+        assert_eq!(
+            bat.upower_cmd(),
+            [
+                "upower",
+                "-i",
+                "/org/freedesktop/UPower/devices/battery_BAT0"
+            ]
+        );
+        let v = bat.read_markup_from_upower(include_bytes!(
+            "../../tests/fixtures/upower_bat_discharging"
+        ));
+        assert_eq!(plain(&v), "bat [ 56%] DIS 7.91 W [03:36 rem]");
+        let v =
+            bat.read_markup_from_upower(include_bytes!("../../tests/fixtures/upower_bat_pending"));
+        assert_eq!(plain(&v), "bat [ 80%] BAL 0.00 W [--:-- rem]");
+        assert!(bat.read_markup_from_upower(b"  present: no\n").health == Health::Error);
+    }
+
+    #[test]
+    fn low_charge_degrades_health_while_discharging() {
+        let mut bat = Bat::from_cfg(BatConfig {
+            bat_id: 0,
+            backend: BatBackend::Sysfs,
+            upower_device: None,
+            power_smoothing_sec: 2.5,
+            critical_pct: 5.0,
+            flash_critical: false,
+            level_health: toml::from_str("warn = 60.0\nerror = 20.0").unwrap(),
+        });
+        // The fixture battery sits at 50%.
+        let v = bat.read_markup_from_bytes(uevent(10_000_000).as_bytes());
+        assert_eq!(v.health, Health::Degraded);
+        let charging = uevent(10_000_000).replace("Discharging", "Charging");
+        let v = bat.read_markup_from_bytes(charging.as_bytes());
+        assert_eq!(v.health, Health::Ok);
+    }
+
+    #[test]
+    fn level_health_warns_above_its_error_level() {
+        let validate = |health: &str| {
+            Bat::from_cfg(toml::from_str(&format!("bat_id = 0\nlevel_health = {health}")).unwrap())
+                .fix_up_and_validate()
+        };
+        assert!(validate("{ warn = 20.0, error = 10.0 }").is_ok());
+        let err = validate("{ warn = 10.0, error = 20.0 }").unwrap_err();
+        assert!(err.to_string().contains("must be above error"), "{err}");
+    }
 }
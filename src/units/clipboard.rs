@@ -0,0 +1,182 @@
+use crate::core::{ClickEvent, Color};
+use crate::mode_enum;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+mode_enum!(Preview, Length);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// `wl-paste`/`wl-copy` if `WAYLAND_DISPLAY` is set, else `xclip`.
+    Auto,
+    Wayland,
+    /// `xclip`.
+    X11,
+    Xsel,
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipboardConfig {
+    #[serde_inline_default(ClipboardBackend::Auto)]
+    pub backend: ClipboardBackend,
+    /// How long a clipboard read is reused before shelling out again (seconds).
+    #[serde_inline_default(2.0)]
+    pub refresh_interval_sec: f64,
+    #[serde_inline_default(24)]
+    pub preview_chars: usize,
+}
+
+#[derive(Debug)]
+pub struct Clipboard {
+    cfg: ClipboardConfig,
+    backend: ClipboardBackend,
+    mode: DisplayMode,
+    pending_clear: bool,
+    /// The last read's exit status and output, re-rendered on mode switches.
+    last: Option<(bool, bytes::Bytes)>,
+}
+
+impl Clipboard {
+    pub fn from_cfg(cfg: ClipboardConfig) -> Self {
+        let backend = match cfg.backend {
+            ClipboardBackend::Auto if std::env::var_os("WAYLAND_DISPLAY").is_some() => {
+                ClipboardBackend::Wayland
+            }
+            ClipboardBackend::Auto => ClipboardBackend::X11,
+            b => b,
+        };
+        Self {
+            cfg,
+            backend,
+            mode: DisplayMode::Preview,
+            pending_clear: false,
+            last: None,
+        }
+    }
+
+    pub fn refresh_interval_sec(&self) -> f64 {
+        self.cfg.refresh_interval_sec
+    }
+
+    pub fn read_cmd(&self) -> Vec<String> {
+        let argv: &[&str] = match self.backend {
+            ClipboardBackend::Wayland => &["wl-paste", "--no-newline"],
+            ClipboardBackend::Xsel => &["xsel", "--clipboard", "--output"],
+            _ => &["xclip", "-selection", "clipboard", "-out"],
+        };
+        argv.iter().map(ToString::to_string).collect()
+    }
+
+    pub fn clear_cmd(&self) -> Vec<String> {
+        let argv: &[&str] = match self.backend {
+            ClipboardBackend::Wayland => &["wl-copy", "--clear"],
+            ClipboardBackend::Xsel => &["xsel", "--clipboard", "--clear"],
+            // xclip has no clear; owning an empty selection is the same thing.
+            _ => &["xclip", "-selection", "clipboard", "-in", "/dev/null"],
+        };
+        argv.iter().map(ToString::to_string).collect()
+    }
+
+    /// Returns whether a clear was requested since the last call.
+    pub fn take_pending_clear(&mut self) -> bool {
+        std::mem::take(&mut self.pending_clear)
+    }
+
+    /// Collapses whitespace, drops control characters and truncates to `max_chars`.
+    fn sanitize_preview(text: &str, max_chars: usize) -> String {
+        let flat = text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect::<String>();
+        if flat.chars().count() <= max_chars {
+            return flat;
+        }
+        let mut out: String = flat.chars().take(max_chars.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+
+    pub fn read_markup_from_output(&mut self, success: bool, stdout: bytes::Bytes) -> Markup {
+        let markup = self.render_output(success, &stdout);
+        self.last = Some((success, stdout));
+        markup
+    }
+
+    /// The last read in the current mode; `None` before the first one.
+    pub fn render(&self) -> Option<Markup> {
+        let (success, stdout) = self.last.as_ref()?;
+        Some(self.render_output(*success, stdout))
+    }
+
+    fn render_output(&self, success: bool, stdout: &[u8]) -> Markup {
+        let prefix = Markup::label("clip ");
+        // The tools exit non-zero, or print nothing, on an empty selection.
+        if !success || stdout.is_empty() {
+            return prefix + Markup::text("empty").fg(Color::Grey);
+        }
+        let Ok(text) = std::str::from_utf8(stdout) else {
+            return prefix
+                + Markup::bracketed(
+                    Markup::text(format!("binary {} B", stdout.len())).fg(Color::Violet),
+                );
+        };
+        match self.mode {
+            DisplayMode::Preview => {
+                prefix
+                    + Markup::text(format!(
+                        "\"{}\"",
+                        Self::sanitize_preview(text, self.cfg.preview_chars)
+                    ))
+            }
+            DisplayMode::Length => {
+                prefix + Markup::bracketed(Markup::text(format!("{} chars", text.chars().count())))
+            }
+        }
+    }
+
+    pub fn handle_click(&mut self, click: ClickEvent) {
+        match click.button {
+            ClickEvent::BUTTON_LEFT => self.pending_clear = true,
+            ClickEvent::BUTTON_RIGHT => self.mode = DisplayMode::next(self.mode),
+            ClickEvent::BUTTON_SCROLL_UP | ClickEvent::BUTTON_SCROLL_DOWN => {
+                self.mode = self.mode.rotate(&click);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clipboard, ClipboardBackend, ClipboardConfig};
+
+    #[test]
+    fn x11_uses_xclip_unless_xsel_is_asked_for() {
+        let cfg = |backend| ClipboardConfig {
+            backend,
+            refresh_interval_sec: 2.0,
+            preview_chars: 24,
+        };
+        let xclip = Clipboard::from_cfg(cfg(ClipboardBackend::X11));
+        assert_eq!(xclip.read_cmd()[0], "xclip");
+        assert_eq!(xclip.clear_cmd()[0], "xclip");
+        let xsel = Clipboard::from_cfg(cfg(ClipboardBackend::Xsel));
+        assert_eq!(xsel.read_cmd()[0], "xsel");
+    }
+
+    #[test]
+    fn preview_is_flattened_and_truncated() {
+        assert_eq!(Clipboard::sanitize_preview("a\n\tb  c", 10), "a b c");
+        assert_eq!(Clipboard::sanitize_preview("abcdefgh", 5), "abcd…");
+    }
+}
@@ -0,0 +1,173 @@
+use crate::render::color::Srgb8;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkupMode {
+    /// Output is shown literally (escaped).
+    None,
+    /// Output is trusted pango markup and passed through unescaped.
+    Pango,
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandConfig {
+    /// Run with `sh -c`.
+    pub command: String,
+    /// How long an output is reused before the command runs again (seconds).
+    #[serde_inline_default(5.0)]
+    pub refresh_interval_sec: f64,
+    /// The command is killed if it runs longer than this (seconds).
+    #[serde_inline_default(5.0)]
+    pub timeout_sec: f64,
+    #[serde_inline_default(MarkupMode::None)]
+    pub markup: MarkupMode,
+}
+
+/// One run's output, read the way i3blocks does: line 1 is the full text,
+/// line 2 the short text and line 3 a `#RRGGBB` color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockOutput {
+    pub full_text: String,
+    pub short_text: Option<String>,
+    pub color: Option<Srgb8>,
+}
+
+#[derive(Debug)]
+pub struct Command {
+    cfg: CommandConfig,
+    /// The button of a click not yet passed to a run.
+    pending_button: Option<i32>,
+}
+
+impl Command {
+    pub fn from_cfg(cfg: CommandConfig) -> Self {
+        Self {
+            cfg,
+            pending_button: None,
+        }
+    }
+
+    pub fn cmd(&self) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), self.cfg.command.clone()]
+    }
+
+    pub fn refresh_interval_sec(&self) -> f64 {
+        self.cfg.refresh_interval_sec
+    }
+
+    pub fn timeout_sec(&self) -> f64 {
+        self.cfg.timeout_sec
+    }
+
+    fn parse_color(line: &str) -> Option<Srgb8> {
+        let hex = line.trim().strip_prefix('#')?;
+        (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| Srgb8::from(hex))
+    }
+
+    pub fn parse_output(stdout: &[u8]) -> BlockOutput {
+        let text = String::from_utf8_lossy(stdout);
+        let mut lines = text.lines();
+        let full_text = lines.next().unwrap_or_default().to_string();
+        let short_text = lines.next().filter(|l| !l.is_empty()).map(str::to_string);
+        let color = lines.next().and_then(Self::parse_color);
+        BlockOutput {
+            full_text,
+            short_text,
+            color,
+        }
+    }
+
+    pub fn read_markup(&self, out: &BlockOutput) -> Markup {
+        self.render_line(&out.full_text, out.color)
+    }
+
+    /// Line 2, rendered like line 1.
+    pub fn read_short_markup(&self, out: &BlockOutput) -> Option<Markup> {
+        let short = out.short_text.as_deref()?;
+        Some(self.render_line(short, out.color))
+    }
+
+    fn render_line(&self, line: &str, color: Option<Srgb8>) -> Markup {
+        let text = match self.cfg.markup {
+            MarkupMode::None => Markup::text(line),
+            MarkupMode::Pango => Markup::trusted_pango(line),
+        };
+        match color {
+            Some(color) => text.fg(color),
+            None => text,
+        }
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.pending_button = Some(click.button);
+    }
+
+    /// The environment for the next run: after a click, `BLOCK_BUTTON` holds
+    /// its button, as i3blocks sets it. Returns `None` for a plain refresh.
+    pub fn take_click_env(&mut self) -> Option<Vec<(String, String)>> {
+        let button = self.pending_button.take()?;
+        Some(vec![("BLOCK_BUTTON".to_string(), button.to_string())])
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, CommandConfig, MarkupMode};
+    use crate::core::ClickEvent;
+    use crate::render::color::Srgb8;
+    use crate::units::testing::{click, pango};
+
+    #[test]
+    fn clicks_set_block_button_for_one_run() {
+        let mut unit = Command::from_cfg(cfg(MarkupMode::None));
+        assert_eq!(unit.take_click_env(), None);
+        unit.handle_click(click(ClickEvent::BUTTON_RIGHT));
+        assert_eq!(
+            unit.take_click_env(),
+            Some(vec![("BLOCK_BUTTON".to_string(), "3".to_string())])
+        );
+        assert_eq!(unit.take_click_env(), None);
+    }
+
+    fn cfg(markup: MarkupMode) -> CommandConfig {
+        CommandConfig {
+            command: "true".to_string(),
+            refresh_interval_sec: 5.0,
+            timeout_sec: 5.0,
+            markup,
+        }
+    }
+
+    #[test]
+    fn output_follows_i3blocks_lines() {
+        let out = Command::parse_output(b"5 updates <new>\n5\n#CC6666\nignored\n");
+        assert_eq!(out.short_text.as_deref(), Some("5"));
+        let unit = Command::from_cfg(cfg(MarkupMode::None));
+        assert_eq!(
+            unit.read_short_markup(&out).map(|m| pango(&m)).as_deref(),
+            Some("<span color='#CC6666'>5</span>")
+        );
+        assert_eq!(out.color, Some(Srgb8::new(0xCC, 0x66, 0x66)));
+        assert_eq!(
+            pango(&Command::from_cfg(cfg(MarkupMode::None)).read_markup(&out)),
+            "<span color='#CC6666'>5 updates &lt;new&gt;</span>"
+        );
+
+        let bare = Command::parse_output(b"hello\n\nnot-a-color\n");
+        assert_eq!((bare.short_text.clone(), bare.color), (None, None));
+
+        let trusted = Command::parse_output(b"<b>hi</b>\n");
+        assert_eq!(
+            pango(&Command::from_cfg(cfg(MarkupMode::Pango)).read_markup(&trusted)),
+            "<b>hi</b>"
+        );
+    }
+}
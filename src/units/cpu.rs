@@ -3,8 +3,9 @@ use serde::Deserialize;
 use serde_inline_default::serde_inline_default;
 use sysinfo::Components;
 
-use crate::core::{BROWN, VIOLET};
-use crate::display::{color_by_pct, color_by_pct_custom};
+use crate::core::{theme, Color};
+use crate::display::{color_by_pct, color_by_pct_custom, format_fixed, TempUnits};
+use crate::machine::types::{Health, HealthThresholds, View};
 use crate::mode_enum;
 use crate::render::markup::Markup;
 
@@ -12,14 +13,100 @@ mode_enum!(Combined, Breakdown);
 
 #[serde_inline_default]
 #[derive(Debug, Clone, Deserialize)]
-pub struct CpuConfig {}
+pub struct CpuConfig {
+    /// Flag thermal throttling / frequency capping. Needs cpufreq sysfs nodes.
+    #[serde_inline_default(false)]
+    pub show_throttle: bool,
+    /// Unit the temperature is shown in.
+    #[serde_inline_default(TempUnits::Celsius)]
+    pub temp_units: TempUnits,
+    /// Package temperature (°C, whatever `temp_units`) levels that mark the
+    /// segment degraded/error.
+    #[serde(default)]
+    pub temp_health: HealthThresholds,
+    /// Decimals on the usage percentages.
+    #[serde_inline_default(0)]
+    pub load_precision: u8,
+    /// Decimals on the temperature.
+    #[serde_inline_default(0)]
+    pub temp_precision: u8,
+    /// Add time stolen by the hypervisor to the breakdown; useful on VMs.
+    #[serde_inline_default(false)]
+    pub show_steal: bool,
+}
+
+/// Cumulative jiffies from the aggregate `cpu` line of `/proc/stat`, whose
+/// fields are `user nice system idle iowait irq softirq steal guest guest_nice`.
+/// Older kernels stop early; missing fields count as 0.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct CpuTimes {
+    /// `user + nice`. Guest time is already folded into these by the kernel.
+    user: u64,
+    /// `system + irq + softirq`.
+    kernel: u64,
+    idle: u64,
+    /// Idle while waiting on IO; never counted as busy.
+    iowait: u64,
+    steal: u64,
+    /// Every field up to `steal`; `guest`/`guest_nice` would count twice.
+    total: u64,
+}
+
+impl CpuTimes {
+    fn parse(fields: &[u64]) -> Self {
+        let f = |i: usize| fields.get(i).copied().unwrap_or(0);
+        Self {
+            user: f(0) + f(1),
+            kernel: f(2) + f(5) + f(6),
+            idle: f(3),
+            iowait: f(4),
+            steal: f(7),
+            total: (0..8).map(f).sum(),
+        }
+    }
+}
+
+pub const CUR_FREQ_PATH: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_cur_freq";
+pub const MAX_FREQ_PATH: &str = "/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq";
+pub const THROTTLE_COUNT_PATH: &str =
+    "/sys/devices/system/cpu/cpu0/thermal_throttle/package_throttle_count";
+
+// Under heavy load, a clock this far below max means something is capping it.
+const THROTTLE_LOAD_PCT: f64 = 80.0;
+const THROTTLE_FREQ_FRAC: f64 = 0.6;
+
+/// Raw sysfs contents backing the throttle indicator; any may be missing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThrottleInputs<'a> {
+    pub cur_freq: Option<&'a [u8]>,
+    pub max_freq: Option<&'a [u8]>,
+    pub throttle_count: Option<&'a [u8]>,
+}
+
+/// Usage percentages over the last sampled interval, kept so a mode switch
+/// can re-render without a new sample.
+#[derive(Debug, Clone, Copy)]
+struct CpuUsage {
+    user: f64,
+    kernel: f64,
+    iowait: f64,
+    steal: f64,
+    total: f64,
+    temp_c: Option<f64>,
+    throttled: bool,
+}
 
 #[derive(Debug)]
 pub struct Cpu {
+    cfg: CpuConfig,
     mode: DisplayMode,
-    prev_total: u64,
-    prev_user: u64,
-    prev_kernel: u64,
+    prev: CpuTimes,
+    last: Option<CpuUsage>,
+    freq_frac: Option<f64>,
+    prev_throttle_count: Option<u64>,
+    throttle_event: bool,
+    /// Enumerated on first use, then only refreshed.
+    components: Option<Components>,
 }
 
 const KNOWN_CPU_HWMON_NAMES: &[&str] = &[
@@ -28,16 +115,58 @@ const KNOWN_CPU_HWMON_NAMES: &[&str] = &[
 ];
 
 impl Cpu {
-    pub fn from_cfg(_cfg: CpuConfig) -> Self {
+    pub fn from_cfg(cfg: CpuConfig) -> Self {
         Self {
+            cfg,
             mode: DisplayMode::Combined,
-            prev_total: 0,
-            prev_user: 0,
-            prev_kernel: 0,
+            prev: CpuTimes::default(),
+            last: None,
+            freq_frac: None,
+            prev_throttle_count: None,
+            throttle_event: false,
+            components: None,
+        }
+    }
+
+    pub fn show_throttle(&self) -> bool {
+        self.cfg.show_throttle
+    }
+
+    /// Updates throttle state; call before rendering. Missing nodes disable the
+    /// corresponding check.
+    pub fn set_throttle_inputs(&mut self, inputs: ThrottleInputs<'_>) {
+        fn parse(bytes: Option<&[u8]>) -> Option<u64> {
+            std::str::from_utf8(bytes?).ok()?.trim().parse().ok()
         }
+
+        self.freq_frac = match (parse(inputs.cur_freq), parse(inputs.max_freq)) {
+            (Some(cur), Some(max)) if max > 0 => Some(cur as f64 / max as f64),
+            _ => None,
+        };
+
+        let count = parse(inputs.throttle_count);
+        self.throttle_event = matches!(
+            (self.prev_throttle_count, count),
+            (Some(prev), Some(cur)) if cur > prev
+        );
+        self.prev_throttle_count = count;
+    }
+
+    fn is_throttled(&self, total_usage: f64) -> bool {
+        self.throttle_event
+            || (total_usage >= THROTTLE_LOAD_PCT
+                && self.freq_frac.is_some_and(|f| f < THROTTLE_FREQ_FRAC))
     }
 
-    pub fn read_markup_from_proc_stat(&mut self, proc_stat: &[u8]) -> Markup {
+    /// Renders from raw `/proc/stat` contents; usage is the delta since the previous call.
+    pub fn read_markup_from_bytes(&mut self, proc_stat: &[u8]) -> View {
+        let temp_c = self.read_temp().ok();
+        self.read_markup_with_temp(proc_stat, temp_c)
+    }
+
+    /// As [`Self::read_markup_from_bytes`], with the temperature supplied instead of read
+    /// from sensors. Pure given its inputs.
+    pub fn read_markup_with_temp(&mut self, proc_stat: &[u8], temp_c: Option<f64>) -> View {
         let line = std::str::from_utf8(proc_stat)
             .ok()
             .and_then(|s| s.lines().next())
@@ -49,60 +178,120 @@ impl Cpu {
             .filter_map(|s| s.parse::<u64>().ok())
             .collect();
         if parts.len() < 4 {
-            return Markup::text("read err").fg(BROWN);
+            self.last = None;
+            return View::error(Markup::text("read err").fg(theme().missing));
         }
 
-        let total: u64 = parts.iter().sum();
-        let user = parts[0] + parts[1];
-        let kernel = parts[2];
-        self.read_markup_from_times(total, user, kernel)
+        let usage = self.sample(CpuTimes::parse(&parts), temp_c);
+        self.last = Some(usage);
+        self.render_usage(&usage)
     }
 
-    fn read_markup_from_times(&mut self, total: u64, user: u64, kernel: u64) -> Markup {
-        let d_total = total.saturating_sub(self.prev_total) as f64;
-        let d_user = user.saturating_sub(self.prev_user) as f64;
-        let d_kernel = kernel.saturating_sub(self.prev_kernel) as f64;
-
-        self.prev_total = total;
-        self.prev_user = user;
-        self.prev_kernel = kernel;
-
-        let p_user = if d_total > 0.0 { d_user / d_total } else { 0.0 };
-        let p_kernel = if d_total > 0.0 {
-            d_kernel / d_total
+    /// Usage since the previous sample; the first one reads as 0%.
+    fn sample(&mut self, times: CpuTimes, temp_c: Option<f64>) -> CpuUsage {
+        let prev = std::mem::replace(&mut self.prev, times);
+        let d_total = times.total.saturating_sub(prev.total) as f64;
+        // Percent of the interval, 0 before there is one.
+        let share = |now: u64, before: u64| {
+            if d_total > 0.0 {
+                now.saturating_sub(before) as f64 / d_total * 100.0
+            } else {
+                0.0
+            }
+        };
+        let iowait = share(times.iowait, prev.iowait);
+        // Busy is everything but idle and iowait.
+        let total = if d_total > 0.0 {
+            100.0 - share(times.idle, prev.idle) - iowait
         } else {
             0.0
         };
+        CpuUsage {
+            user: share(times.user, prev.user),
+            kernel: share(times.kernel, prev.kernel),
+            iowait,
+            steal: share(times.steal, prev.steal),
+            total,
+            temp_c,
+            throttled: self.cfg.show_throttle && self.is_throttled(total),
+        }
+    }
 
-        let p_user = p_user * 100.0;
-        let p_kernel = p_kernel * 100.0;
-        let total_usage = p_user + p_kernel;
+    /// The last sample in the current mode; `None` before the first one.
+    pub fn render(&self) -> Option<View> {
+        self.last.map(|usage| self.render_usage(&usage))
+    }
+
+    fn render_usage(&self, usage: &CpuUsage) -> View {
+        let health = usage
+            .temp_c
+            .map_or(Health::Ok, |tc| self.cfg.temp_health.above(tc));
+        let view = View::ok(self.render_body(usage))
+            .with_health(health)
+            .with_metric("cpu_usage_percent", usage.total);
+        match usage.temp_c {
+            Some(tc) => view.with_metric("cpu_temp_celsius", tc),
+            None => view,
+        }
+    }
 
-        let temp_str = match Self::read_temp() {
-            Err(_) => Markup::text("unk").fg(VIOLET),
-            Ok(tc) => Markup::text(format!("{tc:>3.0}"))
+    fn render_body(&self, usage: &CpuUsage) -> Markup {
+        let temp_str = match usage.temp_c {
+            None => Markup::text("unk").fg(Color::Violet),
+            Some(tc) => {
+                let units = self.cfg.temp_units;
+                Markup::text(format_fixed(
+                    units.convert_from_celcius(tc),
+                    3,
+                    self.cfg.temp_precision,
+                ))
                 .fg(color_by_pct_custom(tc, &[40.0, 50.0, 70.0, 90.0]))
-                .append(Markup::text(" C")),
+                .append(Markup::text(format!(" {}", units.suffix())))
+            }
         };
 
+        let pct = |v: f64| {
+            Markup::text(format!("{}%", format_fixed(v, 3, self.cfg.load_precision)))
+                .fg(color_by_pct(v))
+        };
         let load_str = if self.mode == DisplayMode::Breakdown {
-            Markup::text("u ")
-                .append(Markup::text(format!("{p_user:>3.0}%")).fg(color_by_pct(p_user)))
+            let out = Markup::text("u ")
+                .append(pct(usage.user))
                 .append(Markup::text(" k "))
-                .append(Markup::text(format!("{p_kernel:>3.0}%")).fg(color_by_pct(p_kernel)))
+                .append(pct(usage.kernel))
+                .append(Markup::text(" io "))
+                .append(pct(usage.iowait));
+            if self.cfg.show_steal {
+                out.append(Markup::text(" st ")).append(pct(usage.steal))
+            } else {
+                out
+            }
         } else {
-            Markup::text("load ")
-                .append(Markup::text(format!("{total_usage:>3.0}%")).fg(color_by_pct(total_usage)))
+            Markup::text("load ").append(pct(usage.total))
         };
-        Markup::text("cpu ")
+        let out = Markup::label("cpu ")
             .append(Markup::bracketed(load_str))
             .append(Markup::text(" "))
-            .append(Markup::bracketed(Markup::text("temp ").append(temp_str)))
+            .append(Markup::bracketed(Markup::text("temp ").append(temp_str)));
+        if usage.throttled {
+            out.append(Markup::text(" "))
+                .append(Markup::text("thr").fg(Color::Red))
+        } else {
+            out
+        }
     }
 
-    fn read_temp() -> Result<f64> {
-        let cs = Components::new_with_refreshed_list();
-        for component in &cs {
+    fn read_temp(&mut self) -> Result<f64> {
+        let cs = match &mut self.components {
+            Some(cs) => {
+                cs.refresh(true);
+                cs
+            }
+            None => self
+                .components
+                .insert(Components::new_with_refreshed_list()),
+        };
+        for component in cs.iter() {
             if let Some((name, _)) = &component.label().split_once(' ') {
                 if KNOWN_CPU_HWMON_NAMES.contains(name) {
                     if let Some(temp) = component.temperature() {
@@ -114,9 +303,126 @@ impl Cpu {
         Err(anyhow!("No temperature sensors found in components"))
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        self.cfg.temp_health.validate_above("temp_health")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cpu, ThrottleInputs};
+    use crate::machine::types::Health;
+    use crate::units::testing::plain;
+
+    fn cpu_with(cfg: &str) -> Cpu {
+        Cpu::from_cfg(toml::from_str(cfg).unwrap())
+    }
+
+    #[test]
+    fn usage_is_delta_between_samples() {
+        let mut cpu = cpu_with("");
+        let _ = cpu.read_markup_with_temp(b"cpu  100 0 100 800 0 0 0 0 0 0\n", None);
+        let m = cpu.read_markup_with_temp(b"cpu  150 0 150 900 0 0 0 0 0 0\n", Some(45.0));
+        assert_eq!(plain(&m), "cpu [load  50%] [temp  45 C]");
+        assert_eq!(
+            m.metrics,
+            [("cpu_usage_percent", 50.0), ("cpu_temp_celsius", 45.0)]
+        );
+    }
+
+    #[test]
+    fn precision_adds_decimals() {
+        let mut cpu = cpu_with("load_precision = 1\ntemp_precision = 2");
+        let _ = cpu.read_markup_with_temp(
+            b"cpu  0 0 0 0 0 0 0 0 0 0
+",
+            None,
+        );
+        let m = cpu.read_markup_with_temp(
+            b"cpu  1 0 0 2 0 0 0 0 0 0
+",
+            Some(45.125),
+        );
+        assert_eq!(plain(&m), "cpu [load  33.3%] [temp  45.12 C]");
+    }
+
+    #[test]
+    fn throttle_count_increase_is_flagged() {
+        let mut cpu = cpu_with("show_throttle = true");
+        let count = |c: &'static [u8]| ThrottleInputs {
+            throttle_count: Some(c),
+            ..ThrottleInputs::default()
+        };
+        cpu.set_throttle_inputs(count(b"3\n"));
+        let _ = cpu.read_markup_with_temp(b"cpu  0 0 0 100\n", None);
+        cpu.set_throttle_inputs(count(b"4\n"));
+        let m = cpu.read_markup_with_temp(b"cpu  0 0 0 200\n", None);
+        assert!(plain(&m).ends_with(" thr"));
+        cpu.set_throttle_inputs(count(b"4\n"));
+        let m = cpu.read_markup_with_temp(b"cpu  0 0 0 300\n", None);
+        assert!(!plain(&m).contains("thr"));
+    }
+
+    #[test]
+    fn hot_package_degrades_health() {
+        let mut cpu = cpu_with("temp_health = { warn = 85.0, error = 95.0 }");
+        let stat = b"cpu  0 0 0 100\n";
+        assert_eq!(
+            cpu.read_markup_with_temp(stat, Some(60.0)).health,
+            Health::Ok
+        );
+        assert_eq!(
+            cpu.read_markup_with_temp(stat, Some(85.0)).health,
+            Health::Degraded
+        );
+        assert_eq!(
+            cpu.read_markup_with_temp(stat, Some(99.0)).health,
+            Health::Error
+        );
+        assert_eq!(cpu.read_markup_with_temp(stat, None).health, Health::Ok);
+    }
+
+    #[test]
+    fn misordered_temp_health_is_a_config_error() {
+        let validate =
+            |health: &str| cpu_with(&format!("temp_health = {health}")).fix_up_and_validate();
+        assert!(validate("{ warn = 85.0, error = 95.0 }").is_ok());
+        assert!(validate("{ warn = 95.0 }").is_ok());
+        let err = validate("{ warn = 95.0, error = 85.0 }").unwrap_err();
+        assert!(err.to_string().contains("must be below error"), "{err}");
     }
 
-    pub fn fix_up_and_validate() {}
+    #[test]
+    fn fahrenheit_display_keeps_celsius_thresholds() {
+        let mut cpu =
+            cpu_with("temp_units = \"fahrenheit\"\ntemp_health = { warn = 85.0, error = 95.0 }");
+        let m = cpu.read_markup_with_temp(b"cpu  0 0 0 100\n", Some(90.0));
+        assert_eq!(plain(&m), "cpu [load   0%] [temp 194 F]");
+        assert_eq!(m.health, Health::Degraded);
+        assert_eq!(m.metrics[1], ("cpu_temp_celsius", 90.0));
+    }
+
+    #[test]
+    fn iowait_and_guest_are_not_counted_as_busy() {
+        use crate::core::ClickEvent;
+        use crate::units::testing::click;
+        // 100 jiffies: 20 user (10 of it guest), 10 system, 40 idle, 20 iowait,
+        // 5 softirq, 5 steal.
+        let zero = b"cpu  0 0 0 0 0 0 0 0 0 0\n";
+        let stat = b"cpu  20 0 10 40 20 0 5 5 10 0\n";
+        let mut cpu = cpu_with("show_steal = true");
+        let _ = cpu.read_markup_with_temp(zero, None);
+        let m = cpu.read_markup_with_temp(stat, None);
+        assert_eq!(plain(&m), "cpu [load  40%] [temp unk]");
+
+        let mut cpu = cpu_with("show_steal = true");
+        cpu.handle_click(click(ClickEvent::BUTTON_LEFT));
+        let _ = cpu.read_markup_with_temp(zero, None);
+        let m = cpu.read_markup_with_temp(stat, None);
+        assert_eq!(plain(&m), "cpu [u  20% k  15% io  20% st   5%] [temp unk]");
+    }
 }
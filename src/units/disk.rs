@@ -1,4 +1,8 @@
-use crate::core::{BLUE, BROWN, ORANGE, VIOLET};
+use crate::core::{theme, Color};
+use crate::display::{band_index, color_by_pct_custom};
+use crate::machine::types::{Health, HealthThresholds, View};
+use crate::mode_enum;
+use crate::render::color::Paint;
 use crate::render::markup::Markup;
 use crate::util::{Ema, Smoother};
 use cute::c;
@@ -8,6 +12,29 @@ use std::time::Instant;
 use tracing::info;
 const BARS: &[&str; 9] = &[" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
 
+mode_enum!(Throughput, Iops, Latency);
+
+/// Average latency color breakpoints (ms): SSD-fast up to sluggish HDD.
+const LATENCY_BREAKPOINTS_MS: [f64; 4] = [1.0, 5.0, 20.0, 100.0];
+
+/// Auto-calibrated peaks never drop below this (bytes/sec), so an idle disk
+/// doesn't stretch a trickle into full bars.
+const MIN_AUTO_PEAK: f64 = 1e6;
+
+/// One `/sys/class/block/<disk>/stat` line. All fields but `in_flight` are
+/// cumulative counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct DiskStat {
+    read_ios: u64,
+    read_bytes: u64,
+    write_ios: u64,
+    write_bytes: u64,
+    /// Requests currently queued or being served.
+    in_flight: u64,
+    /// Sum of every request's time in flight (ms).
+    time_in_queue_ms: u64,
+}
+
 #[serde_inline_default]
 #[derive(Debug, Clone, Deserialize)]
 pub struct DiskConfig {
@@ -26,20 +53,92 @@ pub struct DiskConfig {
 
     #[serde_inline_default(1.5e9)]
     read_peak_ref: f64,
+
+    /// Scale the bars to the observed peak instead of `*_peak_ref`.
+    #[serde(default)]
+    auto_calibrate: bool,
+
+    /// Time constant (seconds) over which an auto-calibrated peak decays.
+    #[serde_inline_default(600.0)]
+    calibration_window_sec: f64,
+
+    /// Report the fullest mounted filesystem instead of one disk's I/O; no
+    /// selector is needed.
+    #[serde(default)]
+    worst: bool,
+
+    /// Filesystem types `worst` considers; everything else (tmpfs, proc,
+    /// cgroup, ...) is skipped.
+    #[serde_inline_default(DEFAULT_FS_TYPES.iter().map(|&t| t.into()).collect())]
+    fs_types: Vec<String>,
+
+    /// Used-percent levels at which `worst` turns degraded/error.
+    #[serde_inline_default(HealthThresholds { warn: Some(85.0), error: Some(95.0) })]
+    usage_health: HealthThresholds,
+}
+
+/// Real on-disk filesystems `worst` scans by default.
+const DEFAULT_FS_TYPES: &[&str] = &[
+    "ext2", "ext3", "ext4", "xfs", "btrfs", "bcachefs", "f2fs", "zfs", "vfat", "exfat", "ntfs3",
+];
+
+/// One mounted filesystem's capacity, as statvfs reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountUsage {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl MountUsage {
+    /// Parses one `stat -f --format='%b %a %S %n'` line: total and available
+    /// blocks, the block size they count in, then the mount point.
+    fn parse_stat_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, ' ');
+        let mut num = || fields.next()?.parse::<u64>().ok();
+        let (blocks, available, block_size) = (num()?, num()?, num()?);
+        Some(Self {
+            mount_point: fields.next()?.to_string(),
+            total_bytes: blocks.saturating_mul(block_size),
+            available_bytes: available.saturating_mul(block_size),
+        })
+    }
+
+    /// Share of the filesystem in use (percent); `None` for empty ones.
+    pub fn used_pct(&self) -> Option<f64> {
+        (self.total_bytes > 0).then(|| {
+            let used = self.total_bytes.saturating_sub(self.available_bytes);
+            used as f64 / self.total_bytes as f64 * 100.0
+        })
+    }
 }
 
 impl DiskConfig {
     pub fn validate(&self) -> Result<(), &'static str> {
-        let has_any_selector =
-            self.disk.is_some() || self.partlabel.is_some() || self.partuuid.is_some();
+        let has_any_selector = self.worst
+            || self.disk.is_some()
+            || self.partlabel.is_some()
+            || self.partuuid.is_some();
         if has_any_selector {
             Ok(())
         } else {
-            Err("Disk: missing selector: set `disk` or `partlabel` or `partuuid`")
+            Err("Disk: missing selector: set `disk` or `partlabel` or `partuuid`, or `worst`")
         }
     }
 }
 
+/// Smoothed rates from the last sample, kept so a mode switch can re-render
+/// without a new one.
+#[derive(Debug, Clone, Copy)]
+struct DiskRates {
+    bps_read: f64,
+    bps_write: f64,
+    iops_read: f64,
+    iops_write: f64,
+    latency_ms: f64,
+    in_flight: u64,
+}
+
 #[derive(Debug)]
 pub struct Disk {
     cfg: DiskConfig,
@@ -47,29 +146,39 @@ pub struct Disk {
     root: Option<String>,
     name: Option<String>,
     initialized: bool,
+    mode: DisplayMode,
     write_ema: Ema<f64>,
     read_ema: Ema<f64>,
+    read_iops_ema: Ema<f64>,
+    write_iops_ema: Ema<f64>,
+    latency_ema: Ema<f64>,
+    read_peak: f64,
+    write_peak: f64,
     read_threshs: Vec<f64>,
     write_threshs: Vec<f64>,
-    last_r: u64,
-    last_w: u64,
+    last: DiskStat,
     last_t: Instant,
+    shown: Option<DiskRates>,
 }
 
 impl Disk {
     pub fn from_cfg(cfg: DiskConfig) -> Self {
         // TODO evetually we'll make these Results and handle construction with toml config
         let sector_size = None;
-        let (last_r, last_w): (u64, u64) = (0, 0);
         let name = if cfg.partlabel.is_none() && cfg.partuuid.is_none() {
             cfg.disk.clone()
         } else {
             None
         };
 
-        let read_threshs = c![cfg.read_peak_ref.powf(i as f64 /9.0), for i in 1..10];
+        let (read_peak, write_peak) = if cfg.auto_calibrate {
+            (MIN_AUTO_PEAK, MIN_AUTO_PEAK)
+        } else {
+            (cfg.read_peak_ref, cfg.write_peak_ref)
+        };
+        let read_threshs = Self::log_thresholds(read_peak);
         info!("computed read thresholds: {:?}", read_threshs);
-        let write_threshs = c![cfg.write_peak_ref.powf(i as f64 / 9.0), for i in 1..10];
+        let write_threshs = Self::log_thresholds(write_peak);
         info!("computed write thresholds: {:?}", write_threshs);
 
         Self {
@@ -77,22 +186,62 @@ impl Disk {
             root: None,
             name,
             initialized: false,
+            mode: DisplayMode::Throughput,
             write_ema: Ema::new(cfg.smoothing_sec),
             read_ema: Ema::new(cfg.smoothing_sec),
+            read_iops_ema: Ema::new(cfg.smoothing_sec),
+            write_iops_ema: Ema::new(cfg.smoothing_sec),
+            latency_ema: Ema::new(cfg.smoothing_sec),
+            read_peak,
+            write_peak,
             read_threshs,
             write_threshs,
-            last_r,
-            last_w,
+            last: DiskStat::default(),
             last_t: Instant::now(),
+            shown: None,
             cfg,
         }
     }
 
-    fn parse_stat(buf: &str, sector_size: u64) -> Option<(u64, u64)> {
+    /// Bar glyph for `bps`; at or past the last threshold the bar is full.
+    fn bar(bps: f64, threshs: &[f64]) -> &'static str {
+        BARS[band_index(bps, threshs).min(BARS.len() - 1)]
+    }
+
+    /// Log-spaced bar thresholds from 1 B/s up to `peak`.
+    fn log_thresholds(peak: f64) -> Vec<f64> {
+        c![peak.powf(i as f64 / 9.0), for i in 1..10]
+    }
+
+    /// A peak that jumps to any higher sample and otherwise decays by `e`
+    /// every `window_sec`.
+    fn track_peak(peak: f64, sample: f64, dt: f64, window_sec: f64) -> f64 {
+        let decayed = peak * (-dt / window_sec.max(f64::EPSILON)).exp();
+        decayed.max(sample).max(MIN_AUTO_PEAK)
+    }
+
+    /// Adapts the bar thresholds to the smoothed throughput just observed.
+    fn calibrate(&mut self, bps_read: f64, bps_write: f64, dt: f64) {
+        let window = self.cfg.calibration_window_sec;
+        self.read_peak = Self::track_peak(self.read_peak, bps_read, dt, window);
+        self.write_peak = Self::track_peak(self.write_peak, bps_write, dt, window);
+        self.read_threshs = Self::log_thresholds(self.read_peak);
+        self.write_threshs = Self::log_thresholds(self.write_peak);
+    }
+
+    /// Fields 0/4 are completed read/write operations, 2/6 sectors read/written,
+    /// 8 requests in flight and 10 the weighted time in queue.
+    fn parse_stat(buf: &str, sector_size: u64) -> Option<DiskStat> {
         let spl: Vec<&str> = buf.split_whitespace().collect();
-        let r = spl.get(2).and_then(|s| s.parse::<u64>().ok())? * sector_size;
-        let w = spl.get(6).and_then(|s| s.parse::<u64>().ok())? * sector_size;
-        Some((r, w))
+        let field = |i: usize| spl.get(i).and_then(|s| s.parse::<u64>().ok());
+        Some(DiskStat {
+            read_ios: field(0)?,
+            read_bytes: field(2)? * sector_size,
+            write_ios: field(4)?,
+            write_bytes: field(6)? * sector_size,
+            in_flight: field(8)?,
+            time_in_queue_ms: field(10)?,
+        })
     }
 
     pub fn select_root(&mut self, entries: &[String]) {
@@ -146,17 +295,123 @@ impl Disk {
     pub fn selector_partuuid(&self) -> Option<&str> {
         self.cfg.partuuid.as_deref()
     }
+
+    pub fn is_worst(&self) -> bool {
+        self.cfg.worst
+    }
 }
 
 impl Disk {
+    /// Mount points in `/proc/mounts` whose type is in `fs_types`, each once
+    /// and in file order. Only these get statvfs'd, so network and pseudo
+    /// filesystems are never touched.
+    pub fn worst_mount_points(&self, proc_mounts: &[u8]) -> Vec<String> {
+        let mut kept: Vec<String> = Vec::new();
+        for line in String::from_utf8_lossy(proc_mounts).lines() {
+            let mut fields = line.split_whitespace().skip(1);
+            let (Some(mount_point), Some(fs_type)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let mount_point = unescape_mount_field(mount_point);
+            if self.cfg.fs_types.iter().any(|t| t == fs_type) && !kept.contains(&mount_point) {
+                kept.push(mount_point);
+            }
+        }
+        kept
+    }
+
+    /// Statvfs's `mount_points` through coreutils' `stat`, one line each.
+    pub fn worst_stat_cmd(mount_points: &[String]) -> Vec<String> {
+        ["stat", "-f", "--format=%b %a %S %n", "--"]
+            .into_iter()
+            .map(String::from)
+            .chain(mount_points.iter().cloned())
+            .collect()
+    }
+
+    /// Renders the fullest filesystem from [`Self::worst_stat_cmd`]'s output.
+    /// Mounts `stat` could not read (gone since `/proc/mounts`) are skipped.
+    pub fn read_worst_mount(&self, stat_out: &[u8]) -> View {
+        let mounts: Vec<MountUsage> = String::from_utf8_lossy(stat_out)
+            .lines()
+            .filter_map(MountUsage::parse_stat_line)
+            .collect();
+        self.format_worst_mount(&mounts)
+    }
+
+    fn format_worst_mount(&self, mounts: &[MountUsage]) -> View {
+        let prefix = Markup::label("disk worst ");
+        let worst = mounts
+            .iter()
+            .filter_map(|m| Some((m, m.used_pct()?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((mount, pct)) = worst else {
+            return View::error(prefix + Markup::text("no filesystems").fg(theme().missing));
+        };
+        let health = self.cfg.usage_health.above(pct);
+        let color: Paint = match health {
+            Health::Ok => Color::Green.into(),
+            Health::Degraded => Color::Orange.into(),
+            Health::Error => theme().error.into(),
+        };
+        View::ok(
+            prefix
+                + Markup::text(format!("{} ", mount.mount_point))
+                + Markup::text(format!("{pct:.0}%")).fg(color),
+        )
+        .with_health(health)
+        .with_metric("disk_worst_used_percent", pct)
+    }
+}
+
+/// Undoes the octal escapes (`\040` for a space, ...) `/proc/mounts` uses for
+/// whitespace and backslashes in paths.
+fn unescape_mount_field(field: &str) -> String {
+    let mut out = Vec::with_capacity(field.len());
+    let bytes = field.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u8::from_str_radix(d, 8).ok());
+        match octal {
+            Some(b) if bytes[i] == b'\\' => {
+                out.push(b);
+                i += 4;
+            }
+            _ => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+impl Disk {
+    /// Renders from raw `/sys/class/block/<disk>/stat` contents (and, on first use,
+    /// `queue/hw_sector_size`). The first call only primes the counters.
     pub fn read_markup_from_bytes(
         &mut self,
         stat_bytes: &[u8],
         sector_size_bytes: Option<&[u8]>,
-    ) -> Markup {
+    ) -> View {
+        self.read_markup_from_bytes_at(stat_bytes, sector_size_bytes, Instant::now())
+    }
+
+    /// As [`Self::read_markup_from_bytes`], sampled at `now`.
+    pub fn read_markup_from_bytes_at(
+        &mut self,
+        stat_bytes: &[u8],
+        sector_size_bytes: Option<&[u8]>,
+        now: Instant,
+    ) -> View {
         if self.name.is_none() {
-            return Markup::text(format!("disk {} ", self.display_name()))
-                .append(Markup::text("resolving").fg(VIOLET));
+            return View::degraded(
+                Markup::label(format!("disk {} ", self.display_name()))
+                    .append(Markup::text("resolving").fg(theme().loading)),
+            );
         }
 
         if self.sector_size.is_none() {
@@ -169,57 +424,197 @@ impl Disk {
         }
 
         let Some(sector_size) = self.sector_size else {
-            return Markup::text(format!("disk {} ", self.display_name()))
-                .append(Markup::bracketed(Markup::text("no such disk").fg(BROWN)));
+            self.shown = None;
+            return View::error(
+                Markup::label(format!("disk {} ", self.display_name())).append(Markup::bracketed(
+                    Markup::text("no such disk").fg(theme().missing),
+                )),
+            );
         };
 
         let buf = std::str::from_utf8(stat_bytes).unwrap_or_default();
-        let Some((r, w)) = Self::parse_stat(buf, sector_size) else {
-            return Markup::text(format!("disk {} ", self.display_name()))
-                .append(Markup::bracketed(Markup::text("no such disk").fg(BROWN)));
+        let Some(stat) = Self::parse_stat(buf, sector_size) else {
+            self.shown = None;
+            return View::error(
+                Markup::label(format!("disk {} ", self.display_name())).append(Markup::bracketed(
+                    Markup::text("no such disk").fg(theme().missing),
+                )),
+            );
         };
 
-        let now = Instant::now();
         if !self.initialized {
             self.initialized = true;
-            self.last_r = r;
-            self.last_w = w;
+            self.last = stat;
             self.last_t = now;
-            return Markup::text(format!("disk {} ", self.display_name()))
-                .append(Markup::text("loading").fg(VIOLET));
+            return View::degraded(
+                Markup::label(format!("disk {} ", self.display_name()))
+                    .append(Markup::text("loading").fg(theme().loading)),
+            );
         }
 
         let dt = now.duration_since(self.last_t).as_secs_f64();
-        let dr = r.saturating_sub(self.last_r);
-        let dw = w.saturating_sub(self.last_w);
-        self.last_r = r;
-        self.last_w = w;
+        let rate = |cur: u64, last: u64| {
+            if dt > 0.0 {
+                cur.saturating_sub(last) as f64 / dt
+            } else {
+                0.0
+            }
+        };
+        let bps_read = rate(stat.read_bytes, self.last.read_bytes);
+        let bps_write = rate(stat.write_bytes, self.last.write_bytes);
+        let iops_read = rate(stat.read_ios, self.last.read_ios);
+        let iops_write = rate(stat.write_ios, self.last.write_ios);
+        let ops = (stat.read_ios + stat.write_ios)
+            .saturating_sub(self.last.read_ios + self.last.write_ios);
+        let queued_ms = stat
+            .time_in_queue_ms
+            .saturating_sub(self.last.time_in_queue_ms);
+        // Idle intervals carry no latency sample rather than a misleading 0.
+        let latency_ms = (ops > 0).then(|| queued_ms as f64 / ops as f64);
+        self.last = stat;
         self.last_t = now;
 
-        let bps_read = if dt > 0.0 { dr as f64 / dt } else { 0.0 };
-        let bps_write = if dt > 0.0 { dw as f64 / dt } else { 0.0 };
-        let bps_read = self.read_ema.feed_and_read(bps_read, now).unwrap_or(&0.0);
-        let bps_write = self.write_ema.feed_and_read(bps_write, now).unwrap_or(&0.0);
+        // Feed every smoother each poll so switching modes shows settled values.
+        let bps_read = *self.read_ema.feed_and_read(bps_read, now).unwrap_or(&0.0);
+        let bps_write = *self.write_ema.feed_and_read(bps_write, now).unwrap_or(&0.0);
+        let iops_read = *self
+            .read_iops_ema
+            .feed_and_read(iops_read, now)
+            .unwrap_or(&0.0);
+        let iops_write = *self
+            .write_iops_ema
+            .feed_and_read(iops_write, now)
+            .unwrap_or(&0.0);
+        if self.cfg.auto_calibrate {
+            self.calibrate(bps_read, bps_write, dt);
+        }
 
-        let r_bar = BARS[self
-            .read_threshs
-            .iter()
-            .position(|&t| *bps_read < t)
-            .unwrap_or(BARS.len() - 1)];
-        let w_bar = BARS[self
-            .write_threshs
-            .iter()
-            .position(|&t| *bps_write < t)
-            .unwrap_or(BARS.len() - 1)];
+        let latency_ms = match latency_ms {
+            Some(ms) => *self.latency_ema.feed_and_read(ms, now).unwrap_or(&ms),
+            None => *self.latency_ema.read().unwrap_or(&0.0),
+        };
 
-        Markup::text(format!("disk {} ", self.display_name())).append(Markup::bracketed(
-            Markup::text(r_bar)
-                .fg(BLUE)
-                .append(Markup::text(w_bar).fg(ORANGE)),
-        ))
+        let rates = DiskRates {
+            bps_read,
+            bps_write,
+            iops_read,
+            iops_write,
+            latency_ms,
+            in_flight: stat.in_flight,
+        };
+        self.shown = Some(rates);
+        self.render_rates(&rates)
     }
 
-    pub fn handle_click(_click: crate::core::ClickEvent) {}
+    /// The last I/O sample in the current mode; `None` until one has been
+    /// taken, after a failed read and in `worst` mode, which clicks don't change.
+    pub fn render(&self) -> Option<View> {
+        self.shown.map(|rates| self.render_rates(&rates))
+    }
+
+    fn render_rates(&self, rates: &DiskRates) -> View {
+        let prefix = Markup::label(format!("disk {} ", self.display_name()));
+        if self.mode == DisplayMode::Latency {
+            let latency_ms = rates.latency_ms;
+            let col = color_by_pct_custom(latency_ms, &LATENCY_BREAKPOINTS_MS);
+            // SSDs live below 1 ms; keep a decimal until it stops mattering.
+            let lat = if latency_ms < 10.0 {
+                format!("{latency_ms:.1}ms")
+            } else {
+                format!("{latency_ms:.0}ms")
+            };
+            return View::ok(
+                prefix.append(Markup::bracketed(
+                    Markup::text("lat ")
+                        .append(Markup::text(lat).fg(col))
+                        .append(Markup::text(format!(" q {}", rates.in_flight))),
+                )),
+            );
+        }
+        if self.mode == DisplayMode::Iops {
+            return View::ok(
+                prefix.append(Markup::bracketed(
+                    Markup::text("r ")
+                        .append(Markup::text(format!("{:.0}", rates.iops_read)).fg(Color::Blue))
+                        .append(Markup::text(" w "))
+                        .append(Markup::text(format!("{:.0}", rates.iops_write)).fg(Color::Orange))
+                        .append(Markup::text(" iops")),
+                )),
+            );
+        }
+
+        let r_bar = Self::bar(rates.bps_read, &self.read_threshs);
+        let w_bar = Self::bar(rates.bps_write, &self.write_threshs);
+
+        View::ok(
+            prefix.append(Markup::bracketed(
+                Markup::text(r_bar)
+                    .fg(Color::Blue)
+                    .append(Markup::text(w_bar).fg(Color::Orange)),
+            )),
+        )
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        self.cfg.validate().map_err(anyhow::Error::msg)?;
+        self.cfg.usage_health.validate_above("usage_health")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Disk, BARS, MIN_AUTO_PEAK};
+    use crate::machine::types::Health;
+    use crate::units::testing::plain;
+
+    #[test]
+    fn rate_on_a_threshold_takes_the_next_bar() {
+        let threshs = Disk::log_thresholds(1e9);
+        assert_eq!(Disk::bar(0.0, &threshs), BARS[0]);
+        assert_eq!(Disk::bar(threshs[0] - 1.0, &threshs), BARS[0]);
+        assert_eq!(Disk::bar(threshs[0], &threshs), BARS[1]);
+        assert_eq!(Disk::bar(threshs[7], &threshs), BARS[8]);
+        assert_eq!(Disk::bar(1e12, &threshs), BARS[8]);
+    }
 
-    pub fn fix_up_and_validate() {}
+    #[test]
+    fn auto_peak_jumps_up_and_decays() {
+        let peak = Disk::track_peak(MIN_AUTO_PEAK, 2e8, 1.0, 600.0);
+        assert_eq!(peak, 2e8);
+        // One window later an idle disk has lost a factor of e.
+        let idle = Disk::track_peak(peak, 0.0, 600.0, 600.0);
+        assert!((idle - 2e8 / std::f64::consts::E).abs() < 1.0);
+        assert_eq!(Disk::track_peak(idle, 0.0, 1e6, 600.0), MIN_AUTO_PEAK);
+    }
+
+    #[test]
+    fn worst_mode_reports_the_fullest_real_filesystem() {
+        let disk = Disk::from_cfg(toml::from_str("worst = true").unwrap());
+        let proc_mounts = b"/dev/sda2 / ext4 rw,relatime 0 0\n\
+            proc /proc proc rw 0 0\n\
+            /dev/sda3 /home/my\\040stuff btrfs rw 0 0\n\
+            tmpfs /run tmpfs rw 0 0\n\
+            server:/srv /mnt/nfs nfs4 rw 0 0\n\
+            /dev/sda2 / ext4 rw,relatime 0 0\n";
+        let kept = disk.worst_mount_points(proc_mounts);
+        assert_eq!(kept, ["/", "/home/my stuff"]);
+        assert_eq!(
+            Disk::worst_stat_cmd(&kept)[3..],
+            ["--", "/", "/home/my stuff"]
+        );
+
+        let view = disk.read_worst_mount(b"100 6 4096 /\n100 40 4096 /home/my stuff\n");
+        assert_eq!(plain(&view.body), "disk worst / 94%");
+        assert_eq!(view.health, Health::Degraded);
+        assert_eq!(view.metrics, vec![("disk_worst_used_percent", 94.0)]);
+
+        let view = disk.read_worst_mount(b"100 80 4096 /\n");
+        assert_eq!(view.health, Health::Ok);
+        let view = disk.read_worst_mount(b"");
+        assert_eq!(view.health, Health::Error);
+    }
 }
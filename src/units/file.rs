@@ -0,0 +1,143 @@
+use crate::core::theme;
+use crate::machine::types::View;
+use crate::render::color::Srgb8;
+use crate::render::markup::Markup;
+use crate::units::command::MarkupMode;
+use regex::Regex;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileColorRule {
+    /// Regex searched for in the shown line.
+    pub pattern: String,
+    pub color: Srgb8,
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileConfig {
+    pub path: PathBuf,
+    /// How long a read is reused before the file is read again (seconds).
+    #[serde_inline_default(1.0)]
+    pub refresh_interval_sec: f64,
+    /// The first rule whose pattern matches colors the line.
+    #[serde(default)]
+    pub colors: Vec<FileColorRule>,
+    #[serde_inline_default(MarkupMode::None)]
+    pub markup: MarkupMode,
+}
+
+#[derive(Debug)]
+pub struct File {
+    cfg: FileConfig,
+    /// `cfg.colors`, compiled by `fix_up_and_validate`.
+    rules: Vec<(Regex, Srgb8)>,
+}
+
+impl File {
+    pub fn from_cfg(cfg: FileConfig) -> Self {
+        Self {
+            cfg,
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.cfg.path
+    }
+
+    pub fn refresh_interval_sec(&self) -> f64 {
+        self.cfg.refresh_interval_sec
+    }
+
+    /// Shows the trimmed first line of the contents; `None` (the read
+    /// failed, e.g. no such file) shows `n/a`.
+    pub fn read_markup_from_bytes(&self, bytes: Option<&[u8]>) -> View {
+        let Some(bytes) = bytes else {
+            return View::degraded(Markup::text("n/a").fg(theme().missing));
+        };
+        let text = String::from_utf8_lossy(bytes);
+        let line = text.lines().next().unwrap_or_default().trim();
+        let markup = match self.cfg.markup {
+            MarkupMode::None => Markup::text(line),
+            MarkupMode::Pango => Markup::trusted_pango(line),
+        };
+        match self.rules.iter().find(|(re, _)| re.is_match(line)) {
+            Some((_, color)) => View::ok(markup.fg(*color)),
+            None => View::ok(markup),
+        }
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        self.rules = self
+            .cfg
+            .colors
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|re| (re, rule.color))
+                    .map_err(|e| {
+                        anyhow::anyhow!("bad config: color pattern {:?}: {e}", rule.pattern)
+                    })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{File, FileConfig};
+    use crate::machine::types::Health;
+    use crate::units::testing::{pango, plain};
+
+    fn file(colors: &str) -> File {
+        let cfg: FileConfig = toml::from_str(&format!("path = \"/x\"\n{colors}")).unwrap();
+        File::from_cfg(cfg)
+    }
+
+    #[test]
+    fn first_line_is_colored_by_the_first_matching_rule() {
+        let mut f = file(
+            r##"colors = [
+  { pattern = "^(failed|error)", color = "#CC6666" },
+  { pattern = "^\\d+$", color = "#B5BD68" },
+]"##,
+        );
+        f.fix_up_and_validate().unwrap();
+        assert_eq!(
+            pango(&f.read_markup_from_bytes(Some(b"  128\n255\n"))),
+            "<span color='#B5BD68'>128</span>"
+        );
+        assert_eq!(
+            pango(&f.read_markup_from_bytes(Some(b"failed: x"))),
+            "<span color='#CC6666'>failed: x</span>"
+        );
+        assert_eq!(pango(&f.read_markup_from_bytes(Some(b"idle"))), "idle");
+
+        let missing = f.read_markup_from_bytes(None);
+        assert_eq!(
+            (plain(&missing).as_str(), missing.health),
+            ("n/a", Health::Degraded)
+        );
+    }
+
+    #[test]
+    fn pango_markup_passes_through_and_plain_strips_it() {
+        let f = file("markup = \"pango\"");
+        let view = f.read_markup_from_bytes(Some(b"<b>ok</b> &amp; up"));
+        assert_eq!(pango(&view), "<b>ok</b> &amp; up");
+        assert_eq!(plain(&view), "ok & up");
+        let f = file("");
+        assert_eq!(pango(&f.read_markup_from_bytes(Some(b"<b>"))), "&lt;b&gt;");
+    }
+
+    #[test]
+    fn bad_pattern_fails_validation() {
+        let mut f = file(r##"colors = [{ pattern = "(", color = "#CC6666" }]"##);
+        assert!(f.fix_up_and_validate().is_err());
+    }
+}
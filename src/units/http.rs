@@ -0,0 +1,137 @@
+use crate::core::Color;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+/// Requests are never made more often than this, whatever the config says.
+const MIN_REFRESH_INTERVAL: f64 = 5.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpField {
+    pub label: String,
+    /// JSON Pointer (RFC 6901) into the response, e.g. `/sensors/0/temp`.
+    pub pointer: String,
+    /// Numeric values above this are shown in yellow.
+    #[serde(default)]
+    pub warn_above: Option<f64>,
+    /// Numeric values above this are shown in red.
+    #[serde(default)]
+    pub crit_above: Option<f64>,
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpUnitConfig {
+    pub url: String,
+    /// Shown before the fields.
+    #[serde_inline_default("http".to_string())]
+    pub name: String,
+    #[serde_inline_default(60.0)]
+    pub refresh_interval_sec: f64,
+    #[serde(default)]
+    pub fields: Vec<HttpField>,
+}
+
+#[derive(Debug)]
+pub struct Http {
+    cfg: HttpUnitConfig,
+}
+
+impl Http {
+    pub fn from_cfg(cfg: HttpUnitConfig) -> Self {
+        Self { cfg }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.cfg.url
+    }
+
+    pub fn refresh_interval_sec(&self) -> f64 {
+        self.cfg.refresh_interval_sec
+    }
+
+    fn format_field(field: &HttpField, value: Option<&serde_json::Value>) -> Markup {
+        let label = Markup::text(format!("{} ", field.label));
+        let value = match value {
+            None | Some(serde_json::Value::Null) => {
+                return label + Markup::text("?").fg(Color::Grey)
+            }
+            Some(serde_json::Value::String(s)) => return label + Markup::text(s.as_str()),
+            Some(v) => v,
+        };
+        let text = Markup::text(value.to_string());
+        let color = value.as_f64().and_then(|x| {
+            if field.crit_above.is_some_and(|t| x > t) {
+                Some(Color::Red)
+            } else if field.warn_above.is_some_and(|t| x > t) {
+                Some(Color::Yellow)
+            } else {
+                None
+            }
+        });
+        label + color.map_or(text.clone(), |c| text.fg(c))
+    }
+
+    /// Renders one `label value` segment per configured field from a JSON body.
+    pub fn read_markup_from_body(&self, body: &[u8]) -> Result<Markup, String> {
+        let json: serde_json::Value =
+            serde_json::from_slice(body).map_err(|e| format!("bad json: {e}"))?;
+        let parts = self
+            .cfg
+            .fields
+            .iter()
+            .map(|f| Markup::bracketed(Self::format_field(f, json.pointer(&f.pointer))));
+        Ok(Markup::label(format!("{} ", self.cfg.name)) + Markup::join(" ", parts))
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        let cfg = &mut self.cfg;
+        if cfg.refresh_interval_sec < MIN_REFRESH_INTERVAL {
+            tracing::warn!(
+                "Http refresh interval too low: {:.0}s, using minimum {:.0}s",
+                cfg.refresh_interval_sec,
+                MIN_REFRESH_INTERVAL
+            );
+            cfg.refresh_interval_sec = MIN_REFRESH_INTERVAL;
+        }
+        reqwest::Url::parse(&cfg.url).map_err(|e| anyhow::anyhow!("bad config: url: {e}"))?;
+        for field in &cfg.fields {
+            anyhow::ensure!(
+                field.pointer.is_empty() || field.pointer.starts_with('/'),
+                "bad config: pointer `{}` must start with `/`",
+                field.pointer
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Http, HttpUnitConfig};
+    use crate::units::testing::{pango, plain};
+
+    #[test]
+    fn fields_are_extracted_and_colored() {
+        let cfg: HttpUnitConfig = toml::from_str(
+            r#"
+url = "http://localhost/"
+name = "home"
+fields = [
+    { label = "temp", pointer = "/sensors/0/temp", warn_above = 25.0 },
+    { label = "state", pointer = "/state" },
+    { label = "gone", pointer = "/nope" },
+]
+"#,
+        )
+        .unwrap();
+        let http = Http::from_cfg(cfg);
+        let out = http
+            .read_markup_from_body(br#"{"sensors":[{"temp":27.5}],"state":"<on>"}"#)
+            .unwrap();
+        assert_eq!(plain(&out), "home [temp 27.5] [state <on>] [gone ?]");
+        assert!(pango(&out).contains("color='#F0C674'>27.5<"));
+        assert!(http.read_markup_from_body(b"not json").is_err());
+    }
+}
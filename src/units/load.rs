@@ -0,0 +1,111 @@
+use crate::display::{color_by_pct_custom, format_fixed};
+use crate::mode_enum;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+use sysinfo::System;
+
+mode_enum!(Absolute, PerCore);
+
+#[serde_inline_default]
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct LoadConfig {
+    /// Decimals on each average.
+    #[serde_inline_default(2)]
+    pub precision: u8,
+}
+
+#[derive(Debug)]
+pub struct Load {
+    cfg: LoadConfig,
+    mode: DisplayMode,
+    ncpu: f64,
+    /// The last averages read, re-rendered on mode switches.
+    last: Option<[f64; 3]>,
+}
+
+impl Load {
+    /// Fractions of the CPU count at which load turns from calm to hot.
+    const PER_CORE_BREAKPOINTS: [f64; 4] = [0.1, 0.25, 0.50, 0.75];
+
+    pub fn from_cfg(cfg: LoadConfig) -> Self {
+        Self {
+            cfg,
+            mode: DisplayMode::Absolute,
+            ncpu: f64::from(num_cpus::get().min(u32::MAX as usize) as u32),
+            last: None,
+        }
+    }
+
+    /// 1, 5 and 15 minute averages, colored relative to the CPU count.
+    fn format_load(&self, load: [f64; 3]) -> Markup {
+        let (label, scale) = match self.mode {
+            DisplayMode::Absolute => ("load ", 1.0),
+            DisplayMode::PerCore => ("load/core ", self.ncpu.max(1.0)),
+        };
+        let breakpoints = Self::PER_CORE_BREAKPOINTS.map(|b| b * self.ncpu / scale);
+        let parts = load.map(|l| {
+            let l = l / scale;
+            Markup::text(format_fixed(l, 1, self.cfg.precision))
+                .fg(color_by_pct_custom(l, &breakpoints))
+        });
+        Markup::label(label) + Markup::join(" ", parts)
+    }
+
+    pub fn read_markup(&mut self) -> Markup {
+        let avg = System::load_average();
+        let load = [avg.one, avg.five, avg.fifteen];
+        self.last = Some(load);
+        self.format_load(load)
+    }
+
+    /// The last averages in the current mode; `None` before the first read.
+    pub fn render(&self) -> Option<Markup> {
+        self.last.map(|load| self.format_load(load))
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Load, LoadConfig};
+    use crate::core::ClickEvent;
+    use crate::units::testing::{click, pango, plain};
+
+    #[test]
+    fn per_core_divides_by_cpu_count() {
+        let mut load = Load::from_cfg(LoadConfig { precision: 2 });
+        load.ncpu = 4.0;
+        assert_eq!(
+            plain(&load.format_load([0.4, 0.6, 0.9])),
+            "load 0.40 0.60 0.90"
+        );
+        load.handle_click(click(ClickEvent::BUTTON_LEFT));
+        assert_eq!(
+            plain(&load.format_load([0.4, 0.6, 4.0])),
+            "load/core 0.10 0.15 1.00"
+        );
+    }
+
+    #[test]
+    fn boundary_load_colors_alike_in_both_modes() {
+        let mut load = Load::from_cfg(LoadConfig { precision: 2 });
+        load.ncpu = 4.0;
+        let color = |load: &Load, l: f64| {
+            let out = pango(&load.format_load([l; 3]));
+            out.split("color='").nth(1).map(|s| s[..7].to_string())
+        };
+        // Exactly 0.25 per core sits on a breakpoint and takes the band above.
+        let on_edge = color(&load, 1.0);
+        assert_ne!(on_edge, color(&load, 0.99));
+        load.handle_click(click(ClickEvent::BUTTON_LEFT));
+        assert_eq!(color(&load, 1.0), on_edge);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::core::Color;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lock {
+    Caps,
+    Num,
+    Scroll,
+}
+
+impl Lock {
+    /// The suffix of the matching `/sys/class/leds` entries, e.g. `input3::capslock`.
+    pub fn led_suffix(self) -> &'static str {
+        match self {
+            Lock::Caps => "::capslock",
+            Lock::Num => "::numlock",
+            Lock::Scroll => "::scrolllock",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Lock::Caps => "CAPS",
+            Lock::Num => "NUM",
+            Lock::Scroll => "SCRL",
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocksConfig {
+    #[serde_inline_default(vec![Lock::Caps, Lock::Num])]
+    pub locks: Vec<Lock>,
+    /// Hide unlit indicators instead of dimming them.
+    #[serde_inline_default(false)]
+    pub hide_unlit: bool,
+}
+
+#[derive(Debug)]
+pub struct Locks {
+    cfg: LocksConfig,
+}
+
+impl Locks {
+    pub fn from_cfg(cfg: LocksConfig) -> Self {
+        Self { cfg }
+    }
+
+    pub fn locks(&self) -> &[Lock] {
+        &self.cfg.locks
+    }
+
+    /// LED entries for `lock` among a `/sys/class/leds` listing.
+    pub fn leds_for(lock: Lock, entries: &[String]) -> impl Iterator<Item = &str> {
+        entries
+            .iter()
+            .map(String::as_str)
+            .filter(move |e| e.ends_with(lock.led_suffix()))
+    }
+
+    pub fn parse_brightness(bytes: &[u8]) -> bool {
+        std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+            .is_some_and(|b| b > 0)
+    }
+
+    /// `lit` is parallel to `locks()`.
+    pub fn read_markup(&self, lit: &[bool]) -> Markup {
+        let parts = self.cfg.locks.iter().zip(lit).filter_map(|(lock, &on)| {
+            match (on, self.cfg.hide_unlit) {
+                (true, _) => Some(Markup::text(lock.label()).fg(Color::Yellow)),
+                (false, false) => Some(Markup::text(lock.label()).fg(Color::DarkGrey)),
+                (false, true) => None,
+            }
+        });
+        Markup::label("locks ") + Markup::bracketed(Markup::join(" ", parts))
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lock, Locks};
+    use crate::units::testing::{pango, plain};
+
+    #[test]
+    fn brightness_and_led_names_parse() {
+        assert!(Locks::parse_brightness(b"1\n"));
+        assert!(Locks::parse_brightness(b"255"));
+        assert!(!Locks::parse_brightness(b"0\n"));
+        assert!(!Locks::parse_brightness(b"garbage"));
+
+        let entries = [
+            "input3::capslock",
+            "input3::numlock",
+            "input7::capslock",
+            "phy0-led",
+        ]
+        .map(String::from);
+        let caps: Vec<_> = Locks::leds_for(Lock::Caps, &entries).collect();
+        assert_eq!(caps, ["input3::capslock", "input7::capslock"]);
+        assert_eq!(Locks::leds_for(Lock::Scroll, &entries).count(), 0);
+    }
+
+    #[test]
+    fn unlit_locks_dim_or_hide() {
+        let dim = Locks::from_cfg(toml::from_str("").unwrap());
+        let m = dim.read_markup(&[true, false]);
+        assert_eq!(plain(&m), "locks [CAPS NUM]");
+        assert!(pango(&m).contains("<span color='#F0C674'>CAPS</span>"));
+        assert!(pango(&m).contains("<span color='#373B41'>NUM</span>"));
+
+        let hide = Locks::from_cfg(toml::from_str("hide_unlit = true").unwrap());
+        assert_eq!(plain(&hide.read_markup(&[false, true])), "locks [NUM]");
+    }
+}
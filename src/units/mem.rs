@@ -1,21 +1,34 @@
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::display::{color_by_pct, color_by_pct_custom};
+use crate::machine::types::View;
 use crate::mode_enum;
 use crate::render::markup::Markup;
 use serde::Deserialize;
-use sysinfo::{ProcessesToUpdate, System};
+use serde_inline_default::serde_inline_default;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 
 mode_enum!(Totals, WorstProcess);
 
+#[serde_inline_default]
 #[derive(Debug, Deserialize, Clone, Copy)]
-pub struct MemConfig {}
+pub struct MemConfig {
+    /// Minimum seconds between full process scans in worst-process mode;
+    /// polls in between reuse the last result.
+    #[serde_inline_default(2.0)]
+    pub process_scan_interval_sec: f64,
+}
 
 #[derive(Debug)]
 pub struct Mem {
-    #[allow(dead_code)]
     cfg: MemConfig,
     mode: DisplayMode,
+    /// Reused across polls so refreshes only update what changed.
+    sys: System,
+    /// Name and RSS (bytes) of the largest process at the last scan.
+    worst: Option<(String, u64)>,
+    last_scan: Option<Instant>,
 }
 
 impl Mem {
@@ -23,12 +36,19 @@ impl Mem {
         Self {
             cfg,
             mode: DisplayMode::Totals,
+            sys: System::new(),
+            worst: None,
+            last_scan: None,
         }
     }
-    fn read_formatted_totals() -> Markup {
-        let mut sys = System::new();
-        sys.refresh_memory();
 
+    fn read_formatted_totals(&mut self) -> Markup {
+        self.sys.refresh_memory();
+        self.format_totals()
+    }
+
+    fn format_totals(&self) -> Markup {
+        let sys = &self.sys;
         let total_bytes = sys.total_memory();
         let used_bytes = sys.used_memory();
 
@@ -37,8 +57,8 @@ impl Mem {
         let used_gib = used_bytes as f64 / (1 << 30) as f64;
         let used_percent = used_frac * 100.0;
 
-        let col = crate::render::color::Srgb8::from(color_by_pct(used_percent));
-        Markup::text("mem ")
+        let col = color_by_pct(used_percent);
+        Markup::label("mem ")
             + Markup::bracketed(
                 Markup::text("used ")
                     + Markup::text(format!("{used_gib:>4.1}")).fg(col)
@@ -48,34 +68,54 @@ impl Mem {
             )
     }
 
-    fn read_formatted_worst_rss() -> Markup {
-        let mut sys = System::new();
-        sys.refresh_processes(ProcessesToUpdate::All, true);
-        sys.refresh_memory();
-        let mut max_name = "";
-        let mut max_rss_bytes = 0;
-
-        for process in sys.processes().values() {
-            if let Some(name) = process
-                .exe()
-                .and_then(Path::file_name)
-                .and_then(|s| s.to_str())
-            {
-                let rss = process.memory();
-                if rss > max_rss_bytes {
-                    max_name = name;
-                    max_rss_bytes = rss;
-                }
-            }
+    /// Whether the process table is due for another scan.
+    fn scan_due(&self, now: Instant) -> bool {
+        let interval = Duration::from_secs_f64(self.cfg.process_scan_interval_sec.max(0.0));
+        self.last_scan
+            .is_none_or(|last| now.duration_since(last) >= interval)
+    }
+
+    fn scan_worst_process(&mut self) {
+        // Memory and (once per process) the executable path are all we need.
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        self.worst = self
+            .sys
+            .processes()
+            .values()
+            .filter_map(|process| {
+                let name = process.exe().and_then(Path::file_name)?.to_str()?;
+                Some((name, process.memory()))
+            })
+            .max_by_key(|&(_, rss)| rss)
+            .map(|(name, rss)| (name.to_string(), rss));
+    }
+
+    fn read_formatted_worst_rss(&mut self) -> Markup {
+        let now = Instant::now();
+        if self.scan_due(now) {
+            self.scan_worst_process();
+            self.last_scan = Some(now);
         }
+        self.sys.refresh_memory();
+        self.format_worst_rss()
+    }
+
+    fn format_worst_rss(&self) -> Markup {
+        let (max_name, max_rss_bytes) = match &self.worst {
+            Some((name, rss)) => (name.as_str(), *rss),
+            None => ("", 0),
+        };
 
         let max_rss_gib = max_rss_bytes as f64 / (1 << 30) as f64;
-        let max_rss_rel = max_rss_bytes as f64 / sys.total_memory() as f64 * 100.0;
-        let col = crate::render::color::Srgb8::from(color_by_pct_custom(
-            max_rss_rel,
-            &[5.0, 10.0, 20.0, 50.0],
-        ));
-        Markup::text("mem ")
+        let max_rss_rel = max_rss_bytes as f64 / self.sys.total_memory() as f64 * 100.0;
+        let col = color_by_pct_custom(max_rss_rel, &[5.0, 10.0, 20.0, 50.0]);
+        Markup::label("mem ")
             + Markup::bracketed(
                 Markup::text("worst ")
                     + Markup::text(max_name)
@@ -85,16 +125,59 @@ impl Mem {
             )
     }
 
-    pub fn read_markup(&self) -> Markup {
-        match self.mode {
-            DisplayMode::Totals => Self::read_formatted_totals(),
-            DisplayMode::WorstProcess => Self::read_formatted_worst_rss(),
-        }
+    pub fn read_markup(&mut self) -> View {
+        let body = match self.mode {
+            DisplayMode::Totals => self.read_formatted_totals(),
+            DisplayMode::WorstProcess => self.read_formatted_worst_rss(),
+        };
+        self.with_metrics(body)
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    /// The last read in the current mode, without refreshing; `None` before
+    /// the first read, or before the first process scan in worst-process mode.
+    pub fn render(&self) -> Option<View> {
+        let body = match self.mode {
+            _ if self.sys.total_memory() == 0 => return None,
+            DisplayMode::Totals => self.format_totals(),
+            DisplayMode::WorstProcess if self.last_scan.is_none() => return None,
+            DisplayMode::WorstProcess => self.format_worst_rss(),
+        };
+        Some(self.with_metrics(body))
     }
 
-    pub fn fix_up_and_validate() {}
+    fn with_metrics(&self, body: Markup) -> View {
+        let used = self.sys.used_memory() as f64;
+        View::ok(body)
+            .with_metric("mem_used_bytes", used)
+            .with_metric(
+                "mem_used_percent",
+                used / self.sys.total_memory() as f64 * 100.0,
+            )
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mem, MemConfig};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn process_scan_is_throttled() {
+        let mut mem = Mem::from_cfg(MemConfig {
+            process_scan_interval_sec: 2.0,
+        });
+        let t0 = Instant::now();
+        assert!(mem.scan_due(t0));
+        mem.last_scan = Some(t0);
+        assert!(!mem.scan_due(t0 + Duration::from_secs(1)));
+        assert!(mem.scan_due(t0 + Duration::from_secs(2)));
+    }
 }
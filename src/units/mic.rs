@@ -0,0 +1,184 @@
+use crate::core::{ClickEvent, Color};
+use crate::display::color_by_pct;
+use crate::render::markup::Markup;
+use crate::util::ScrollAccumulator;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+use std::time::Instant;
+
+const SOURCE: &str = "@DEFAULT_AUDIO_SOURCE@";
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MicConfig {
+    /// How long a source read is reused before shelling out again (seconds).
+    #[serde_inline_default(1.0)]
+    pub refresh_interval_sec: f64,
+    /// Level change per scroll step (percent).
+    #[serde_inline_default(5)]
+    pub volume_step_pct: u8,
+    /// Scroll events per volume step; raise for high-resolution touchpads.
+    #[serde_inline_default(1)]
+    pub scroll_threshold: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicAction {
+    ToggleMute,
+    Raise,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceState {
+    pub level: f64,
+    pub muted: bool,
+}
+
+#[derive(Debug)]
+pub struct Mic {
+    cfg: MicConfig,
+    pending: Vec<MicAction>,
+    scroll: ScrollAccumulator,
+}
+
+impl Mic {
+    pub fn from_cfg(cfg: MicConfig) -> Self {
+        Self {
+            scroll: ScrollAccumulator::new(cfg.scroll_threshold),
+            cfg,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn refresh_interval_sec(&self) -> f64 {
+        self.cfg.refresh_interval_sec
+    }
+
+    pub fn volume_cmd() -> Vec<String> {
+        ["wpctl", "get-volume", SOURCE]
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Lists recording streams; any output means some app has the mic open.
+    pub fn in_use_cmd() -> Vec<String> {
+        ["pactl", "list", "short", "source-outputs"]
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn action_cmd(&self, action: MicAction) -> Vec<String> {
+        let step = self.cfg.volume_step_pct;
+        let source = SOURCE.to_string();
+        match action {
+            MicAction::ToggleMute => {
+                vec!["wpctl".into(), "set-mute".into(), source, "toggle".into()]
+            }
+            // `-l 1.0` keeps scrolling from amplifying past 100%.
+            MicAction::Raise => vec![
+                "wpctl".into(),
+                "set-volume".into(),
+                "-l".into(),
+                "1.0".into(),
+                source,
+                format!("{step}%+"),
+            ],
+            MicAction::Lower => vec![
+                "wpctl".into(),
+                "set-volume".into(),
+                source,
+                format!("{step}%-"),
+            ],
+        }
+    }
+
+    pub fn take_pending(&mut self) -> Vec<MicAction> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Parses `wpctl get-volume` output, e.g. `Volume: 0.45 [MUTED]`.
+    pub fn parse_volume(stdout: &[u8]) -> Option<SourceState> {
+        let s = std::str::from_utf8(stdout).ok()?;
+        let rest = s.trim().strip_prefix("Volume:")?;
+        let mut parts = rest.split_whitespace();
+        let level = parts.next()?.parse::<f64>().ok()?;
+        let muted = parts.any(|p| p == "[MUTED]");
+        Some(SourceState { level, muted })
+    }
+
+    pub fn read_markup(&self, source: Option<SourceState>, in_use: bool) -> Markup {
+        let Some(source) = source else {
+            return Markup::label("mic ") + Markup::text("no source").fg(Color::Grey);
+        };
+        let body = if source.muted {
+            Markup::text("🔇 muted").fg(Color::Grey)
+        } else {
+            let pct = source.level * 100.0;
+            Markup::text("🎤 ") + Markup::text(format!("{pct:>3.0}%")).fg(color_by_pct(pct))
+        };
+        let rec = if in_use {
+            Markup::text(" ") + Markup::bracketed(Markup::text("rec").fg(Color::Red))
+        } else {
+            Markup::text(" ") + Markup::bracketed(Markup::text("idle").fg(Color::Green))
+        };
+        Markup::label("mic ") + body + rec
+    }
+
+    pub fn handle_click(&mut self, click: ClickEvent) {
+        let action = match click.button {
+            ClickEvent::BUTTON_LEFT => MicAction::ToggleMute,
+            _ => match self.scroll.feed(&click, Instant::now()) {
+                1 => MicAction::Raise,
+                -1 => MicAction::Lower,
+                _ => return,
+            },
+        };
+        self.pending.push(action);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mic, MicAction, SourceState};
+    use crate::core::ClickEvent;
+    use crate::units::testing::click;
+
+    #[test]
+    fn parses_wpctl_volume() {
+        assert_eq!(
+            Mic::parse_volume(b"Volume: 0.45 [MUTED]\n"),
+            Some(SourceState {
+                level: 0.45,
+                muted: true
+            })
+        );
+        assert_eq!(
+            Mic::parse_volume(b"Volume: 1.00\n"),
+            Some(SourceState {
+                level: 1.0,
+                muted: false
+            })
+        );
+        assert_eq!(Mic::parse_volume(b"garbage"), None);
+    }
+
+    #[test]
+    fn fine_scroll_is_accumulated() {
+        let mut mic = Mic::from_cfg(toml::from_str("scroll_threshold = 2").unwrap());
+        for _ in 0..5 {
+            mic.handle_click(click(ClickEvent::BUTTON_SCROLL_UP));
+        }
+        mic.handle_click(click(ClickEvent::BUTTON_LEFT));
+        assert_eq!(
+            mic.take_pending(),
+            [MicAction::Raise, MicAction::Raise, MicAction::ToggleMute]
+        );
+    }
+}
@@ -1,5 +1,8 @@
-use crate::core::{ClickEvent, GREEN, GREY, ORANGE, RED, VIOLET};
-use crate::display::{color_by_pct_custom, COL_USE_HIGH, COL_USE_NORM, COL_USE_VERY_HIGH};
+use crate::core::{theme, ClickEvent, Color};
+use crate::display::{
+    color_by_pct_custom, format_fixed, COL_USE_HIGH, COL_USE_NORM, COL_USE_VERY_HIGH,
+};
+use crate::machine::types::View;
 use crate::mode_enum;
 use crate::render::markup::Markup;
 use crate::util::{Ema, Smoother};
@@ -25,6 +28,44 @@ pub struct NetConfig {
 
     #[serde_inline_default(25)]
     pub ping_window: usize,
+
+    /// Decimals on the bandwidth figures.
+    #[serde_inline_default(0)]
+    pub bandwidth_precision: u8,
+
+    /// Send upload and download as separate i3bar blocks.
+    #[serde_inline_default(false)]
+    pub split_bandwidth: bool,
+
+    #[serde_inline_default(PingFamily::Auto)]
+    pub ping_family: PingFamily,
+
+    /// Seconds between pings (`ping -i`).
+    #[serde_inline_default(1.0)]
+    pub ping_interval_sec: f64,
+
+    /// ICMP payload bytes (`ping -s`).
+    #[serde_inline_default(56)]
+    pub ping_packet_size: u32,
+
+    /// Consecutive carrier-down reads before the link shows as down; a
+    /// single carrier-up read recovers.
+    #[serde_inline_default(3)]
+    pub carrier_down_polls: u32,
+
+    /// Decimal (1000-based, `MB/s`) bandwidth magnitudes instead of binary
+    /// (1024-based, `MiB/s`).
+    #[serde_inline_default(false)]
+    pub si_units: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PingFamily {
+    /// `-6` for IPv6 literals, `-4` for IPv4 literals; hostnames use `ping`'s default.
+    Auto,
+    V4,
+    V6,
 }
 
 #[derive(Debug)]
@@ -42,23 +83,44 @@ pub struct Net {
     rxtx: Option<RxTxRecord>,
     rx_ema: Ema<f64>,
     tx_ema: Ema<f64>,
+    /// Enumerated on first use, then only refreshed.
+    networks: Option<Networks>,
     // ping
     ping_times: VecDeque<f64>,
-    ping_received: usize,
-    ping_last_seq: u32,
+    /// Sequence numbers received within the last `ping_window` sequence numbers.
+    ping_seqs: VecDeque<u32>,
+    /// First sequence number since `ping` (re)started; earlier ones were never expected.
+    ping_first_seq: Option<u32>,
+    /// Highest sequence number `ping` has reported on, answered or not.
+    ping_last_seq: Option<u32>,
+    /// Sequence numbers, within the same window, that drew a duplicate reply.
+    ping_dups: VecDeque<u32>,
+    /// Per split block (up, down): show bytes moved instead of the rate.
+    show_totals: [bool; 2],
+    /// Consecutive polls that read the carrier as down.
+    carrier_down_streak: u32,
+    /// Smoothed rates and byte counters (`[down, up]`) of the last bandwidth
+    /// read, re-rendered when a click flips a split block.
+    last_bandwidth: Option<([f64; 2], [u64; 2])>,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 struct PingOutput {
     // Typical line: "64 bytes from 8.8.8.8: icmp_seq=1 ttl=117 time=25.6 ms"
-    bytes: u32,
-    ip: String,
     icmp_seq: u32,
-    ttl: u32,
     time_ms: f64,
 }
 
+/// The `ping -O` lines that matter for latency and loss accounting.
+#[derive(Debug)]
+enum PingLine {
+    Reply(PingOutput),
+    /// A reply already seen, suffixed with `(DUP!)`.
+    Dup(u32),
+    /// `no answer yet for icmp_seq=N`, printed when the next request goes out.
+    NoAnswer(u32),
+}
+
 impl Net {
     pub fn from_cfg(cfg: NetConfig) -> Self {
         let ping_times = VecDeque::with_capacity(cfg.ping_window);
@@ -67,104 +129,295 @@ impl Net {
             rxtx: None,
             rx_ema: Ema::new(cfg.smoothing_window_sec),
             tx_ema: Ema::new(cfg.smoothing_window_sec),
+            networks: None,
             ping_times,
-            ping_received: 0,
-            ping_last_seq: 0,
+            ping_seqs: VecDeque::new(),
+            ping_first_seq: None,
+            ping_last_seq: None,
+            ping_dups: VecDeque::new(),
+            show_totals: [false; 2],
+            carrier_down_streak: 0,
+            last_bandwidth: None,
             cfg,
         }
     }
+    /// The long-running `ping` invocation for the configured server and family.
+    pub fn ping_cmd(&self) -> Vec<String> {
+        let family = match self.cfg.ping_family {
+            PingFamily::V4 => Some("-4"),
+            PingFamily::V6 => Some("-6"),
+            PingFamily::Auto => match self.cfg.ping_server.parse::<std::net::IpAddr>() {
+                Ok(std::net::IpAddr::V4(_)) => Some("-4"),
+                Ok(std::net::IpAddr::V6(_)) => Some("-6"),
+                Err(_) => None,
+            },
+        };
+        let mut cmd = vec!["ping".to_string(), "-n".to_string(), "-O".to_string()];
+        cmd.extend(family.map(str::to_string));
+        cmd.extend([
+            "-i".to_string(),
+            self.cfg.ping_interval_sec.to_string(),
+            "-s".to_string(),
+            self.cfg.ping_packet_size.to_string(),
+        ]);
+        cmd.extend([
+            "-I".to_string(),
+            self.cfg.interface.clone(),
+            self.cfg.ping_server.clone(),
+        ]);
+        cmd
+    }
+
+    /// Silence after which the `ping` process is presumed hung and restarted.
+    pub fn ping_max_idle(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64((10.0 * self.cfg.ping_interval_sec).max(10.0))
+    }
+
     fn stop_ping(&mut self) {
         self.ping_times.clear();
+        self.reset_seqs();
+    }
+
+    fn reset_seqs(&mut self) {
+        self.ping_seqs.clear();
+        self.ping_first_seq = None;
+        self.ping_last_seq = None;
+        self.ping_dups.clear();
     }
 
     fn refresh_ping_buffer_from(&mut self, lines: Vec<String>) {
         for line in lines {
-            if self.ping_times.len() == self.cfg.ping_window {
-                self.ping_times.pop_front();
+            match Self::parse_ping_line(&line) {
+                Some(PingLine::Reply(po)) => {
+                    if self.ping_times.len() == self.cfg.ping_window {
+                        self.ping_times.pop_front();
+                    }
+                    self.ping_times.push_back(po.time_ms);
+                    self.record_seq(po.icmp_seq, true);
+                }
+                Some(PingLine::NoAnswer(seq)) => self.record_seq(seq, false),
+                Some(PingLine::Dup(seq)) => {
+                    self.ping_dups.push_back(seq);
+                    self.trim_to_window();
+                }
+                None => {}
             }
+        }
+    }
 
-            let slice = line.as_str();
-            let po: PingOutput = match scan!("{} bytes from {}: icmp_seq={} ttl={} time={} ms" <- slice)
-            {
-                Ok(po) => po,
-                Err(_) => continue,
-            };
+    /// Notes that `seq` was sent, and whether it has been answered. A late
+    /// reply after its "no answer yet" line still counts as received.
+    fn record_seq(&mut self, seq: u32, answered: bool) {
+        let window = self.cfg.ping_window.max(1) as u32;
+        // A sequence falling behind the window, or a repeat not marked as
+        // a DUP, means `ping` restarted (or wrapped).
+        let restarted = self.ping_last_seq.is_some_and(|last| seq + window <= last)
+            || (answered && self.ping_seqs.contains(&seq));
+        if restarted {
+            self.reset_seqs();
+        }
+        self.ping_first_seq.get_or_insert(seq);
+        self.ping_last_seq = Some(self.ping_last_seq.map_or(seq, |l| l.max(seq)));
+        if answered {
+            let at = self.ping_seqs.partition_point(|&s| s < seq);
+            self.ping_seqs.insert(at, seq);
+        }
+        self.trim_to_window();
+    }
 
-            self.ping_times.push_back(po.time_ms);
-            self.ping_last_seq = po.icmp_seq;
-            self.ping_received += 1;
+    /// Forgets replies and duplicates older than the last `ping_window`
+    /// sequence numbers.
+    fn trim_to_window(&mut self) {
+        let Some(last) = self.ping_last_seq else {
+            return;
+        };
+        let window = self.cfg.ping_window.max(1) as u32;
+        let stale = |&s: &u32| s + window <= last;
+        while self.ping_seqs.front().is_some_and(stale) {
+            self.ping_seqs.pop_front();
         }
+        self.ping_dups.retain(|s| !stale(s));
     }
 
-    fn median_and_mad(samples: &[f64]) -> (f64, f64) {
-        let mut v = samples.to_vec();
-        v.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let median = v[v.len() / 2];
+    /// Loss over the last `ping_window` sequence numbers, as a percentage.
+    fn window_loss_pct(&self) -> f64 {
+        let (Some(last), Some(first)) = (self.ping_last_seq, self.ping_first_seq) else {
+            return 0.0;
+        };
+        let window = self.cfg.ping_window.max(1) as u32;
+        let start = first.max((last + 1).saturating_sub(window));
+        let expected = last - start + 1;
+        100.0 - 100.0 * self.ping_seqs.len() as f64 / f64::from(expected)
+    }
+
+    /// The only parser for `ping` output; every line from the `ProcBatch` goes
+    /// through here. `None` for headers, summaries and errors.
+    fn parse_ping_line(line: &str) -> Option<PingLine> {
+        if let Some(seq) = line.strip_prefix("no answer yet for icmp_seq=") {
+            return seq.trim().parse().ok().map(PingLine::NoAnswer);
+        }
+        // Split on the separator first: IPv6 addresses contain `:` themselves,
+        // so a single `from {}:` pattern would stop inside the address.
+        let (head, tail) = line.split_once(": icmp_seq=")?;
+        let (_bytes, _ip): (u32, String) = scan!("{} bytes from {}" <- head).ok()?;
+        let (tail, dup) = match tail.strip_suffix(" (DUP!)") {
+            Some(tail) => (tail, true),
+            None => (tail, false),
+        };
+        let (icmp_seq, _ttl, time_ms): (u32, u32, f64) =
+            scan!("{} ttl={} time={} ms" <- tail).ok()?;
+        if dup {
+            return Some(PingLine::Dup(icmp_seq));
+        }
+        Some(PingLine::Reply(PingOutput { icmp_seq, time_ms }))
+    }
+
+    /// Median and median absolute deviation of the finite samples, or `None`
+    /// if there are none.
+    fn median_and_mad(samples: &[f64]) -> Option<(f64, f64)> {
+        let mut v: Vec<f64> = samples.iter().copied().filter(|x| x.is_finite()).collect();
+        v.sort_by(f64::total_cmp);
+        let median = *v.get(v.len() / 2)?;
         // absolute deviations
         let mut devs: Vec<f64> = v.iter().map(|x| (x - median).abs()).collect();
-        devs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        devs.sort_by(f64::total_cmp);
         let mad = devs[devs.len() / 2];
-        (median, mad)
+        Some((median, mad))
     }
 
-    pub(crate) fn read_formatted_ping(&mut self, lines: Vec<String>) -> Markup {
+    pub(crate) fn read_formatted_ping(&mut self, lines: Vec<String>) -> View {
         self.refresh_ping_buffer_from(lines);
-        let prefix = Markup::text(format!(
+        self.last_bandwidth = None;
+        self.ping_view()
+    }
+
+    /// The current mode from what was last read, without reading again;
+    /// `None` in bandwidth mode until a rate has been measured.
+    pub fn render(&self) -> Option<View> {
+        match self.mode {
+            DisplayMode::Ping => Some(self.ping_view()),
+            DisplayMode::Bandwidth => {
+                let (bps, bytes) = self.last_bandwidth?;
+                let prefix = Markup::label(format!("net {} ", self.cfg.interface));
+                Some(self.bandwidth_view(prefix, bps, bytes))
+            }
+        }
+    }
+
+    fn ping_view(&self) -> View {
+        let prefix = Markup::label(format!(
             "net {} [ping {}] ",
             &self.cfg.interface, &self.cfg.ping_server
         ));
 
+        let loss_pct = self.window_loss_pct();
+        // Nothing answered within the window: no latency to show, but the
+        // outage itself is the news.
+        if self.ping_seqs.is_empty() && self.ping_last_seq.is_some() {
+            return View::degraded(
+                prefix
+                    + Markup::bracketed(
+                        Markup::text(format!("{loss_pct:.0}% loss")).fg(Color::Red),
+                    ),
+            );
+        }
         if self.ping_times.len() < 2 {
-            return prefix + Markup::text("loading").fg(VIOLET);
+            return View::degraded(prefix + Markup::text("loading").fg(theme().loading));
         }
+        let samples: Vec<f64> = self.ping_times.iter().copied().collect();
+        let Some((med, mad)) = Self::median_and_mad(&samples) else {
+            return View::degraded(prefix + Markup::text("loading").fg(theme().loading));
+        };
 
-        let (med, mad) = Self::median_and_mad(self.ping_times.make_contiguous());
         let med_str = Markup::text(format!("{med:>3.1}"))
             .fg(color_by_pct_custom(med, &[10.0, 20.0, 30.0, 90.0]));
         let mad_str = Markup::text(format!("{mad:>2.1}"))
             .fg(color_by_pct_custom(mad, &[2.0, 5.0, 10.0, 30.0]));
-        let loss_pct = 100.0 - 100.0 * self.ping_received as f64 / self.ping_last_seq as f64;
-        let loss_str = if loss_pct > 0.0 {
-            Markup::text(format!("{loss_pct:>3.1}% loss")).fg(ORANGE)
+        let mut loss_str = if loss_pct > 0.0 {
+            Markup::text(format!("{loss_pct:>3.1}% loss")).fg(Color::Orange)
         } else {
-            Markup::text("no loss").fg(GREEN)
+            Markup::text("no loss").fg(Color::Green)
         };
+        if !self.ping_dups.is_empty() {
+            let dups = self.ping_dups.len();
+            loss_str = loss_str + Markup::text(format!(" {dups} dup")).fg(Color::Orange);
+        }
 
-        prefix
-            + Markup::bracketed(
-                Markup::text("med ")
-                    + med_str
-                    + Markup::text(" mad ")
-                    + mad_str
-                    + Markup::text(" ms"),
-            )
-            + Markup::text(" ")
-            + Markup::bracketed(loss_str)
+        View::ok(
+            prefix
+                + Markup::bracketed(
+                    Markup::text("med ")
+                        + med_str
+                        + Markup::text(" mad ")
+                        + mad_str
+                        + Markup::text(" ms"),
+                )
+                + Markup::text(" ")
+                + Markup::bracketed(loss_str),
+        )
     }
 
     // STATS
-    pub(crate) fn read_formatted_stats(&mut self, carrier: Option<&[u8]>) -> Markup {
-        let nets = Networks::new_with_refreshed_list();
+    /// Feeds one `carrier` read (unreadable counts as up) into the debounce.
+    fn carrier_debounced_down(&mut self, carrier: Option<&[u8]>) -> bool {
+        let down = carrier
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .is_some_and(|v| v.trim() == "0");
+        self.carrier_down_streak = if down {
+            self.carrier_down_streak.saturating_add(1)
+        } else {
+            0
+        };
+        self.carrier_down_streak >= self.cfg.carrier_down_polls.max(1)
+    }
+
+    /// `bps` in the largest magnitude it exceeds, with that magnitude's prefix
+    /// (`M` or `Mi`) and color; `None` below a kilobyte.
+    fn scale_bandwidth(bps: f64, si_units: bool) -> Option<(f64, &'static str, Color)> {
+        let base: f64 = if si_units { 1000.0 } else { 1024.0 };
+        [
+            (3, "G", "Gi", COL_USE_VERY_HIGH),
+            (2, "M", "Mi", COL_USE_HIGH),
+            (1, "k", "Ki", COL_USE_NORM),
+        ]
+        .into_iter()
+        .find_map(|(exp, si, binary, col)| {
+            let den = base.powi(exp);
+            (bps > den).then(|| (bps / den, if si_units { si } else { binary }, col))
+        })
+    }
+
+    pub(crate) fn read_formatted_stats(&mut self, carrier: Option<&[u8]>) -> View {
+        self.last_bandwidth = None;
+        let carrier_down = self.carrier_debounced_down(carrier);
+        let nets = match &mut self.networks {
+            Some(nets) => {
+                nets.refresh(true);
+                nets
+            }
+            None => self.networks.insert(Networks::new_with_refreshed_list()),
+        };
         let Some(net) = nets.get(self.cfg.interface.as_str()) else {
-            return Markup::text(format!("net {} ", self.cfg.interface))
-                + Markup::text("gone").fg(RED);
+            return View::error(
+                Markup::label(format!("net {} ", self.cfg.interface))
+                    + Markup::text("gone").fg(theme().missing),
+            );
         };
-        if carrier
-            .and_then(|bytes| std::str::from_utf8(bytes).ok())
-            .is_some_and(|v| v.trim() == "0")
-        {
-            return Markup::text(format!("net {} ", self.cfg.interface))
-                + Markup::text("down").fg(RED);
+        if carrier_down {
+            return View::degraded(
+                Markup::label(format!("net {} ", self.cfg.interface))
+                    + Markup::text("down").fg(Color::Red),
+            );
         }
 
-        let prefix = Markup::text(format!("net {} ", self.cfg.interface));
-
         let now0 = Instant::now();
         let rx_bytes = net.total_received();
         let tx_bytes = net.total_transmitted();
         let now1 = Instant::now();
         let now = now0 + (now1.duration_since(now0) / 2);
 
+        let prefix = Markup::label(format!("net {} ", self.cfg.interface));
+
         let cur_rxtx = RxTxRecord {
             rx: rx_bytes,
             tx: tx_bytes,
@@ -173,7 +426,7 @@ impl Net {
 
         let Some(prev_rxtx) = self.rxtx.take() else {
             self.rxtx = Some(cur_rxtx);
-            return prefix + Markup::text("loading").fg(VIOLET);
+            return View::degraded(prefix + Markup::text("loading").fg(theme().loading));
         };
 
         let dt_sec = cur_rxtx.time.duration_since(prev_rxtx.time).as_secs_f64();
@@ -187,42 +440,321 @@ impl Net {
         self.rx_ema.feed(bps_down, now);
         self.tx_ema.feed(bps_up, now);
 
-        let bps_down = self.rx_ema.read().unwrap_or(&0.0);
-        let bps_up = self.tx_ema.read().unwrap_or(&0.0);
+        let bps_down = *self.rx_ema.read().unwrap_or(&0.0);
+        let bps_up = *self.tx_ema.read().unwrap_or(&0.0);
+        self.last_bandwidth = Some(([bps_down, bps_up], [rx_bytes, tx_bytes]));
+        self.bandwidth_view(prefix, [bps_down, bps_up], [rx_bytes, tx_bytes])
+    }
+
+    /// The bandwidth view for smoothed rates and the interface's byte
+    /// counters, both ordered `[down, up]`.
+    fn bandwidth_view(&self, prefix: Markup, bps: [f64; 2], bytes: [u64; 2]) -> View {
+        let metrics = vec![
+            ("net_rx_bytes_per_second", bps[0]),
+            ("net_tx_bytes_per_second", bps[1]),
+        ];
 
-        let mut sfs = [Markup::text("B/s").fg(GREY), Markup::text("B/s").fg(GREY)];
-        let mut vals = [*bps_down, *bps_up];
+        // (suffix, short-form suffix) per direction.
+        let mut sfs = [
+            (Markup::text("B/s").fg(Color::Grey), Markup::empty()),
+            (Markup::text("B/s").fg(Color::Grey), Markup::empty()),
+        ];
+        let mut vals = bps;
         // Order: [down, up]
         for ix in 0..2 {
-            for (mag, sf) in &[
-                (30u64, Markup::text("G/s").fg(COL_USE_VERY_HIGH)),
-                (20u64, Markup::text("M/s").fg(COL_USE_HIGH)),
-                (10u64, Markup::text("K/s").fg(COL_USE_NORM)),
-            ] {
-                let den = f64::from(1u32 << *mag as u32);
-                if vals[ix] > den {
-                    vals[ix] /= den;
-                    sfs[ix] = sf.clone();
-                    break;
-                }
+            if let Some((val, sf, col)) = Self::scale_bandwidth(vals[ix], self.cfg.si_units) {
+                vals[ix] = val;
+                sfs[ix] = (
+                    Markup::text(format!("{sf}B/s")).fg(col),
+                    Markup::text(sf).fg(col),
+                );
             }
         }
 
-        prefix
-            + Markup::bracketed(Markup::text(format!("u {:>4.0} ", vals[1])) + sfs[1].clone())
-            + Markup::text(" ")
-            + Markup::bracketed(Markup::text(format!("d {:4.0} ", vals[0])) + sfs[0].clone())
+        let p = self.cfg.bandwidth_precision;
+        // Short form: no interface, brackets, padding or "/s".
+        let short = Markup::label("net ")
+            + Markup::text(format!("u{}", format_fixed(vals[1], 0, p)))
+            + sfs[1].1.clone()
+            + Markup::text(format!(" d{}", format_fixed(vals[0], 0, p)))
+            + sfs[0].1.clone();
+        let up = Markup::bracketed(
+            Markup::text(format!("u {} ", format_fixed(vals[1], 4, p))) + sfs[1].0.clone(),
+        );
+        let down = Markup::bracketed(
+            Markup::text(format!("d {} ", format_fixed(vals[0], 4, p))) + sfs[0].0.clone(),
+        );
+        let view = if self.cfg.split_bandwidth {
+            let total = |dir: &str, bytes: u64| {
+                let (val, sf) = Self::scale_bandwidth(bytes as f64, self.cfg.si_units)
+                    .map_or((bytes as f64, ""), |(val, sf, _)| (val, sf));
+                Markup::bracketed(
+                    Markup::text(format!("{dir} {} ", format_fixed(val, 4, p)))
+                        + Markup::text(format!("{sf}B")).fg(Color::Grey),
+                )
+            };
+            let up = if self.show_totals[0] {
+                total("u", bytes[1])
+            } else {
+                up
+            };
+            let down = if self.show_totals[1] {
+                total("d", bytes[0])
+            } else {
+                down
+            };
+            View::ok(prefix + up)
+                .with_extra(vec![down])
+                .with_short(short)
+        } else {
+            View::ok(prefix + up + Markup::text(" ") + down).with_short(short)
+        };
+        View { metrics, ..view }
     }
 }
 
 impl Net {
-    pub fn handle_click(&mut self, _click: ClickEvent) {
-        self.mode = match self.mode {
-            DisplayMode::Bandwidth => DisplayMode::Ping,
-            DisplayMode::Ping => {
-                self.stop_ping();
-                DisplayMode::Bandwidth
+    /// `ping` refuses intervals below this without root or `CAP_NET_RAW`.
+    const UNPRIVILEGED_MIN_PING_INTERVAL: f64 = 0.2;
+    /// `ping` rejects intervals below this even when privileged.
+    const MIN_PING_INTERVAL: f64 = 0.002;
+    /// Largest ICMP payload that fits an IPv4 packet.
+    const MAX_PING_PACKET_SIZE: u32 = 65507;
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        let cfg = &mut self.cfg;
+        if cfg.ping_interval_sec.is_nan() || cfg.ping_interval_sec < Self::MIN_PING_INTERVAL {
+            tracing::warn!(
+                "Net ping interval {}s is too low, using minimum {}s",
+                cfg.ping_interval_sec,
+                Self::MIN_PING_INTERVAL
+            );
+            cfg.ping_interval_sec = Self::MIN_PING_INTERVAL;
+        }
+        if cfg.ping_interval_sec < Self::UNPRIVILEGED_MIN_PING_INTERVAL {
+            tracing::warn!(
+                "Net ping interval {}s is below {}s; ping will refuse it without CAP_NET_RAW",
+                cfg.ping_interval_sec,
+                Self::UNPRIVILEGED_MIN_PING_INTERVAL
+            );
+        }
+        if cfg.ping_packet_size > Self::MAX_PING_PACKET_SIZE {
+            tracing::warn!(
+                "Net ping packet size {} is too large, using {}",
+                cfg.ping_packet_size,
+                Self::MAX_PING_PACKET_SIZE
+            );
+            cfg.ping_packet_size = Self::MAX_PING_PACKET_SIZE;
+        }
+        Ok(())
+    }
+
+    pub fn handle_click(&mut self, click: ClickEvent) {
+        // Split blocks are told apart by `instance`: a middle-click flips
+        // that block alone between rate and total.
+        if self.cfg.split_bandwidth
+            && self.mode == DisplayMode::Bandwidth
+            && click.button == ClickEvent::BUTTON_MIDDLE
+        {
+            let block = click
+                .instance
+                .as_deref()
+                .and_then(|i| i.parse::<usize>().ok());
+            if let Some(shown) = block.and_then(|ix| self.show_totals.get_mut(ix)) {
+                *shown = !*shown;
+                return;
             }
+        }
+        if self.mode == DisplayMode::Ping {
+            self.stop_ping();
+        }
+        self.mode = self.mode.rotate(&click);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Net, NetConfig, PingLine};
+    use crate::machine::types::Health;
+    use crate::units::testing::plain;
+
+    fn net(extra: &str) -> Net {
+        let cfg: NetConfig = toml::from_str(&format!("interface = \"eth0\"\n{extra}")).unwrap();
+        Net::from_cfg(cfg)
+    }
+
+    #[test]
+    fn ping_family_follows_server() {
+        let v6 = net("ping_server = \"2606:4700:4700::1111\"");
+        assert!(v6.ping_cmd().contains(&"-6".to_string()));
+        let host = net("ping_server = \"example.com\"");
+        assert!(!host.ping_cmd().iter().any(|a| a == "-4" || a == "-6"));
+        let forced = net("ping_server = \"example.com\"\nping_family = \"v6\"");
+        assert!(forced.ping_cmd().contains(&"-6".to_string()));
+    }
+
+    #[test]
+    fn loss_is_windowed_and_survives_restarts() {
+        let mut n = net("ping_window = 5");
+        let line = |seq: u32| format!("64 bytes from 8.8.8.8: icmp_seq={seq} ttl=117 time=20.0 ms");
+        // An early outage (seq 2) scrolls out of the window.
+        n.refresh_ping_buffer_from([1, 3, 4, 5, 6, 7].map(line).to_vec());
+        assert!(n.window_loss_pct().abs() < 1e-9);
+        n.refresh_ping_buffer_from([9, 10, 11].map(line).to_vec());
+        assert!((n.window_loss_pct() - 20.0).abs() < 1e-9);
+        // A restart resets the expected range instead of inflating loss.
+        n.refresh_ping_buffer_from([1, 2].map(line).to_vec());
+        assert!(n.window_loss_pct().abs() < 1e-9);
+    }
+
+    #[test]
+    fn median_and_mad_handles_degenerate_samples() {
+        assert_eq!(Net::median_and_mad(&[]), None);
+        assert_eq!(Net::median_and_mad(&[f64::NAN, f64::INFINITY]), None);
+        assert_eq!(Net::median_and_mad(&[7.0]), Some((7.0, 0.0)));
+        assert_eq!(
+            Net::median_and_mad(&[f64::NAN, 1.0, 3.0, 2.0]),
+            Some((2.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn bandwidth_labels_match_their_base() {
+        assert_eq!(Net::scale_bandwidth(1000.0, false), None);
+        assert_eq!(
+            Net::scale_bandwidth(1000.0 * 1000.0 * 100.0, true).map(|(v, sf, _)| (v, sf)),
+            Some((100.0, "M"))
+        );
+        let (v, sf, _) = Net::scale_bandwidth(1000.0 * 1000.0 * 100.0, false).unwrap();
+        assert_eq!(sf, "Mi");
+        assert!((v - 95.367).abs() < 1e-3);
+        assert_eq!(
+            Net::scale_bandwidth(1.5 * 1024.0, false).map(|(v, sf, _)| (v, sf)),
+            Some((1.5, "Ki"))
+        );
+        assert_eq!(
+            Net::scale_bandwidth(2e9, true).map(|(v, sf, _)| (v, sf)),
+            Some((2.0, "G"))
+        );
+    }
+
+    #[test]
+    fn carrier_blips_are_debounced() {
+        let mut n = net("carrier_down_polls = 3");
+        let mut feed = |reads: &[&[u8]]| {
+            reads
+                .iter()
+                .map(|r| n.carrier_debounced_down(Some(r)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            feed(&[b"0\n", b"0\n", b"1\n", b"0\n", b"0\n", b"0\n", b"0\n", b"1\n"]),
+            [false, false, false, false, false, true, true, false]
+        );
+        let mut eager = net("carrier_down_polls = 0");
+        assert!(eager.carrier_debounced_down(Some(b"0")));
+        assert!(!eager.carrier_debounced_down(None));
+    }
+
+    #[test]
+    fn ping_interval_is_floored() {
+        let mut fast = net("ping_interval_sec = 0.0\nping_packet_size = 100000");
+        fast.fix_up_and_validate().unwrap();
+        let cmd = fast.ping_cmd().join(" ");
+        assert!(cmd.contains("-i 0.002 -s 65507"), "{cmd}");
+    }
+
+    /// Recorded `ping -n -O` output (`tests/fixtures/ping_O_*`), one token per
+    /// recognized line.
+    fn replies(fixture: &str) -> Vec<String> {
+        fixture
+            .lines()
+            .filter_map(Net::parse_ping_line)
+            .map(|line| match line {
+                PingLine::Reply(po) => format!("{}@{}", po.icmp_seq, po.time_ms),
+                PingLine::Dup(seq) => format!("dup {seq}"),
+                PingLine::NoAnswer(seq) => format!("lost {seq}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_blocks_keep_the_short_form_and_take_their_own_clicks() {
+        use crate::core::ClickEvent;
+        use crate::render::markup::Markup;
+        let mut n = net("split_bandwidth = true");
+        let view = |n: &Net| n.bandwidth_view(Markup::text("net "), [2048.0, 0.0], [3 << 30, 0]);
+        let v = view(&n);
+        assert_eq!(plain(&v), "net [u    0 B/s]");
+        assert_eq!(plain(&v.extra[0]), "[d    2 KiB/s]");
+        assert_eq!(v.short.as_ref().map(plain).as_deref(), Some("net u0 d2Ki"));
+
+        let middle_on = |block: &str| ClickEvent {
+            instance: Some(block.to_string()),
+            ..crate::units::testing::click(ClickEvent::BUTTON_MIDDLE)
+        };
+        n.handle_click(middle_on("1"));
+        let v = view(&n);
+        assert_eq!(plain(&v), "net [u    0 B/s]");
+        assert_eq!(plain(&v.extra[0]), "[d    3 GiB]");
+        assert_eq!(n.mode, super::DisplayMode::Bandwidth);
+    }
+
+    #[test]
+    fn recorded_ping_output_parses() {
+        let v4 = replies(include_str!("../../tests/fixtures/ping_O_v4"));
+        // The header line is skipped.
+        assert_eq!(
+            v4,
+            ["1@14.2", "2@13.9", "lost 3", "4@15.1", "dup 4", "5@14"]
+        );
+        let v6 = replies(include_str!("../../tests/fixtures/ping_O_v6"));
+        assert_eq!(v6, ["1@10.2", "lost 2", "3@9.8"]);
+    }
+
+    #[test]
+    fn no_answer_and_dup_lines_feed_loss() {
+        let mut n = net("ping_server = \"1.1.1.1\"\nping_window = 4");
+        let lines = |ls: &[&str]| ls.iter().map(ToString::to_string).collect::<Vec<_>>();
+        // Total loss from the start is reported rather than "loading".
+        n.refresh_ping_buffer_from(lines(&[
+            "no answer yet for icmp_seq=1",
+            "no answer yet for icmp_seq=2",
+        ]));
+        let v = n.read_formatted_ping(Vec::new());
+        assert_eq!(plain(&v), "net eth0 [ping 1.1.1.1] [100% loss]");
+        assert_eq!(v.health, Health::Degraded);
+        // A late reply to seq 2 and its DUP count once.
+        n.refresh_ping_buffer_from(lines(&[
+            "64 bytes from 1.1.1.1: icmp_seq=2 ttl=57 time=900 ms",
+            "64 bytes from 1.1.1.1: icmp_seq=2 ttl=57 time=901 ms (DUP!)",
+            "64 bytes from 1.1.1.1: icmp_seq=3 ttl=57 time=10.0 ms",
+            "64 bytes from 1.1.1.1: icmp_seq=4 ttl=57 time=10.0 ms",
+        ]));
+        assert!((n.window_loss_pct() - 25.0).abs() < 1e-9);
+        assert_eq!(n.ping_dups.len(), 1);
+        assert_eq!(n.ping_times.len(), 3);
+        // The DUP ages out of the window along with its sequence number.
+        n.refresh_ping_buffer_from(lines(&[
+            "64 bytes from 1.1.1.1: icmp_seq=5 ttl=57 time=10.0 ms",
+            "64 bytes from 1.1.1.1: icmp_seq=6 ttl=57 time=10.0 ms",
+        ]));
+        assert!(n.ping_dups.is_empty());
+    }
+
+    #[test]
+    fn ping_lines_parse_for_both_families() {
+        let v4 = Net::parse_ping_line("64 bytes from 8.8.8.8: icmp_seq=1 ttl=117 time=25.6 ms");
+        let Some(PingLine::Reply(v4)) = v4 else {
+            panic!("not a reply: {v4:?}");
+        };
+        assert_eq!((v4.icmp_seq, v4.time_ms), (1, 25.6));
+        let v6 = Net::parse_ping_line(
+            "64 bytes from 2606:4700:4700::1111: icmp_seq=7 ttl=57 time=10.2 ms",
+        );
+        let Some(PingLine::Reply(v6)) = v6 else {
+            panic!("not a reply: {v6:?}");
         };
+        assert_eq!((v6.icmp_seq, v6.time_ms), (7, 10.2));
     }
 }
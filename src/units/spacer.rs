@@ -0,0 +1,29 @@
+//! The smallest unit: a fixed string and nothing to read. Start here when
+//! writing a new one; `machine::units::spacer` shows the runtime half.
+
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpacerConfig {
+    /// Shown as is (escaped), e.g. `"   "` or `"│"`.
+    #[serde_inline_default("│".to_string())]
+    pub text: String,
+}
+
+#[derive(Debug)]
+pub struct Spacer {
+    cfg: SpacerConfig,
+}
+
+impl Spacer {
+    pub fn from_cfg(cfg: SpacerConfig) -> Self {
+        Self { cfg }
+    }
+
+    pub fn read_markup(&self) -> Markup {
+        Markup::text(self.cfg.text.as_str())
+    }
+}
@@ -0,0 +1,150 @@
+//! Golden tests feeding recorded sysfs/procfs fixtures (`tests/fixtures/`) into
+//! the byte-level unit entry points.
+
+use crate::core::ClickEvent;
+use crate::machine::types::{Health, HealthThresholds};
+use crate::units::bat::{Bat, BatBackend, BatConfig};
+use crate::units::cpu::Cpu;
+use crate::units::disk::{Disk, DiskConfig};
+use crate::units::testing::{click, plain};
+
+macro_rules! fixture {
+    ($name:literal) => {
+        include_bytes!(concat!("../../tests/fixtures/", $name)).as_slice()
+    };
+}
+
+fn bat() -> Bat {
+    Bat::from_cfg(BatConfig {
+        bat_id: 0,
+        backend: BatBackend::Sysfs,
+        upower_device: None,
+        power_smoothing_sec: 2.5,
+        critical_pct: 5.0,
+        flash_critical: false,
+        level_health: HealthThresholds::default(),
+    })
+}
+
+fn cpu() -> Cpu {
+    Cpu::from_cfg(toml::from_str("").unwrap())
+}
+
+fn disk(name: &str) -> Disk {
+    let cfg: DiskConfig = toml::from_str(&format!("disk = \"{name}\"")).unwrap();
+    Disk::from_cfg(cfg)
+}
+
+#[test]
+fn cpu_modern_proc_stat() {
+    let mut cpu = cpu();
+    let _ = cpu.read_markup_with_temp(fixture!("proc_stat_a"), None);
+    let m = cpu.read_markup_with_temp(fixture!("proc_stat_b"), Some(52.0));
+    assert_eq!(plain(&m), "cpu [load  41%] [temp  52 C]");
+    assert_eq!(m.health, Health::Ok);
+}
+
+#[test]
+fn cpu_old_kernel_proc_stat() {
+    let mut cpu = cpu();
+    let _ = cpu.read_markup_with_temp(fixture!("proc_stat_old_a"), None);
+    let m = cpu.read_markup_with_temp(fixture!("proc_stat_old_b"), None);
+    assert_eq!(plain(&m), "cpu [load  20%] [temp unk]");
+}
+
+#[test]
+fn cpu_garbage_proc_stat() {
+    let mut cpu = cpu();
+    let m = cpu.read_markup_with_temp(b"intr 1 2 3\n", None);
+    assert_eq!(plain(&m), "read err");
+    assert_eq!(m.health, Health::Error);
+}
+
+#[test]
+fn bat_charging_from_charge() {
+    let m = bat().read_markup_from_bytes(fixture!("bat_uevent_charging_charge"));
+    assert_eq!(plain(&m), "bat [ 63%] CHR 18.00 W [01:21 rem]");
+}
+
+#[test]
+fn bat_discharging_from_energy() {
+    let m = bat().read_markup_from_bytes(fixture!("bat_uevent_discharging_energy"));
+    assert_eq!(plain(&m), "bat [ 50%] DIS 10.00 W [02:30 rem]");
+    assert!(!m.urgent);
+}
+
+#[test]
+fn bat_full_from_energy() {
+    let m = bat().read_markup_from_bytes(fixture!("bat_uevent_full_energy"));
+    assert_eq!(plain(&m), "bat [100%] FUL 0.00 W [--:-- rem]");
+}
+
+#[test]
+fn bat_not_charging_is_balanced() {
+    let mut b = bat();
+    b.set_charge_threshold_from_bytes(Some(b"80\n"));
+    let m = b.read_markup_from_bytes(fixture!("bat_uevent_not_charging"));
+    assert_eq!(plain(&m), "bat [ 80%] BAL cap 80% 0.00 W [--:-- rem]");
+}
+
+#[test]
+fn bat_zero_charge_is_invalid() {
+    let m = bat().read_markup_from_bytes(fixture!("bat_uevent_zero_charge"));
+    assert_eq!(plain(&m), "invalid data");
+    assert_eq!(m.health, Health::Error);
+}
+
+#[test]
+fn bat_absent() {
+    let m = bat().read_markup_from_bytes(fixture!("bat_uevent_absent"));
+    assert_eq!(plain(&m), "No battery");
+    assert_eq!(m.health, Health::Error);
+}
+
+#[test]
+fn disk_nvme_stat() {
+    let mut d = disk("nvme0n1p2");
+    let sector = Some(fixture!("disk_hw_sector_size"));
+    let m = d.read_markup_from_bytes(fixture!("disk_stat_nvme_a"), sector);
+    assert_eq!(plain(&m), "disk nvme0n1p2 loading");
+    // The same counters again: nothing moved.
+    let m = d.read_markup_from_bytes(fixture!("disk_stat_nvme_a"), sector);
+    assert_eq!(plain(&m), "disk nvme0n1p2 [  ]");
+    let m = d.read_markup_from_bytes(fixture!("disk_stat_nvme_busy"), sector);
+    assert_eq!(plain(&m), "disk nvme0n1p2 [██]");
+}
+
+#[test]
+fn disk_old_kernel_stat() {
+    let mut d = disk("sda1");
+    let m = d.read_markup_from_bytes(fixture!("disk_stat_sata_old"), None);
+    assert_eq!(plain(&m), "disk sda1 loading");
+    assert_eq!(m.health, Health::Degraded);
+    let m = d.read_markup_from_bytes(b"", None);
+    assert_eq!(plain(&m), "disk sda1 [no such disk]");
+    assert_eq!(m.health, Health::Error);
+}
+
+#[test]
+fn disk_iops_mode() {
+    let mut d = disk("nvme0n1p2");
+    let sector = Some(fixture!("disk_hw_sector_size"));
+    d.handle_click(click(ClickEvent::BUTTON_LEFT));
+    let t0 = std::time::Instant::now();
+    let _ = d.read_markup_from_bytes_at(fixture!("disk_stat_nvme_a"), sector, t0);
+    // 100 reads and 100 writes completed over two seconds.
+    let later = t0 + std::time::Duration::from_secs(2);
+    let m = d.read_markup_from_bytes_at(fixture!("disk_stat_nvme_busy"), sector, later);
+    assert_eq!(plain(&m), "disk nvme0n1p2 [r 50 w 50 iops]");
+}
+
+#[test]
+fn disk_latency_mode() {
+    let mut d = disk("nvme0n1p2");
+    let sector = Some(fixture!("disk_hw_sector_size"));
+    d.handle_click(click(ClickEvent::BUTTON_SCROLL_DOWN));
+    let _ = d.read_markup_from_bytes(fixture!("disk_stat_nvme_a"), sector);
+    // 200 ops completed while time in queue grew by 100 ms.
+    let m = d.read_markup_from_bytes(fixture!("disk_stat_nvme_busy"), sector);
+    assert_eq!(plain(&m), "disk nvme0n1p2 [lat 0.5ms q 0]");
+}
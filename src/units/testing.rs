@@ -0,0 +1,46 @@
+//! Helpers for asserting on unit output without the runtime.
+//!
+//! Units expose `read_markup_from_bytes`-style entry points that are pure given
+//! raw sysfs/procfs contents; feed them fixtures and render with these. Both
+//! helpers take a `Markup` or a unit's `View`.
+
+use crate::render::markup::Markup;
+
+/// The pango string i3bar would receive for `m` under the default theme.
+pub(crate) fn pango(m: &impl AsRef<Markup>) -> String {
+    crate::render::pango::to_pango(m.as_ref(), &crate::core::Theme::default())
+}
+
+/// A click with `button` and no modifiers.
+pub(crate) fn click(button: i32) -> crate::core::ClickEvent {
+    crate::core::ClickEvent {
+        name: String::new(),
+        instance: None,
+        button,
+        modifiers: vec![],
+        x: 0,
+        y: 0,
+        relative_x: 0,
+        relative_y: 0,
+        width: 0,
+        height: 0,
+    }
+}
+
+/// The text content of `m`, with all styling dropped.
+pub(crate) fn plain(m: &impl AsRef<Markup>) -> String {
+    m.as_ref().plain_text()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pango, plain};
+    use crate::render::markup::Markup;
+
+    #[test]
+    fn plain_drops_styles() {
+        let m = Markup::text("a ") + Markup::text("<b>").fg(crate::core::RED);
+        assert_eq!(plain(&m), "a <b>");
+        assert_eq!(pango(&m), "a <span color='#CC6666'>&lt;b&gt;</span>");
+    }
+}
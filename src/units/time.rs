@@ -1,13 +1,7 @@
-use crate::display::color_by_pct_custom;
-use crate::display::format_duration;
-use crate::mode_enum;
 use crate::render::markup::Markup;
 use chrono::Local;
 use serde::Deserialize;
 use serde_inline_default::serde_inline_default;
-use sysinfo::System;
-
-mode_enum!(DateTime, Uptime);
 
 #[serde_inline_default]
 #[derive(Debug, Clone, Deserialize)]
@@ -19,53 +13,18 @@ pub struct TimeConfig {
 #[derive(Debug)]
 pub struct Time {
     cfg: TimeConfig,
-    mode: DisplayMode,
-    uptime_breakpints: [f64; 4],
 }
 
 impl Time {
     pub fn from_cfg(cfg: TimeConfig) -> Self {
-        let ncpu = f64::from(num_cpus::get().min(u32::MAX as usize) as u32);
-        Self {
-            cfg,
-            mode: DisplayMode::DateTime,
-            uptime_breakpints: [ncpu * 0.1, ncpu * 0.25, ncpu * 0.50, ncpu * 0.75],
-        }
-    }
-
-    fn read_formatted_datetime(&self) -> String {
-        Local::now().format(&self.cfg.format).to_string()
-    }
-
-    fn read_formatted_uptime(&self) -> Markup {
-        let uptime = System::uptime();
-        let load_avg = System::load_average();
-        let ut_s = format_duration(f64::from(uptime.min(u32::MAX as u64) as u32));
-
-        let mut load_strings = Vec::new();
-        for data in [&load_avg.one, &load_avg.five, &load_avg.fifteen] {
-            load_strings.push(
-                Markup::text(format!("{data:>3.2}"))
-                    .fg(color_by_pct_custom(*data, &self.uptime_breakpints)),
-            );
-        }
-
-        Markup::text("uptime ")
-            + Markup::bracketed(Markup::text(ut_s))
-            + Markup::text(" load ")
-            + Markup::bracketed(Markup::join("/", load_strings))
+        Self { cfg }
     }
 
     pub fn read_markup(&self) -> Markup {
-        match self.mode {
-            DisplayMode::DateTime => self.read_formatted_datetime().into(),
-            DisplayMode::Uptime => self.read_formatted_uptime(),
-        }
+        Local::now().format(&self.cfg.format).to_string().into()
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
     }
-
-    pub fn fix_up_and_validate() {}
 }
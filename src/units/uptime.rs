@@ -0,0 +1,116 @@
+use crate::display::{format_duration, DurationStyle};
+use crate::mode_enum;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+use sysinfo::System;
+
+mode_enum!(Compact, Verbose);
+
+#[serde_inline_default]
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct UptimeConfig {
+    /// Layout of the compact mode: `padded`, `compact` or `single`.
+    #[serde_inline_default(DurationStyle::Padded)]
+    pub style: DurationStyle,
+}
+
+#[derive(Debug)]
+pub struct Uptime {
+    cfg: UptimeConfig,
+    mode: DisplayMode,
+    /// The last uptime read (seconds), re-rendered on mode switches.
+    last: Option<u64>,
+}
+
+impl Uptime {
+    pub fn from_cfg(cfg: UptimeConfig) -> Self {
+        Self {
+            cfg,
+            mode: DisplayMode::Compact,
+            last: None,
+        }
+    }
+
+    /// Days, hours and minutes spelled out, skipping leading zero units.
+    fn format_verbose(secs: u64) -> String {
+        let parts = [
+            (secs / 86_400, "day"),
+            (secs % 86_400 / 3600, "hour"),
+            (secs % 3600 / 60, "minute"),
+        ];
+        let words: Vec<String> = parts
+            .iter()
+            .skip_while(|(n, _)| *n == 0)
+            .map(|(n, unit)| format!("{n} {unit}{}", if *n == 1 { "" } else { "s" }))
+            .collect();
+        if words.is_empty() {
+            return format!("{secs} seconds");
+        }
+        words.join(", ")
+    }
+
+    fn format_uptime(&self, secs: u64) -> Markup {
+        let body = match self.mode {
+            DisplayMode::Compact => format_duration(secs as f64, self.cfg.style),
+            DisplayMode::Verbose => Self::format_verbose(secs),
+        };
+        Markup::label("uptime ") + Markup::bracketed(Markup::text(body))
+    }
+
+    pub fn read_markup(&mut self) -> Markup {
+        let secs = System::uptime();
+        self.last = Some(secs);
+        self.format_uptime(secs)
+    }
+
+    /// The last uptime in the current mode; `None` before the first read.
+    pub fn render(&self) -> Option<Markup> {
+        self.last.map(|secs| self.format_uptime(secs))
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
+    }
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Uptime, UptimeConfig};
+    use crate::core::ClickEvent;
+    use crate::display::DurationStyle;
+    use crate::units::testing::{click, plain};
+
+    #[test]
+    fn click_toggles_verbose_uptime() {
+        let mut up = Uptime::from_cfg(UptimeConfig {
+            style: DurationStyle::Padded,
+        });
+        let secs = 3 * 86_400 + 4 * 3600 + 60;
+        assert_eq!(plain(&up.format_uptime(secs)), "uptime [ 3 d  4 h]");
+        up.handle_click(click(ClickEvent::BUTTON_LEFT));
+        assert_eq!(
+            plain(&up.format_uptime(secs)),
+            "uptime [3 days, 4 hours, 1 minute]"
+        );
+        assert_eq!(Uptime::format_verbose(3600), "1 hour, 0 minutes");
+        assert_eq!(Uptime::format_verbose(42), "42 seconds");
+    }
+
+    #[test]
+    fn style_shapes_the_compact_mode() {
+        let secs = 3 * 86_400 + 4 * 3600 + 60;
+        let up = Uptime::from_cfg(UptimeConfig {
+            style: DurationStyle::Compact,
+        });
+        assert_eq!(plain(&up.format_uptime(secs)), "uptime [3d4h]");
+        let up = Uptime::from_cfg(UptimeConfig {
+            style: DurationStyle::Single,
+        });
+        assert_eq!(plain(&up.format_uptime(secs)), "uptime [3 days]");
+    }
+}
@@ -1,37 +1,54 @@
 // TODO start splitting up into optionals, the dep tree is getting fat
 // TODO phases of the moon!
 use anyhow::Result;
-use chrono::{DateTime, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Timelike, Utc};
 use palette::FromColor;
 use reqwest::Url;
 use serde::{Deserialize, Deserializer};
 use serde_inline_default::serde_inline_default;
 use serde_repr::Deserialize_repr;
-use serde_with::{serde_as, DeserializeAs};
+use serde_with::{serde_as, BoolFromInt, DeserializeAs};
 use std::time::Instant;
 
+use crate::display::{format_fixed, TempUnits};
 use crate::machine::effects::{EffectReq, HttpCacheKey, HttpGet, HttpPolicy};
+use crate::machine::types::TransportError;
 use crate::{
-    core::{BROWN, VIOLET},
+    core::{theme, Color},
     mode_enum,
 };
 
 use crate::render::color::{Gradient, Srgb8, Stop};
 use crate::render::markup::Markup;
+use crate::util::{local_now, Clock, ScrollAccumulator};
 
-mode_enum!(Now, Forecast);
+mode_enum!(Now, Forecast, Sun);
 
 impl std::fmt::Display for DisplayMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DisplayMode::Now => f.write_str("now"),
             DisplayMode::Forecast => f.write_str("forecast"),
+            DisplayMode::Sun => f.write_str("sun"),
         }
     }
 }
 
 const MIN_REFRESH_INTERVAL: f64 = 15.0;
 
+/// Longest server-requested pause (`Retry-After`) that is honored.
+const MAX_RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Refresh intervals are scaled by a factor re-rolled in `1 ± REFRESH_JITTER`
+/// after every attempt, so bars sharing a config don't poll in lockstep.
+const REFRESH_JITTER: f64 = 0.1;
+
+/// How many days the forecast window can be scrolled forward.
+const MAX_FORECAST_PAGE: u32 = 5;
+/// Days fetched per request: the base 2-day window plus every scrollable page,
+/// so paging never needs a refetch.
+const FORECAST_DAYS: u32 = 2 + MAX_FORECAST_PAGE;
+
 /// All possible Open-Meteo weather codes, per WMO WW definitions.
 /// use `serde_repr::Deserialize_repr`;
 #[derive(Clone, Copy, Debug, Deserialize_repr)]
@@ -81,26 +98,65 @@ impl<T> TimeDependent<T> {
             Ok(spa::SunriseAndSet::Daylight(sunrise, sunset)) => {
                 now_utc >= sunrise && now_utc < sunset
             }
-            // SAFETY: we avalidate our lat/lon in the config, so this should never happen.
-            Err(_) => unreachable!(),
+            // Config validation keeps lat/lon in range, but `spa` can still fail
+            // on numerical edge cases; guess from local solar time instead. This
+            // runs on every render, so keep it out of the default log level.
+            Err(e) => {
+                tracing::debug!("Weather: sunrise computation failed ({e:?}), guessing daylight");
+                let solar_hour = (f64::from(now_utc.hour()) + lon / 15.0).rem_euclid(24.0);
+                (6.0..18.0).contains(&solar_hour)
+            }
         }
     }
     /// Returns the value based on the current time.
     fn get_at(&self, lat: f64, lon: f64, now_utc: DateTime<Utc>) -> &T {
         match self {
             TimeDependent::Fixed(value) => value,
-            TimeDependent::DayNight(day, night) => {
-                if Self::is_day_at(lat, lon, now_utc) {
-                    day
-                } else {
-                    night
-                }
-            }
+            TimeDependent::DayNight(..) => self.pick(Self::is_day_at(lat, lon, now_utc)),
+        }
+    }
+    /// Returns the day or night value, e.g. from the API's `is_day` flag.
+    fn pick(&self, is_day: bool) -> &T {
+        match self {
+            TimeDependent::Fixed(value) => value,
+            TimeDependent::DayNight(day, _) if is_day => day,
+            TimeDependent::DayNight(_, night) => night,
         }
     }
 }
 
 impl Wmo {
+    /// Background tint for the condition, escalating from notable weather
+    /// (fog, rain, snow, ice) to hazardous (heavy rain, thunderstorms).
+    fn tint(self) -> Option<Color> {
+        match self {
+            Wmo::ClearSky | Wmo::MainlyClear | Wmo::PartlyCloudy | Wmo::Overcast => None,
+            Wmo::Fog | Wmo::DepositingRimeFog => Some(Color::DarkGrey),
+            Wmo::DrizzleLight
+            | Wmo::DrizzleModerate
+            | Wmo::DrizzleDense
+            | Wmo::RainSlight
+            | Wmo::RainModerate
+            | Wmo::RainShowersSlight
+            | Wmo::RainShowersModerate => Some(Color::Blue),
+            Wmo::SnowfallSlight
+            | Wmo::SnowfallModerate
+            | Wmo::SnowfallHeavy
+            | Wmo::SnowGrains
+            | Wmo::SnowShowersSlight
+            | Wmo::SnowShowersHeavy => Some(Color::Cyan),
+            Wmo::FreezingDrizzleLight
+            | Wmo::FreezingDrizzleDense
+            | Wmo::FreezingRainLight
+            | Wmo::FreezingRainHeavy => Some(Color::Orange),
+            Wmo::RainHeavy
+            | Wmo::RainShowersViolent
+            | Wmo::Thunderstorm
+            | Wmo::ThunderstormWithHail
+            | Wmo::ThunderstormWithHailDup => Some(Color::Red),
+        }
+    }
+
     /// A day/night-aware emoji for each condition.
     fn get_emoji(self) -> TimeDependent<&'static str> {
         match self {
@@ -133,40 +189,251 @@ impl Wmo {
             }
         }
     }
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum TempUnits {
-    Celsius,
-    Fahrenheit,
-}
 
-impl TempUnits {
-    pub fn suffix(&self) -> &str {
+    /// Single-width Nerd Font weather glyphs (`nf-weather-*`).
+    fn get_nerdfont(self) -> TimeDependent<&'static str> {
         match self {
-            TempUnits::Celsius => "C",
-            TempUnits::Fahrenheit => "F",
+            Wmo::ClearSky => TimeDependent::DayNight("\u{e30d}", "\u{e32b}"),
+            Wmo::MainlyClear => TimeDependent::DayNight("\u{e30c}", "\u{e379}"),
+            Wmo::PartlyCloudy => TimeDependent::DayNight("\u{e302}", "\u{e37e}"),
+            Wmo::Overcast => TimeDependent::Fixed("\u{e312}"),
+            Wmo::Fog | Wmo::DepositingRimeFog => TimeDependent::Fixed("\u{e313}"),
+            Wmo::DrizzleLight | Wmo::RainSlight | Wmo::RainShowersSlight => {
+                TimeDependent::DayNight("\u{e309}", "\u{e334}")
+            }
+            Wmo::DrizzleModerate | Wmo::RainModerate | Wmo::RainShowersModerate => {
+                TimeDependent::Fixed("\u{e319}")
+            }
+            Wmo::DrizzleDense | Wmo::RainHeavy | Wmo::RainShowersViolent => {
+                TimeDependent::Fixed("\u{e318}")
+            }
+            Wmo::FreezingDrizzleLight
+            | Wmo::FreezingDrizzleDense
+            | Wmo::FreezingRainLight
+            | Wmo::FreezingRainHeavy => TimeDependent::Fixed("\u{e3ad}"),
+            Wmo::SnowfallSlight
+            | Wmo::SnowShowersSlight
+            | Wmo::SnowGrains
+            | Wmo::SnowfallModerate
+            | Wmo::SnowfallHeavy
+            | Wmo::SnowShowersHeavy => TimeDependent::Fixed("\u{e31a}"),
+            Wmo::Thunderstorm | Wmo::ThunderstormWithHail | Wmo::ThunderstormWithHailDup => {
+                TimeDependent::Fixed("\u{e31d}")
+            }
         }
     }
 
-    pub fn convert_from_celcius(&self, temp_c: f64) -> f64 {
+    /// Plain-text names for fonts without either glyph set.
+    fn get_ascii(self) -> TimeDependent<&'static str> {
         match self {
-            TempUnits::Celsius => temp_c,
-            TempUnits::Fahrenheit => temp_c * 9.0 / 5.0 + 32.0,
+            Wmo::ClearSky => TimeDependent::DayNight("sun", "moon"),
+            Wmo::MainlyClear | Wmo::PartlyCloudy => {
+                TimeDependent::DayNight("sun/cloud", "moon/cloud")
+            }
+            Wmo::Overcast => TimeDependent::Fixed("cloud"),
+            Wmo::Fog | Wmo::DepositingRimeFog => TimeDependent::Fixed("fog"),
+            Wmo::DrizzleLight | Wmo::RainSlight | Wmo::RainShowersSlight => {
+                TimeDependent::Fixed("showers")
+            }
+            Wmo::DrizzleModerate | Wmo::RainModerate | Wmo::RainShowersModerate => {
+                TimeDependent::Fixed("rain")
+            }
+            Wmo::DrizzleDense | Wmo::RainHeavy | Wmo::RainShowersViolent => {
+                TimeDependent::Fixed("rain!")
+            }
+            Wmo::FreezingDrizzleLight
+            | Wmo::FreezingDrizzleDense
+            | Wmo::FreezingRainLight
+            | Wmo::FreezingRainHeavy => TimeDependent::Fixed("ice"),
+            Wmo::SnowfallSlight | Wmo::SnowShowersSlight | Wmo::SnowGrains => {
+                TimeDependent::Fixed("snow")
+            }
+            Wmo::SnowfallModerate => TimeDependent::Fixed("snow+"),
+            Wmo::SnowfallHeavy | Wmo::SnowShowersHeavy => TimeDependent::Fixed("snow!"),
+            Wmo::Thunderstorm | Wmo::ThunderstormWithHail | Wmo::ThunderstormWithHailDup => {
+                TimeDependent::Fixed("storm")
+            }
+        }
+    }
+
+    fn get_icon(self, set: IconSet) -> TimeDependent<&'static str> {
+        match set {
+            IconSet::Emoji => self.get_emoji(),
+            IconSet::Nerdfont => self.get_nerdfont(),
+            IconSet::Ascii => self.get_ascii(),
         }
     }
 }
 
+/// Glyphs for the `{emoji}` placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    Emoji,
+    /// Nerd Font weather glyphs; single width, so the bar doesn't jitter.
+    Nerdfont,
+    Ascii,
+}
+
+/// Where the "feels like" temperature in the now view comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeelsLike {
+    /// Open-Meteo's apparent temperature, computed locally when missing.
+    Provider,
+    /// Always wind chill / heat index from temperature, wind and humidity.
+    Computed,
+    Off,
+}
+
+/// Wind chill (Environment Canada / NWS), valid at or below 10 °C with wind
+/// above 4.8 km/h.
+fn wind_chill_c(temp_c: f64, wind_kmh: f64) -> Option<f64> {
+    if temp_c > 10.0 || wind_kmh <= 4.8 {
+        return None;
+    }
+    let v = wind_kmh.powf(0.16);
+    Some(13.12 + 0.6215 * temp_c - 11.37 * v + 0.3965 * temp_c * v)
+}
+
+/// Heat index (NWS Rothfusz regression), valid from about 27 °C.
+fn heat_index_c(temp_c: f64, humidity_pct: f64) -> Option<f64> {
+    if temp_c < 26.7 {
+        return None;
+    }
+    let t = temp_c * 9.0 / 5.0 + 32.0;
+    let r = humidity_pct;
+    let hi_f = -42.379 + 2.049_015_23 * t + 10.143_331_27 * r
+        - 0.224_755_41 * t * r
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * r * r
+        + 0.001_228_74 * t * t * r
+        + 0.000_852_82 * t * r * r
+        - 0.000_001_99 * t * t * r * r;
+    Some((hi_f - 32.0) * 5.0 / 9.0)
+}
+
+/// 😓 muggy, 🙂 comfortable or 🥶 cold and dry; other combinations are
+/// unremarkable and get none.
+fn comfort_emoji(temp_c: f64, humidity_pct: f64) -> Option<&'static str> {
+    if temp_c >= 22.0 && humidity_pct >= 60.0 {
+        Some("😓")
+    } else if (18.0..26.0).contains(&temp_c) && (30.0..60.0).contains(&humidity_pct) {
+        Some("🙂")
+    } else if temp_c < 10.0 && humidity_pct < 40.0 {
+        Some("🥶")
+    } else {
+        None
+    }
+}
+
+/// Wind chill or heat index where one applies, else the raw temperature.
+fn computed_feels_like_c(temp_c: f64, wind_kmh: Option<f64>, humidity_pct: Option<f64>) -> f64 {
+    wind_kmh
+        .and_then(|w| wind_chill_c(temp_c, w))
+        .or_else(|| humidity_pct.and_then(|h| heat_index_c(temp_c, h)))
+        .unwrap_or(temp_c)
+}
+
 #[serde_inline_default]
 #[derive(Debug, Clone, Deserialize)]
 pub struct WeatherConfig {
-    pub lat: f64,
-    pub lon: f64,
+    /// The first location; optional when `locations` is set.
+    #[serde(default)]
+    pub lat: Option<f64>,
+    #[serde(default)]
+    pub lon: Option<f64>,
+    /// Further places, cycled with middle-click after `lat`/`lon`.
+    #[serde(default)]
+    pub locations: Vec<WeatherLocationConfig>,
     #[serde_inline_default(60.0)]
     pub refresh_interval_sec: f64,
     #[serde_inline_default(TempUnits::Celsius)]
     pub units: TempUnits,
+    /// Tint the condition emoji's background by severity.
+    #[serde_inline_default(false)]
+    pub colorize_condition: bool,
+    /// `[coldest, hottest]` ends of the temperature color scale, in `units`.
+    /// Unset keeps the Celsius default of -15..40.
+    #[serde(default)]
+    pub temp_color_range: Option<[f64; 2]>,
+    /// Number of forecast entries shown per page.
+    #[serde_inline_default(6)]
+    pub forecast_slots: u32,
+    /// Hours between forecast entries, aligned to local midnight. Must divide 24.
+    #[serde_inline_default(4)]
+    pub forecast_step_hours: u32,
+    /// Layout of each condition/temperature part; see [`PartPiece`].
+    #[serde_inline_default(DEFAULT_PART_FORMAT.to_string())]
+    pub part_format: String,
+    /// Wrap each part in `[...]`.
+    #[serde_inline_default(true)]
+    pub bracketed: bool,
+    /// Decimals on temperatures.
+    #[serde_inline_default(0)]
+    pub temp_precision: u8,
+    /// Glyphs for conditions.
+    #[serde_inline_default(IconSet::Emoji)]
+    pub icon_set: IconSet,
+    /// Append a "feels like" temperature to the now view.
+    #[serde_inline_default(FeelsLike::Off)]
+    pub feels_like: FeelsLike,
+    /// Append a temperature/humidity comfort emoji to the now view.
+    #[serde_inline_default(false)]
+    pub show_comfort: bool,
+    /// Scroll events per forecast page; raise for high-resolution touchpads.
+    #[serde_inline_default(1)]
+    pub scroll_threshold: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeatherLocationConfig {
+    pub lat: f64,
+    pub lon: f64,
+    /// Shown before the reading, e.g. `weather [SF 18°C]`.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+const DEFAULT_PART_FORMAT: &str = "{emoji}{temp}°{unit}";
+
+/// A parsed `part_format` element: literal text or one of the `{emoji}`,
+/// `{temp}` and `{unit}` placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PartPiece {
+    Lit(String),
+    Emoji,
+    Temp,
+    Unit,
+}
+
+impl PartPiece {
+    fn parse_format(format: &str) -> Result<Vec<PartPiece>, String> {
+        let mut out = Vec::new();
+        let mut rest = format;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err(format!("unmatched '}}' in {format:?}"));
+            }
+            if open > 0 {
+                out.push(PartPiece::Lit(rest[..open].to_string()));
+            }
+            let Some(close) = rest[open..].find('}') else {
+                return Err(format!("unclosed '{{' in {format:?}"));
+            };
+            out.push(match &rest[open + 1..open + close] {
+                "emoji" => PartPiece::Emoji,
+                "temp" => PartPiece::Temp,
+                "unit" => PartPiece::Unit,
+                other => return Err(format!("unknown placeholder {{{other}}}")),
+            });
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.is_empty() {
+            out.push(PartPiece::Lit(rest.to_string()));
+        }
+        Ok(out)
+    }
 }
 
 /// RFC3339‐ish format *without* seconds: “YYYY‐MM‐DDTHH:MM”
@@ -193,6 +460,19 @@ pub(crate) struct OMCurrentWeather {
     temp_c: f64,
     #[serde(rename = "weathercode")]
     wmo_code: Wmo,
+    /// Only requested when `feels_like` is on.
+    #[serde(rename = "apparent_temperature", default)]
+    apparent_c: Option<f64>,
+    /// Only requested when `feels_like` or `show_comfort` is on.
+    #[serde(rename = "relative_humidity_2m", default)]
+    humidity_pct: Option<f64>,
+    /// Only requested when `feels_like` is on.
+    #[serde(rename = "wind_speed_10m", default)]
+    wind_kmh: Option<f64>,
+    /// Open-Meteo's own daylight flag; preferred over recomputing sunrise.
+    #[serde_as(as = "Option<BoolFromInt>")]
+    #[serde(default)]
+    is_day: Option<bool>,
     // this is what they reutrn by default, just going to assume that's not
     // going to change randomly...
     #[serde(rename = "time")]
@@ -210,6 +490,10 @@ pub(crate) struct OMHourlyForecast {
     temperatures_c: Vec<f64>,
     #[serde(rename = "weathercode")]
     wmo_codes: Vec<Wmo>,
+    /// Per-hour daylight flags; empty in responses that predate them.
+    #[serde_as(as = "Vec<BoolFromInt>")]
+    #[serde(default)]
+    is_day: Vec<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -222,40 +506,72 @@ pub(crate) struct OMResponseContainer {
 pub struct Weather {
     pub(crate) cfg: WeatherConfig,
     pub(crate) mode: DisplayMode,
+    /// Display units; starts from `cfg.units` and flips on right-click.
+    pub(crate) units: TempUnits,
     pub(crate) last_successful_poll: Option<Instant>,
     pub(crate) last_attempt_poll: Option<Instant>,
+    /// No fetches before this, as asked by the server's `Retry-After`.
+    pub(crate) retry_not_before: Option<Instant>,
     pub(crate) res: Option<OMResponseContainer>,
+    /// Day offset of the forecast window, changed by scrolling.
+    pub(crate) forecast_page: u32,
+    /// `cfg.part_format`, parsed once.
+    pub(crate) part_pieces: Vec<PartPiece>,
+    /// Current refresh interval scale; see [`REFRESH_JITTER`].
+    pub(crate) jitter: f64,
+    pub(crate) scroll: ScrollAccumulator,
+    /// `lat`/`lon` followed by `cfg.locations`.
+    pub(crate) locations: Vec<WeatherLocationConfig>,
+    /// Index into `locations` of the place shown.
+    pub(crate) location: usize,
+    /// Fetch state of every location; the shown one's slot is empty while
+    /// its state lives in `res` and the poll timestamps.
+    pub(crate) parked: Vec<LocationCache>,
+    pub(crate) clock: Clock,
 }
 
-/// Gets the next forecast times. These are always the next 4 "4-hour-round"
-/// times, e.g. if now is 10:15, returns 12:00, 16:00, 20:00, 00:00, 04:00, 08:00.
-fn get_wanted_forecast_datetimes() -> Vec<DateTime<Utc>> {
-    const STEPS: u32 = 6;
-    const STRIDE_HOURS: u32 = 4;
-
-    let now_utc = Utc::now();
-    let now_local = now_utc.with_timezone(&chrono::Local);
-
-    // Forecasts are chosen by a 4-hour grid in local time: 00, 04, 08, 12, 16, 20.
-    // The first slot is the first grid point strictly after `now_local`.
-    let start_hour_local = (now_local.hour() / STRIDE_HOURS) * STRIDE_HOURS + STRIDE_HOURS;
+#[derive(Debug, Default)]
+pub(crate) struct LocationCache {
+    res: Option<OMResponseContainer>,
+    last_successful_poll: Option<Instant>,
+    last_attempt_poll: Option<Instant>,
+}
 
-    let mut out = Vec::with_capacity(STEPS as usize);
+/// Gets the next `slots` forecast times on a `stride_hours` grid, e.g. with 6
+/// slots of 4 hours at 10:15, returns 12:00, 16:00, 20:00, 00:00, 04:00, 08:00,
+/// shifted forward by `day_offset` days. The grid is in `now`'s offset.
+fn get_wanted_forecast_datetimes(
+    now: DateTime<FixedOffset>,
+    day_offset: u32,
+    slots: u32,
+    stride_hours: u32,
+) -> Vec<DateTime<Utc>> {
+    // Forecasts are chosen by a grid in local time starting at midnight, e.g.
+    // 00, 04, 08, 12, 16, 20. The first slot is the first grid point strictly
+    // after `now`. `stride_hours` divides 24, so every day shares the grid.
+    let start_hour = (now.hour() / stride_hours) * stride_hours + stride_hours + 24 * day_offset;
 
-    for step in 0..STEPS {
-        let hour_total = start_hour_local + step * STRIDE_HOURS;
-        let hour = hour_total % 24;
-        let day_offset = hour_total / 24;
+    let mut out = Vec::with_capacity(slots as usize);
 
-        let date_local = now_local.date_naive() + chrono::Duration::days(i64::from(day_offset));
-        let dt_local = date_local.and_hms_opt(hour, 0, 0);
-        let Some(dt_local) =
-            dt_local.and_then(|dt| chrono::Local.from_local_datetime(&dt).single())
+    for step in 0..slots {
+        let hour_total = start_hour + step * stride_hours;
+        let date = now.date_naive() + chrono::Duration::days(i64::from(hour_total / 24));
+        let Some(dt) = date
+            .and_hms_opt(hour_total % 24, 0, 0)
+            .and_then(|dt| now.offset().from_local_datetime(&dt).single())
         else {
             continue;
         };
 
-        out.push(dt_local.with_timezone(&Utc));
+        // Forecasts are on whole UTC hours. Where the offset is not (India,
+        // Nepal, ...), take the first one after the grid point instead.
+        let dt = dt.with_timezone(&Utc);
+        let past_hour = dt.timestamp().rem_euclid(3600);
+        out.push(if past_hour == 0 {
+            dt
+        } else {
+            dt + chrono::Duration::seconds(3600 - past_hour)
+        });
     }
 
     out
@@ -263,44 +579,132 @@ fn get_wanted_forecast_datetimes() -> Vec<DateTime<Utc>> {
 
 impl Weather {
     pub fn from_cfg(cfg: WeatherConfig) -> Self {
+        let part_pieces = PartPiece::parse_format(&cfg.part_format).unwrap_or_else(|e| {
+            tracing::warn!("Weather part_format: {e}; using {DEFAULT_PART_FORMAT:?}");
+            PartPiece::parse_format(DEFAULT_PART_FORMAT).unwrap_or_default()
+        });
+        let locations: Vec<WeatherLocationConfig> = cfg
+            .lat
+            .zip(cfg.lon)
+            .map(|(lat, lon)| WeatherLocationConfig {
+                lat,
+                lon,
+                label: None,
+            })
+            .into_iter()
+            .chain(cfg.locations.iter().cloned())
+            .collect();
         Self {
+            part_pieces,
+            parked: locations.iter().map(|_| LocationCache::default()).collect(),
+            locations,
+            location: 0,
+            units: cfg.units,
+            scroll: ScrollAccumulator::new(cfg.scroll_threshold),
             cfg,
             mode: DisplayMode::Now,
             last_successful_poll: None,
             last_attempt_poll: None,
+            retry_not_before: None,
             res: None,
+            forecast_page: 0,
+            jitter: Self::roll_jitter(),
+            clock: local_now,
+        }
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        (self.clock)().with_timezone(&Utc)
+    }
+
+    /// The location shown. `fix_up_and_validate` rejects configs without one.
+    fn place(&self) -> (f64, f64) {
+        self.locations
+            .get(self.location)
+            .map_or((0.0, 0.0), |l| (l.lat, l.lon))
+    }
+
+    /// `"SF "` for a labeled location, else nothing.
+    fn label(&self) -> Markup {
+        match self
+            .locations
+            .get(self.location)
+            .and_then(|l| l.label.as_ref())
+        {
+            Some(label) => Markup::label(format!("{label} ")),
+            None => Markup::text(""),
         }
     }
 
+    /// Shows the next location, keeping each one's fetched data.
+    fn cycle_location(&mut self) {
+        let active = LocationCache {
+            res: self.res.take(),
+            last_successful_poll: self.last_successful_poll.take(),
+            last_attempt_poll: self.last_attempt_poll.take(),
+        };
+        self.parked[self.location] = active;
+        self.location = (self.location + 1) % self.locations.len();
+        let next = std::mem::take(&mut self.parked[self.location]);
+        self.res = next.res;
+        self.last_successful_poll = next.last_successful_poll;
+        self.last_attempt_poll = next.last_attempt_poll;
+    }
+
+    fn roll_jitter() -> f64 {
+        1.0 + REFRESH_JITTER * (2.0 * fastrand::f64() - 1.0)
+    }
+
+    /// `interval` scaled by the current jitter, never below the config floor.
+    fn jittered(&self, interval: f64) -> f64 {
+        (interval * self.jitter).max(MIN_REFRESH_INTERVAL)
+    }
+
     // Open-Meteo open-access limits (documented): 600/min, 5k/hour, 10k/day.
     // Still enforce a much more conservative floor to avoid bursty behavior and
     // to reduce risk of upstream IP throttling.
     const MIN_OPEN_METEO_INTERVAL: f64 = 120.0;
 
+    /// The Open-Meteo request. It does not depend on the display mode: one
+    /// superset payload (`current` plus `hourly`) backs the Now and Forecast
+    /// views and every forecast page, so switching modes never refetches.
+    fn request_url(&self) -> Result<Url> {
+        let mut current = String::from("temperature_2m,weathercode,is_day");
+        match self.cfg.feels_like {
+            FeelsLike::Provider | FeelsLike::Computed => {
+                current.push_str(",apparent_temperature,relative_humidity_2m,wind_speed_10m");
+            }
+            FeelsLike::Off if self.cfg.show_comfort => current.push_str(",relative_humidity_2m"),
+            FeelsLike::Off => {}
+        }
+        let (lat, lon) = self.place();
+        let mut url = Url::parse("https://api.open-meteo.com/v1/forecast")?;
+        url.query_pairs_mut()
+            .append_pair("latitude", &format!("{lat:.4}"))
+            .append_pair("longitude", &format!("{lon:.4}"))
+            .append_pair("current", &current)
+            .append_pair("hourly", "temperature_2m,weathercode,is_day")
+            .append_pair("forecast_days", &FORECAST_DAYS.to_string());
+        Ok(url)
+    }
+
     pub(crate) async fn poll_weather(
         &mut self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
     ) -> Result<(), crate::machine::types::PollError<WeatherError>> {
-        let mut url = Url::parse("https://api.open-meteo.com/v1/forecast")
+        let url = self
+            .request_url()
             .map_err(|e| crate::machine::types::PollError::Unit(WeatherError(e.to_string())))?;
-        {
-            let mut qp = url.query_pairs_mut();
-            qp.append_pair("latitude", &format!("{:.4}", self.cfg.lat));
-            qp.append_pair("longitude", &format!("{:.4}", self.cfg.lon));
-            // Superset request: one payload backs both Now and Forecast views.
-            qp.append_pair("current", "temperature_2m,weathercode");
-            qp.append_pair("hourly", "temperature_2m,weathercode");
-            qp.append_pair("forecast_days", "2");
-        }
-
-        let key = HttpCacheKey::new(format!(
-            "open-meteo:{:.4}:{:.4}",
-            self.cfg.lat, self.cfg.lon
-        ));
+
+        let (lat, lon) = self.place();
+        let key = HttpCacheKey::new(format!("open-meteo:{lat:.4}:{lon:.4}"));
+        // The shortest jittered spacing, so the limiter never swallows a poll
+        // that jitter brought forward.
         let min_interval = self
             .cfg
             .refresh_interval_sec
-            .max(Self::MIN_OPEN_METEO_INTERVAL);
+            .max(Self::MIN_OPEN_METEO_INTERVAL)
+            * (1.0 - REFRESH_JITTER);
         let policy = HttpPolicy {
             // Conservative: stay well below free-tier caps.
             rate: crate::machine::http::RateLimitSpec {
@@ -310,15 +714,29 @@ impl Weather {
             cache_fresh_for: std::time::Duration::from_secs_f64(min_interval),
         };
 
-        let out = effects
+        let out = match effects
             .run(EffectReq::HttpGet(HttpGet { key, url, policy }))
             .await
-            .map_err(crate::machine::types::PollError::Transport)?;
+        {
+            Ok(out) => out,
+            Err(e) => {
+                if let TransportError::Http {
+                    retry_after: Some(wait),
+                    ..
+                } = e
+                {
+                    self.retry_not_before = Some(Instant::now() + wait.min(MAX_RETRY_AFTER));
+                }
+                return Err(crate::machine::types::PollError::Transport(e));
+            }
+        };
 
-        let body = out
+        let response = out
             .expect::<crate::machine::effects::HttpResponse>()
-            .map_err(|e| crate::machine::types::PollError::Unit(WeatherError(e.to_string())))?
-            .body;
+            .map_err(|e| crate::machine::types::PollError::Unit(WeatherError(e.to_string())))?;
+        self.retry_not_before = crate::machine::effects::retry_after(&response.headers)
+            .map(|wait| Instant::now() + wait.min(MAX_RETRY_AFTER));
+        let body = response.body;
 
         let res: OMResponseContainer = serde_json::from_slice(&body)
             .map_err(|e| crate::machine::types::PollError::Unit(WeatherError(e.to_string())))?;
@@ -329,17 +747,20 @@ impl Weather {
 
     async fn do_poll_if_needed(
         &mut self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
     ) -> Result<(), crate::machine::types::PollError<WeatherError>> {
         let now = Instant::now();
-        let min_interval = self
-            .cfg
-            .refresh_interval_sec
-            .max(Self::MIN_OPEN_METEO_INTERVAL);
+        let refresh_interval = self.jittered(self.cfg.refresh_interval_sec);
+        let min_interval = self.jittered(
+            self.cfg
+                .refresh_interval_sec
+                .max(Self::MIN_OPEN_METEO_INTERVAL),
+        );
 
-        if self.last_successful_poll.is_some_and(|last| {
-            now.duration_since(last).as_secs_f64() <= self.cfg.refresh_interval_sec
-        }) {
+        if self
+            .last_successful_poll
+            .is_some_and(|last| now.duration_since(last).as_secs_f64() <= refresh_interval)
+        {
             return Ok(());
         }
 
@@ -350,10 +771,16 @@ impl Weather {
             return Ok(());
         }
 
+        if self.retry_not_before.is_some_and(|at| now < at) {
+            return Ok(());
+        }
+
         self.last_attempt_poll = Some(now);
-        if self.last_successful_poll.is_none_or(|last| {
-            now.duration_since(last).as_secs_f64() > self.cfg.refresh_interval_sec
-        }) {
+        self.jitter = Self::roll_jitter();
+        if self
+            .last_successful_poll
+            .is_none_or(|last| now.duration_since(last).as_secs_f64() > refresh_interval)
+        {
             self.poll_weather(effects).await?;
         }
         Ok(())
@@ -363,19 +790,69 @@ impl Weather {
         let derived = res.is_none().then(|| self.current_from_hourly()).flatten();
         let res = res.or(derived.as_ref());
         let Some(res) = res else {
-            return Markup::text("weather ") + Markup::text("current failed to load").fg(BROWN);
+            return Markup::label("weather ")
+                + Markup::text("current failed to load").fg(theme().missing);
         };
 
-        Markup::text("weather ").append(Markup::bracketed(self.format_single_code_and_tc(
+        let mut inner = self.label().append(self.format_single_code_and_tc(
             res.time,
+            res.is_day,
             res.wmo_code,
             res.temp_c,
-        )))
+        ));
+        if let Some(arrow) = self.temp_trend(res.time, res.temp_c) {
+            inner = inner.append(arrow);
+        }
+        if let Some(feels_c) = self.feels_like_c(res) {
+            let feels = self.units.convert_from_celcius(feels_c);
+            inner = inner.append(
+                Markup::text(format!(
+                    " feels {}°{}",
+                    format_fixed(feels, 0, self.cfg.temp_precision),
+                    self.units.suffix()
+                ))
+                .fg(Color::Grey),
+            );
+        }
+        let comfort = res
+            .humidity_pct
+            .filter(|_| self.cfg.show_comfort)
+            .and_then(|h| comfort_emoji(res.temp_c, h));
+        if let Some(comfort) = comfort {
+            inner = inner.append(Markup::text(format!(" {comfort}")));
+        }
+        Markup::label("weather ").append(self.maybe_bracketed(inner))
+    }
+
+    fn feels_like_c(&self, res: &OMCurrentWeather) -> Option<f64> {
+        let computed = || computed_feels_like_c(res.temp_c, res.wind_kmh, res.humidity_pct);
+        match self.cfg.feels_like {
+            FeelsLike::Off => None,
+            FeelsLike::Provider => Some(res.apparent_c.unwrap_or_else(computed)),
+            FeelsLike::Computed => Some(computed()),
+        }
+    }
+
+    /// ▲/▼/▬ comparing `temp_c` at `time` with the first hourly sample after it.
+    fn temp_trend(&self, time: DateTime<Utc>, temp_c: f64) -> Option<Markup> {
+        // Smaller changes than this read as steady.
+        const STEADY_BAND_C: f64 = 0.3;
+
+        let hourly = self.res.as_ref()?.hourly.as_ref()?;
+        let ix = hourly.times_utc.iter().position(|t| *t > time)?;
+        let delta = hourly.temperatures_c.get(ix)? - temp_c;
+        Some(if delta > STEADY_BAND_C {
+            Markup::text("▲").fg(Color::Red)
+        } else if delta < -STEADY_BAND_C {
+            Markup::text("▼").fg(Color::Blue)
+        } else {
+            Markup::text("▬").fg(Color::Grey)
+        })
     }
 
     fn current_from_hourly(&self) -> Option<OMCurrentWeather> {
         let hourly = self.res.as_ref()?.hourly.as_ref()?;
-        let now_utc = Utc::now();
+        let now_utc = self.now();
 
         // Prefer an hourly sample at/just before now (UTC).
         // Open-Meteo hourly data is regular; we only need a stable "now-ish" point.
@@ -392,11 +869,24 @@ impl Weather {
         Some(OMCurrentWeather {
             temp_c: hourly.temperatures_c.get(ix).copied()?,
             wmo_code: *hourly.wmo_codes.get(ix)?,
+            apparent_c: None,
+            humidity_pct: None,
+            wind_kmh: None,
+            is_day: hourly.is_day.get(ix).copied(),
             time: *hourly.times_utc.get(ix)?,
         })
     }
 
-    fn format_single_code_and_tc(&self, time: DateTime<Utc>, wmo_code: Wmo, temp_c: f64) -> Markup {
+    /// The condition icon and the colored temperature, `temp_width` digits
+    /// wide, for one reading.
+    fn code_and_temp(
+        &self,
+        time: DateTime<Utc>,
+        is_day: Option<bool>,
+        wmo_code: Wmo,
+        temp_c: f64,
+        temp_width: usize,
+    ) -> (Markup, Markup) {
         let grad = Gradient::new(vec![
             Stop {
                 t: 0.0,
@@ -416,51 +906,173 @@ impl Weather {
             },
         ]);
 
-        let emoji = *wmo_code
-            .get_emoji()
-            .get_at(self.cfg.lat, self.cfg.lon, time);
-        let col: Srgb8 = grad.map_clamped(temp_c, -15.0, 40.0);
-        let temp = Markup::text(format!(
-            "{:2.0}",
-            self.cfg.units.convert_from_celcius(temp_c)
-        ))
-        .fg(col);
+        let (lat, lon) = self.place();
+        let icons = wmo_code.get_icon(self.cfg.icon_set);
+        let emoji = *match is_day {
+            Some(day) => icons.pick(day),
+            None => icons.get_at(lat, lon, time),
+        };
+        let temp_disp = self.units.convert_from_celcius(temp_c);
+        let col: Srgb8 = match self.cfg.temp_color_range {
+            // The range is in the configured units, whatever is shown.
+            Some([lo, hi]) => grad.map_clamped(self.cfg.units.convert_from_celcius(temp_c), lo, hi),
+            None => grad.map_clamped(temp_c, -15.0, 40.0),
+        };
+        let temp =
+            Markup::text(format_fixed(temp_disp, temp_width, self.cfg.temp_precision)).fg(col);
+
+        let mut emoji = Markup::text(emoji);
+        if let Some(tint) = wmo_code.tint().filter(|_| self.cfg.colorize_condition) {
+            emoji = emoji.bg(tint);
+        }
+        (emoji, temp)
+    }
 
-        Markup::text(emoji)
-            .append(temp)
-            .append(Markup::text(format!("°{}", self.cfg.units.suffix())))
+    /// `is_day` is the API's flag for `time`; without one, daylight is
+    /// computed from the location.
+    fn format_single_code_and_tc(
+        &self,
+        time: DateTime<Utc>,
+        is_day: Option<bool>,
+        wmo_code: Wmo,
+        temp_c: f64,
+    ) -> Markup {
+        let (emoji, temp) = self.code_and_temp(time, is_day, wmo_code, temp_c, 2);
+        let pieces = self.part_pieces.iter().map(|piece| match piece {
+            PartPiece::Lit(s) => Markup::text(s.as_str()),
+            PartPiece::Emoji => emoji.clone(),
+            PartPiece::Temp => temp.clone(),
+            PartPiece::Unit => Markup::text(self.units.suffix()),
+        });
+        Markup::join("", pieces)
+    }
+
+    fn maybe_bracketed(&self, part: Markup) -> Markup {
+        if self.cfg.bracketed {
+            Markup::bracketed(part)
+        } else {
+            part
+        }
     }
 
     pub(crate) fn format_res_forecast(&self, res: Option<&OMHourlyForecast>) -> Markup {
         let Some(res) = res else {
-            return Markup::text("weather ") + Markup::text("forecast failed to load").fg(BROWN);
+            return Markup::label("weather ")
+                + Markup::text("forecast failed to load").fg(theme().missing);
         };
-        let times = get_wanted_forecast_datetimes();
+        let now = (self.clock)();
+        let times = get_wanted_forecast_datetimes(
+            now,
+            self.forecast_page,
+            self.cfg.forecast_slots,
+            self.cfg.forecast_step_hours,
+        );
         // exact matching should work fine here, everything is rounded
         let mut out_parts = Vec::new();
         for (i, ft) in res.times_utc.iter().enumerate() {
             if times.contains(ft) {
-                let part =
-                    self.format_single_code_and_tc(*ft, res.wmo_codes[i], res.temperatures_c[i]);
+                let part = self.format_single_code_and_tc(
+                    *ft,
+                    res.is_day.get(i).copied(),
+                    res.wmo_codes[i],
+                    res.temperatures_c[i],
+                );
                 out_parts.push((ft, part));
             }
         }
 
-        let mut out = Markup::text("weather ");
+        let mut out = Markup::label("weather ").append(self.label());
+        if self.forecast_page > 0 {
+            out = out.append(Markup::text(format!("+{}d ", self.forecast_page)).fg(Color::Violet));
+        }
         for (ix, (time, part)) in out_parts.into_iter().enumerate() {
             if ix > 0 {
                 out = out.append(Markup::text("-"));
             }
 
-            let time_local = time.with_timezone(&chrono::Local);
+            let time_local = time.with_timezone(now.offset());
             out = out.append(Markup::text(format!("{:02}", time_local.hour())));
-            out = out.append(Markup::bracketed(part));
+            out = out.append(self.maybe_bracketed(part));
         }
         out
     }
+
+    /// The forecast slots of [`Self::format_res_forecast`] as bare icons and
+    /// temperatures (`🌤18 ⛅16 🌧14`), for i3bar's `short_text`.
+    pub(crate) fn format_res_forecast_compact(&self, res: &OMHourlyForecast) -> Markup {
+        let times = get_wanted_forecast_datetimes(
+            (self.clock)(),
+            self.forecast_page,
+            self.cfg.forecast_slots,
+            self.cfg.forecast_step_hours,
+        );
+        let parts = res
+            .times_utc
+            .iter()
+            .enumerate()
+            .filter(|(_, ft)| times.contains(ft))
+            .map(|(i, ft)| {
+                let (emoji, temp) = self.code_and_temp(
+                    *ft,
+                    res.is_day.get(i).copied(),
+                    res.wmo_codes[i],
+                    res.temperatures_c[i],
+                    0,
+                );
+                emoji + temp
+            });
+        Markup::label("weather ") + Markup::join(" ", parts)
+    }
+
+    /// The short form of the current view: the compact forecast in Forecast
+    /// mode once there is one, otherwise none.
+    pub(crate) fn short_markup(&self) -> Option<Markup> {
+        if self.mode != DisplayMode::Forecast {
+            return None;
+        }
+        let hourly = self.res.as_ref()?.hourly.as_ref()?;
+        Some(self.format_res_forecast_compact(hourly))
+    }
 }
 
 impl Weather {
+    /// Countdown to the next sunrise or sunset at `now_utc`. Needs no network.
+    pub(crate) fn format_sun(&self, now_utc: DateTime<Utc>) -> Markup {
+        let (lat, lon) = self.place();
+        let events = |t| spa::sunrise_and_set::<spa::StdFloatOps>(t, lat, lon).ok();
+        let inner = match events(now_utc) {
+            Some(spa::SunriseAndSet::PolarDay) => Markup::text("polar day"),
+            Some(spa::SunriseAndSet::PolarNight) => Markup::text("polar night"),
+            Some(spa::SunriseAndSet::Daylight(sunrise, sunset)) => {
+                let next = if now_utc < sunrise {
+                    Some(("🌅", sunrise))
+                } else if now_utc < sunset {
+                    Some(("🌇", sunset))
+                } else {
+                    // Past today's sunset: tomorrow's sunrise is next.
+                    match events(now_utc + chrono::Duration::days(1)) {
+                        Some(spa::SunriseAndSet::Daylight(sunrise, _)) => Some(("🌅", sunrise)),
+                        _ => None,
+                    }
+                };
+                match next {
+                    Some((icon, at)) => {
+                        let mins = (at - now_utc).num_minutes();
+                        let left = if mins >= 60 {
+                            format!("{}h{:02}m", mins / 60, mins % 60)
+                        } else {
+                            format!("{mins}m")
+                        };
+                        Markup::text(format!("{icon} in {left}"))
+                    }
+                    None => Markup::text("no sunrise tomorrow"),
+                }
+            }
+            None => Markup::text("sun unavailable").fg(theme().missing),
+        };
+        Markup::label("weather ").append(Markup::bracketed(self.label().append(inner)))
+    }
+
     pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
         let cfg = &mut self.cfg;
         if cfg.refresh_interval_sec < MIN_REFRESH_INTERVAL {
@@ -472,33 +1084,111 @@ impl Weather {
             cfg.refresh_interval_sec = MIN_REFRESH_INTERVAL;
         }
         anyhow::ensure!(
-            cfg.lat >= -90.0 && cfg.lat <= 90.0,
-            "bad config: lat must be between -90 and 90 degrees"
+            cfg.lat.is_some() == cfg.lon.is_some(),
+            "bad config: set both lat and lon, or neither"
+        );
+        anyhow::ensure!(
+            !self.locations.is_empty(),
+            "bad config: set lat and lon, or at least one entry in locations"
+        );
+        for loc in &self.locations {
+            anyhow::ensure!(
+                loc.lat >= -90.0 && loc.lat <= 90.0,
+                "bad config: lat must be between -90 and 90 degrees"
+            );
+            anyhow::ensure!(
+                loc.lon >= -180.0 && loc.lon <= 180.0,
+                "bad config: lon must be between -180 and 180 degrees"
+            );
+        }
+        anyhow::ensure!(
+            cfg.forecast_step_hours > 0 && 24 % cfg.forecast_step_hours == 0,
+            "bad config: forecast_step_hours must divide 24 (1, 2, 3, 4, 6, 8, 12 or 24)"
         );
+        // One page spans at most the two days fetched past the last page.
         anyhow::ensure!(
-            cfg.lon >= -180.0 && cfg.lon <= 180.0,
-            "bad config: lon must be between -180 and 180 degrees"
+            cfg.forecast_slots > 0 && cfg.forecast_slots * cfg.forecast_step_hours <= 48,
+            "bad config: forecast_slots * forecast_step_hours must be 1..=48 hours"
         );
+        if let Some([lo, hi]) = cfg.temp_color_range {
+            anyhow::ensure!(
+                lo < hi,
+                "bad config: temp_color_range must be [coldest, hottest]"
+            );
+        }
         Ok(())
     }
 
     pub async fn read_markup(
         &mut self,
-        effects: &crate::machine::effects::EffectEngine,
+        effects: &impl crate::machine::effects::Effects,
     ) -> Result<Markup, crate::machine::types::PollError<WeatherError>> {
-        self.do_poll_if_needed(effects).await?;
+        if self.mode == DisplayMode::Sun {
+            return Ok(self.format_sun(self.now()));
+        }
+        // A failed refresh keeps showing the cached data, aged by the stale
+        // marker; only with nothing cached is it the unit's error.
+        let fetched = self.do_poll_if_needed(effects).await;
         let Some(ref res) = self.res else {
-            return Ok(Markup::text("weather ") + Markup::text("loading").fg(VIOLET));
+            fetched?;
+            return Ok(Markup::label("weather ") + Markup::text("loading").fg(theme().loading));
         };
+        if let Err(e) = fetched {
+            tracing::warn!("Weather: refresh failed, showing cached data: {e:?}");
+        }
 
-        Ok(match self.mode {
+        let body = match self.mode {
             DisplayMode::Now => self.format_res_now(res.current.as_ref()),
             DisplayMode::Forecast => self.format_res_forecast(res.hourly.as_ref()),
+            DisplayMode::Sun => self.format_sun(self.now()),
+        };
+        Ok(match self.stale_marker(Instant::now()) {
+            Some(age) => body + age,
+            None => body,
         })
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    /// Whether the latest fetch attempt failed, leaving older data on show.
+    pub(crate) fn fetch_failed(&self) -> bool {
+        self.last_attempt_poll
+            .is_some_and(|attempt| self.last_successful_poll.is_none_or(|ok| ok < attempt))
+    }
+
+    /// A dim ` (5m)` age once the last good fetch is more than two refresh
+    /// intervals old, i.e. at least one refresh has failed.
+    pub(crate) fn stale_marker(&self, now: Instant) -> Option<Markup> {
+        let age = now.duration_since(self.last_successful_poll?).as_secs();
+        if (age as f64) <= 2.0 * self.cfg.refresh_interval_sec {
+            return None;
+        }
+        let age = match age {
+            ..3600 => format!("{}m", age / 60),
+            3600..86_400 => format!("{}h", age / 3600),
+            _ => format!("{}d", age / 86_400),
+        };
+        Some(Markup::text(format!(" ({age})")).fg(Color::Grey))
+    }
+
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        use crate::core::ClickEvent;
+        // In Forecast, scrolling pages through the hours instead of switching modes.
+        // With several locations, middle-click switches between them.
+        match (self.mode, click.button) {
+            (_, ClickEvent::BUTTON_RIGHT) => self.units = self.units.toggled(),
+            (_, ClickEvent::BUTTON_MIDDLE) if self.locations.len() > 1 => self.cycle_location(),
+            (
+                DisplayMode::Forecast,
+                ClickEvent::BUTTON_SCROLL_UP | ClickEvent::BUTTON_SCROLL_DOWN,
+            ) => match self.scroll.feed(&click, Instant::now()) {
+                1 => self.forecast_page = self.forecast_page.saturating_sub(1),
+                -1 => self.forecast_page = (self.forecast_page + 1).min(MAX_FORECAST_PAGE),
+                _ => {}
+            },
+            _ => {
+                self.mode = self.mode.rotate(&click);
+                self.forecast_page = 0;
+            }
+        }
     }
 }
 
@@ -512,3 +1202,353 @@ impl std::fmt::Display for WeatherError {
 }
 
 impl std::error::Error for WeatherError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_wanted_forecast_datetimes, OMResponseContainer, Weather, WeatherConfig};
+    use crate::units::testing::{pango, plain};
+    use chrono::{FixedOffset, TimeZone, Utc};
+
+    /// India's UTC+05:30, a zone whose local hours fall between UTC hours.
+    fn ist() -> FixedOffset {
+        FixedOffset::east_opt(5 * 3600 + 1800).unwrap()
+    }
+
+    fn weather_at(lat: f64, lon: f64) -> Weather {
+        let cfg: WeatherConfig = toml::from_str(&format!("lat = {lat}\nlon = {lon}")).unwrap();
+        Weather::from_cfg(cfg)
+    }
+
+    fn weather_with(body: &str) -> Weather {
+        let cfg: WeatherConfig = toml::from_str("lat = 0.0\nlon = 0.0").unwrap();
+        let mut w = Weather::from_cfg(cfg);
+        w.res = Some(serde_json::from_str::<OMResponseContainer>(body).unwrap());
+        w
+    }
+
+    #[test]
+    fn now_shows_trend_from_next_hour() {
+        let w = weather_with(
+            r#"{"current":{"temperature_2m":10.0,"weathercode":3,"time":"2024-01-01T12:15"},
+                "hourly":{"time":["2024-01-01T12:00","2024-01-01T13:00"],
+                          "temperature_2m":[9.0,12.0],"weathercode":[3,3]}}"#,
+        );
+        let res = w.res.as_ref().and_then(|r| r.current.as_ref());
+        assert!(plain(&w.format_res_now(res)).ends_with("10°C▲]"));
+    }
+
+    #[test]
+    fn derived_now_follows_the_clock() {
+        let mut w = weather_with(
+            r#"{"hourly":{"time":["2024-03-20T05:00","2024-03-20T13:00"],
+                          "temperature_2m":[5.0,13.0],"weathercode":[0,0]}}"#,
+        );
+        w.clock = || {
+            Utc.with_ymd_and_hms(2024, 3, 20, 5, 30, 0)
+                .unwrap()
+                .fixed_offset()
+        };
+        assert_eq!(plain(&w.format_res_now(None)), "weather [🌙 5°C▲]");
+        w.clock = || {
+            Utc.with_ymd_and_hms(2024, 3, 20, 13, 30, 0)
+                .unwrap()
+                .fixed_offset()
+        };
+        assert_eq!(plain(&w.format_res_now(None)), "weather [☀️13°C]");
+    }
+
+    #[test]
+    fn compact_forecast_drops_hours_and_units() {
+        let mut w = weather_with(
+            r#"{"hourly":{"time":["2024-03-20T12:00","2024-03-20T13:00","2024-03-20T14:00",
+                                  "2024-03-20T15:00"],
+                          "temperature_2m":[10.0,18.0,16.0,14.0],"weathercode":[0,1,2,63],
+                          "is_day":[1,1,1,1]}}"#,
+        );
+        w.cfg.forecast_slots = 3;
+        w.cfg.forecast_step_hours = 1;
+        w.clock = || {
+            Utc.with_ymd_and_hms(2024, 3, 20, 12, 30, 0)
+                .unwrap()
+                .fixed_offset()
+        };
+        assert_eq!(w.short_markup(), None);
+        w.mode = super::DisplayMode::Forecast;
+        let short = w.short_markup().unwrap();
+        assert_eq!(plain(&short), "weather 🌤️18 ⛅16 🌧️14");
+        // The full view keeps its hour labels.
+        let full = w.format_res_forecast(w.res.as_ref().and_then(|r| r.hourly.as_ref()));
+        assert_eq!(full.plain_text().matches('[').count(), 3);
+
+        // At 17:30 in India the 18:00 slot shows the 18:30 (13:00 UTC) forecast.
+        w.clock = || ist().with_ymd_and_hms(2024, 3, 20, 17, 30, 0).unwrap();
+        assert_eq!(plain(&w.short_markup().unwrap()), "weather 🌤️18 ⛅16 🌧️14");
+        let full = w.format_res_forecast(w.res.as_ref().and_then(|r| r.hourly.as_ref()));
+        assert!(plain(&full).starts_with("weather 18["));
+    }
+
+    #[test]
+    fn api_day_flag_overrides_computed_daylight() {
+        // Noon on the equator, but the API says it's night.
+        let mut w = weather_with(
+            r#"{"current":{"temperature_2m":10.0,"weathercode":0,"is_day":0,"time":"2024-03-20T12:00"}}"#,
+        );
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        assert!(plain(&w.format_res_now(current)).contains('🌙'));
+    }
+
+    #[test]
+    fn forecast_grid_follows_config() {
+        use chrono::Timelike;
+        let cet = FixedOffset::east_opt(3600).unwrap();
+        let now = cet.with_ymd_and_hms(2024, 1, 1, 10, 15, 0).unwrap();
+        for (slots, step) in [(6, 4), (4, 6), (3, 12)] {
+            let times = get_wanted_forecast_datetimes(now, 0, slots, step);
+            assert_eq!(times.len(), slots as usize);
+            for pair in times.windows(2) {
+                assert_eq!((pair[1] - pair[0]).num_hours(), i64::from(step));
+            }
+            assert_eq!(times[0].with_timezone(&cet).hour() % step, 0);
+            assert!(
+                times[0] > now && times[0].signed_duration_since(now).num_hours() < i64::from(step)
+            );
+        }
+        // 16:00 in India is 10:30 UTC, between two hourly forecasts.
+        let now = ist().with_ymd_and_hms(2024, 1, 1, 15, 45, 0).unwrap();
+        let times = get_wanted_forecast_datetimes(now, 0, 2, 4);
+        let utc = |h| Utc.with_ymd_and_hms(2024, 1, 1, h, 0, 0).unwrap();
+        assert_eq!(times, [utc(11), utc(15)]);
+        let mut w = weather_at(0.0, 0.0);
+        w.cfg.forecast_step_hours = 5;
+        assert!(w.fix_up_and_validate().is_err());
+    }
+
+    #[test]
+    fn condition_tint_is_opt_in() {
+        let body =
+            r#"{"current":{"temperature_2m":10.0,"weathercode":95,"time":"2024-01-01T12:15"}}"#;
+        let mut w = weather_with(body);
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        assert!(!pango(&w.format_res_now(current)).contains("background"));
+        w.cfg.colorize_condition = true;
+        assert!(pango(&w.format_res_now(current)).contains("background='#CC6666'"));
+    }
+
+    #[test]
+    fn temp_color_range_is_in_display_units() {
+        let body =
+            r#"{"current":{"temperature_2m":10.0,"weathercode":0,"time":"2024-01-01T12:15"}}"#;
+        let mut w = weather_with(body);
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        let color_of = |w: &Weather| {
+            let out = pango(&w.format_res_now(current));
+            out.split("color='").nth(1).map(|s| s[..7].to_string())
+        };
+        let celsius_default = color_of(&w);
+        // -15..40 °C expressed in °F must color identically.
+        w.cfg.units = super::TempUnits::Fahrenheit;
+        w.units = w.cfg.units;
+        w.cfg.temp_color_range = Some([5.0, 104.0]);
+        assert_eq!(color_of(&w), celsius_default);
+    }
+
+    #[test]
+    fn right_click_toggles_units_in_place() {
+        use crate::core::ClickEvent;
+        use crate::units::testing::click;
+        let body =
+            r#"{"current":{"temperature_2m":10.0,"weathercode":0,"time":"2024-01-01T12:15"}}"#;
+        let mut w = weather_with(body);
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        let celsius = pango(&w.format_res_now(current));
+        w.handle_click(click(ClickEvent::BUTTON_RIGHT));
+        assert_eq!(w.mode, super::DisplayMode::Now);
+        let fahrenheit = pango(&w.format_res_now(current));
+        assert!(plain(&w.format_res_now(current)).ends_with("50°F]"));
+        // The color scale stays Celsius-based.
+        let color = |s: &str| s.split("color='").nth(1).map(|s| s[..7].to_string());
+        assert_eq!(color(&fahrenheit), color(&celsius));
+        w.handle_click(click(ClickEvent::BUTTON_RIGHT));
+        assert!(plain(&w.format_res_now(current)).ends_with("10°C]"));
+    }
+
+    #[test]
+    fn part_format_reorders_and_debrackets() {
+        let body =
+            r#"{"current":{"temperature_2m":10.0,"weathercode":0,"time":"2024-01-01T12:15"}}"#;
+        let cfg: WeatherConfig = toml::from_str(
+            "lat = 0.0\nlon = 0.0\npart_format = \"{temp}{unit} {emoji}\"\nbracketed = false",
+        )
+        .unwrap();
+        let mut w = Weather::from_cfg(cfg);
+        w.res = Some(serde_json::from_str::<OMResponseContainer>(body).unwrap());
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        assert_eq!(plain(&w.format_res_now(current)), "weather 10C ☀️");
+
+        assert!(super::PartPiece::parse_format("{temp").is_err());
+        assert!(super::PartPiece::parse_format("{wind}").is_err());
+        let mut bad: WeatherConfig = toml::from_str("lat = 0.0\nlon = 0.0").unwrap();
+        bad.part_format = "{oops}".into();
+        let w = Weather::from_cfg(bad);
+        assert_eq!(
+            w.part_pieces,
+            super::PartPiece::parse_format(super::DEFAULT_PART_FORMAT).unwrap()
+        );
+    }
+
+    #[test]
+    fn feels_like_formulas_match_reference_tables() {
+        use super::{computed_feels_like_c, heat_index_c, wind_chill_c};
+        // Environment Canada table: -10 °C at 20 km/h feels like -18.
+        assert!((wind_chill_c(-10.0, 20.0).unwrap() + 17.86).abs() < 0.01);
+        // NWS table: 90 °F at 70% humidity has a heat index of 106 °F.
+        let hi_f = heat_index_c(32.2222, 70.0).unwrap() * 9.0 / 5.0 + 32.0;
+        assert!((hi_f - 105.9).abs() < 0.1);
+        assert!(wind_chill_c(15.0, 20.0).is_none() && wind_chill_c(0.0, 3.0).is_none());
+        assert!(heat_index_c(25.0, 90.0).is_none());
+        assert_eq!(computed_feels_like_c(18.0, Some(30.0), Some(60.0)), 18.0);
+    }
+
+    #[test]
+    fn comfort_needs_humidity_and_opt_in() {
+        let body = |h: &str| {
+            format!(
+                r#"{{"current":{{"temperature_2m":28.0,"weathercode":3,{h}"time":"2024-01-01T12:15"}}}}"#
+            )
+        };
+        let now = |w: &Weather| {
+            let current = w.res.as_ref().and_then(|r| r.current.as_ref());
+            plain(&w.format_res_now(current))
+        };
+        let mut w = weather_with(&body(r#""relative_humidity_2m":75.0,"#));
+        assert!(!now(&w).contains('😓'));
+        w.cfg.show_comfort = true;
+        assert!(now(&w).ends_with(" 😓]"), "{}", now(&w));
+        assert!(w
+            .request_url()
+            .unwrap()
+            .as_str()
+            .contains("relative_humidity_2m"));
+
+        let mut dry = weather_with(&body(""));
+        dry.cfg.show_comfort = true;
+        assert!(now(&dry).ends_with("°C]"), "{}", now(&dry));
+    }
+
+    #[test]
+    fn feels_like_prefers_provider_value() {
+        let body = r#"{"current":{"temperature_2m":-10.0,"weathercode":0,"time":"2024-01-01T12:15",
+                       "wind_speed_10m":20.0,"relative_humidity_2m":80.0}}"#;
+        let mut w = weather_with(body);
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        assert!(plain(&w.format_res_now(current)).ends_with("-10°C]"));
+        w.cfg.feels_like = super::FeelsLike::Provider;
+        assert!(plain(&w.format_res_now(current)).ends_with("-10°C feels -18°C]"));
+        let body = r#"{"current":{"temperature_2m":-10.0,"weathercode":0,"time":"2024-01-01T12:15",
+                       "apparent_temperature":-14.0,"wind_speed_10m":20.0}}"#;
+        let provided = serde_json::from_str::<OMResponseContainer>(body).unwrap();
+        let out = plain(&w.format_res_now(provided.current.as_ref()));
+        assert!(out.ends_with("feels -14°C]"));
+        w.cfg.feels_like = super::FeelsLike::Computed;
+        let out = plain(&w.format_res_now(provided.current.as_ref()));
+        assert!(out.ends_with("feels -18°C]"));
+    }
+
+    #[test]
+    fn failed_sunrise_computation_guesses_from_solar_time() {
+        use super::TimeDependent;
+        // Out-of-range latitude makes `spa` fail; noon UTC at lon 0 is day,
+        // and the same instant at lon 180 is midnight.
+        let noon = Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap();
+        assert!(TimeDependent::<()>::is_day_at(95.0, 0.0, noon));
+        assert!(!TimeDependent::<()>::is_day_at(95.0, 180.0, noon));
+    }
+
+    #[test]
+    fn icon_set_keeps_day_night() {
+        use super::{IconSet, Wmo};
+        let noon = Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap();
+        let midnight = Utc.with_ymd_and_hms(2024, 3, 20, 0, 0, 0).unwrap();
+        let icon = |set, t| *Wmo::ClearSky.get_icon(set).get_at(0.0, 0.0, t);
+        assert_eq!(icon(IconSet::Emoji, noon), "☀️");
+        assert_eq!(icon(IconSet::Nerdfont, noon), "\u{e30d}");
+        assert_eq!(icon(IconSet::Nerdfont, midnight), "\u{e32b}");
+        assert_eq!(icon(IconSet::Ascii, midnight), "moon");
+
+        let body =
+            r#"{"current":{"temperature_2m":10.0,"weathercode":3,"time":"2024-01-01T12:15"}}"#;
+        let mut w = weather_with(body);
+        w.cfg.icon_set = IconSet::Ascii;
+        let res = w.res.take();
+        let current = res.as_ref().and_then(|r| r.current.as_ref());
+        assert_eq!(plain(&w.format_res_now(current)), "weather [cloud10°C]");
+    }
+
+    #[test]
+    fn one_request_serves_every_mode() {
+        use crate::core::ClickEvent;
+        use crate::units::testing::click;
+        let mut w = weather_at(52.52, 13.405);
+        let now_url = w.request_url().unwrap();
+        let query: Vec<_> = now_url.query_pairs().map(|(k, _)| k.into_owned()).collect();
+        assert!(query.contains(&"current".into()) && query.contains(&"hourly".into()));
+        w.handle_click(click(ClickEvent::BUTTON_LEFT));
+        assert_eq!(w.mode, super::DisplayMode::Forecast);
+        w.handle_click(click(ClickEvent::BUTTON_SCROLL_DOWN));
+        assert_eq!(w.request_url().unwrap(), now_url);
+    }
+
+    #[test]
+    fn refresh_jitter_is_rerolled_within_band() {
+        let rolls: Vec<f64> = (0..200).map(|_| Weather::roll_jitter()).collect();
+        assert!(rolls
+            .iter()
+            .all(|j| (1.0 - super::REFRESH_JITTER..=1.0 + super::REFRESH_JITTER).contains(j)));
+        assert!(rolls.iter().any(|&j| j != rolls[0]));
+        let mut w = weather_at(0.0, 0.0);
+        w.jitter = 0.9;
+        assert!((w.jittered(200.0) - 180.0).abs() < 1e-9);
+        assert_eq!(w.jittered(15.0), super::MIN_REFRESH_INTERVAL);
+    }
+
+    #[test]
+    fn stale_data_shows_its_age() {
+        use std::time::{Duration, Instant};
+        let mut w = weather_at(0.0, 0.0);
+        let now = Instant::now() + Duration::from_secs(3 * 3600);
+        assert!(w.stale_marker(now).is_none());
+        w.last_successful_poll = Some(now - Duration::from_secs(120));
+        assert!(w.stale_marker(now).is_none());
+        w.last_successful_poll = Some(now - Duration::from_secs(300));
+        assert_eq!(
+            w.stale_marker(now).map(|m| plain(&m)).as_deref(),
+            Some(" (5m)")
+        );
+        w.last_successful_poll = Some(now - Duration::from_secs(2 * 3600 + 5));
+        assert_eq!(
+            w.stale_marker(now).map(|m| plain(&m)).as_deref(),
+            Some(" (2h)")
+        );
+    }
+
+    #[test]
+    fn sun_counts_down_to_next_event() {
+        let equator = weather_at(0.0, 0.0);
+        let before_dawn = Utc.with_ymd_and_hms(2024, 3, 20, 4, 0, 0).unwrap();
+        assert!(plain(&equator.format_sun(before_dawn)).starts_with("weather [🌅 in 2h"));
+        let afternoon = Utc.with_ymd_and_hms(2024, 3, 20, 17, 30, 0).unwrap();
+        assert!(plain(&equator.format_sun(afternoon)).starts_with("weather [🌇 in "));
+        let night = Utc.with_ymd_and_hms(2024, 3, 20, 22, 0, 0).unwrap();
+        assert!(plain(&equator.format_sun(night)).starts_with("weather [🌅 in 8h"));
+
+        let arctic = weather_at(85.0, 0.0);
+        let june = Utc.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap();
+        assert_eq!(plain(&arctic.format_sun(june)), "weather [polar day]");
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    core::{BROWN, GREEN, RED, VIOLET},
+    core::{theme, Color},
     display::color_by_pct_rev,
+    machine::types::View,
     mode_enum,
     render::markup::Markup,
 };
@@ -18,6 +19,8 @@ pub struct WifiConfig {
 pub struct Wifi {
     cfg: WifiConfig,
     mode: DisplayMode,
+    /// SSID and signal (percent) of the last read, re-rendered on mode switches.
+    last: Option<(String, u8)>,
 }
 
 impl Wifi {
@@ -25,14 +28,18 @@ impl Wifi {
         Self {
             cfg,
             mode: DisplayMode::ShowSsid,
+            last: None,
         }
     }
 }
 
 impl Wifi {
-    pub fn read_markup(&self) -> Markup {
+    pub fn read_markup(&mut self) -> View {
+        self.last = None;
         let Ok(mut sock) = Socket::connect() else {
-            return Markup::text("wifi ") + Markup::text("no netlink").fg(VIOLET);
+            return View::error(
+                Markup::label("wifi ") + Markup::text("no netlink").fg(theme().loading),
+            );
         };
 
         let Some(interface) = sock.get_interfaces_info().ok().and_then(|v| {
@@ -44,8 +51,10 @@ impl Wifi {
                     == Some(self.cfg.interface.as_str())
             })
         }) else {
-            return Markup::text(format!("wifi {} ", self.cfg.interface))
-                + Markup::text("gone").fg(BROWN);
+            return View::error(
+                Markup::label(format!("wifi {} ", self.cfg.interface))
+                    + Markup::text("gone").fg(theme().missing),
+            );
         };
 
         let Some(station) = sock
@@ -53,7 +62,7 @@ impl Wifi {
             .ok()
             .and_then(|mut v| v.pop())
         else {
-            return Markup::text("wifi ") + Markup::text("down").fg(RED);
+            return View::degraded(Markup::label("wifi ") + Markup::text("down").fg(Color::Red));
         };
 
         // linear remap −80 dBm→0 %, −30 dBm→100 %
@@ -61,28 +70,40 @@ impl Wifi {
             * 100.0)
             .round()
             .clamp(0.0, 100.0) as u8;
-        let pct_str = Markup::text(format!("{pct:2.0}%")).fg(color_by_pct_rev(f64::from(pct)));
-
         let ssid = interface
             .ssid
             .as_deref()
             .and_then(|b| str::from_utf8(b).ok())
             .unwrap_or("?");
+        let view = self.format_station(ssid, pct);
+        self.last = Some((ssid.to_string(), pct));
+        view
+    }
+
+    /// The last station in the current mode; `None` before one was read or
+    /// while the link is down.
+    pub fn render(&self) -> Option<View> {
+        let (ssid, pct) = self.last.as_ref()?;
+        Some(self.format_station(ssid, *pct))
+    }
+
+    fn format_station(&self, ssid: &str, pct: u8) -> View {
+        let pct_str = Markup::text(format!("{pct:2.0}%")).fg(color_by_pct_rev(f64::from(pct)));
         let ssid_str = match self.mode {
             DisplayMode::ShowSsid => {
-                Markup::text(" ")
-                    + Markup::bracketed(Markup::text(ssid).fg(GREEN))
-                    + Markup::text(" ")
+                Markup::bracketed(Markup::text(ssid).fg(Color::Green)) + Markup::text(" ")
             }
-            DisplayMode::HideSsid => Markup::text(" "),
+            DisplayMode::HideSsid => Markup::empty(),
         };
 
-        Markup::text("wifi") + ssid_str + pct_str
+        View::ok(Markup::label("wifi ") + ssid_str + pct_str)
     }
 
-    pub fn handle_click(&mut self, _click: crate::core::ClickEvent) {
-        self.mode = DisplayMode::next(self.mode);
+    pub fn handle_click(&mut self, click: crate::core::ClickEvent) {
+        self.mode = self.mode.rotate(&click);
     }
 
-    pub fn fix_up_and_validate() {}
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,194 @@
+use crate::core::Color;
+use crate::render::markup::Markup;
+use serde::Deserialize;
+use serde_inline_default::serde_inline_default;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowShow {
+    /// The focused window's title.
+    Title,
+    /// The focused workspace's name.
+    Workspace,
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Deserialize)]
+pub struct WindowConfig {
+    #[serde_inline_default(WindowShow::Title)]
+    pub show: WindowShow,
+    #[serde_inline_default(48)]
+    pub max_chars: usize,
+}
+
+#[derive(Debug)]
+pub struct Window {
+    cfg: WindowConfig,
+    /// `swaymsg` under sway, `i3-msg` otherwise; both speak the same IPC.
+    msg: &'static str,
+    title: Option<String>,
+    workspace: Option<String>,
+    primed: bool,
+}
+
+impl Window {
+    pub fn from_cfg(cfg: WindowConfig) -> Self {
+        let msg = if std::env::var_os("SWAYSOCK").is_some() {
+            "swaymsg"
+        } else {
+            "i3-msg"
+        };
+        Self {
+            cfg,
+            msg,
+            title: None,
+            workspace: None,
+            primed: false,
+        }
+    }
+
+    /// Long-lived `-m` subscription; each output line is one JSON event.
+    pub fn subscribe_cmd(&self) -> Vec<String> {
+        let events = match self.cfg.show {
+            WindowShow::Title => r#"["window","workspace"]"#,
+            WindowShow::Workspace => r#"["workspace"]"#,
+        };
+        [self.msg, "-t", "subscribe", "-m", events]
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// One-shot query for the state before the first event arrives.
+    pub fn initial_cmd(&self) -> Vec<String> {
+        let kind = match self.cfg.show {
+            WindowShow::Title => "get_tree",
+            WindowShow::Workspace => "get_workspaces",
+        };
+        [self.msg, "-t", kind]
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn is_primed(&self) -> bool {
+        self.primed
+    }
+
+    /// Seeds state from the output of `initial_cmd`.
+    pub fn apply_initial(&mut self, stdout: &[u8]) {
+        self.primed = true;
+        let Ok(v) = serde_json::from_slice::<serde_json::Value>(stdout) else {
+            return;
+        };
+        match self.cfg.show {
+            WindowShow::Title => self.title = Self::focused_name(&v),
+            WindowShow::Workspace => {
+                self.workspace = v.as_array().and_then(|wss| {
+                    wss.iter()
+                        .find(|ws| ws["focused"].as_bool() == Some(true))
+                        .and_then(|ws| ws["name"].as_str())
+                        .map(str::to_string)
+                });
+            }
+        }
+    }
+
+    /// Depth-first search of a `get_tree` node for the focused container's name.
+    fn focused_name(node: &serde_json::Value) -> Option<String> {
+        if node["focused"].as_bool() == Some(true) {
+            return node["name"].as_str().map(str::to_string);
+        }
+        ["nodes", "floating_nodes"]
+            .iter()
+            .filter_map(|k| node[*k].as_array())
+            .flatten()
+            .find_map(Self::focused_name)
+    }
+
+    /// Applies one subscription event line. Unknown or malformed lines are ignored.
+    pub fn apply_event(&mut self, line: &str) {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else {
+            return;
+        };
+        let change = v["change"].as_str().unwrap_or_default();
+        if let Some(current) = v.get("current").filter(|c| !c.is_null()) {
+            // Workspace event.
+            if change == "focus" {
+                self.workspace = current["name"].as_str().map(str::to_string);
+                // An empty workspace has no focused window to report.
+                if current["nodes"].as_array().is_some_and(Vec::is_empty)
+                    && current["floating_nodes"]
+                        .as_array()
+                        .is_none_or(Vec::is_empty)
+                {
+                    self.title = None;
+                }
+            }
+        } else if let Some(container) = v.get("container") {
+            // Window event.
+            match change {
+                "focus" | "title" if container["focused"].as_bool() == Some(true) => {
+                    self.title = container["name"].as_str().map(str::to_string);
+                }
+                "close" if container["focused"].as_bool() == Some(true) => self.title = None,
+                _ => {}
+            }
+        }
+    }
+
+    fn truncate(text: &str, max_chars: usize) -> String {
+        if text.chars().count() <= max_chars {
+            return text.to_string();
+        }
+        let mut out: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+
+    pub fn read_markup(&self) -> Markup {
+        let (prefix, value) = match self.cfg.show {
+            WindowShow::Title => ("win ", self.title.as_deref()),
+            WindowShow::Workspace => ("ws ", self.workspace.as_deref()),
+        };
+        match value.filter(|v| !v.is_empty()) {
+            // `Markup::text` escapes, so titles with `<`/`&` are safe.
+            Some(v) => Markup::label(prefix) + Markup::text(Self::truncate(v, self.cfg.max_chars)),
+            None => Markup::label(prefix) + Markup::text("none").fg(Color::Grey),
+        }
+    }
+
+    pub fn handle_click(_click: crate::core::ClickEvent) {}
+
+    pub fn fix_up_and_validate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Window, WindowConfig, WindowShow};
+    use crate::units::testing::{pango, plain};
+
+    #[test]
+    fn events_track_focused_title() {
+        let mut w = Window::from_cfg(WindowConfig {
+            show: WindowShow::Title,
+            max_chars: 8,
+        });
+        w.apply_initial(
+            br#"{"focused":false,"nodes":[{"focused":false,"nodes":[
+                {"focused":true,"name":"a <b> & c","nodes":[]}]}]}"#,
+        );
+        assert_eq!(plain(&w.read_markup()), "win a <b> &…");
+        assert_eq!(pango(&w.read_markup()), "win a &lt;b&gt; &amp;…");
+
+        w.apply_event(r#"{"change":"title","container":{"focused":true,"name":"vim"}}"#);
+        assert_eq!(plain(&w.read_markup()), "win vim");
+
+        w.apply_event(
+            r#"{"change":"focus","current":{"name":"2","nodes":[],"floating_nodes":[]}}"#,
+        );
+        assert_eq!(plain(&w.read_markup()), "win none");
+    }
+}
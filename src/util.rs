@@ -1,9 +1,19 @@
 use std::{
     marker::PhantomData,
     ops::{Add, Mul},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+/// Wall-clock source for time-of-day logic, carrying the local UTC offset.
+/// Units hold one so tests can pin "now" and the timezone; production uses
+/// [`local_now`].
+pub type Clock = fn() -> chrono::DateTime<chrono::FixedOffset>;
+
+/// The system time in the system timezone.
+pub fn local_now() -> chrono::DateTime<chrono::FixedOffset> {
+    chrono::Local::now().fixed_offset()
+}
+
 #[macro_export]
 macro_rules! mode_enum {
     ( $($member:ident),* $(,)? ) => {
@@ -11,6 +21,17 @@ macro_rules! mode_enum {
         pub enum DisplayMode {
             $($member),*
         }
+
+        impl DisplayMode {
+            /// The mode after `click`: scroll down (button 5) goes back, scroll up
+            /// (button 4) and every other button advance.
+            pub const fn rotate(self, click: &$crate::core::ClickEvent) -> Self {
+                match click.button {
+                    $crate::core::ClickEvent::BUTTON_SCROLL_DOWN => self.prev(),
+                    _ => self.next(),
+                }
+            }
+        }
     };
 }
 
@@ -81,6 +102,55 @@ where
     }
 }
 
+/// A partial scroll count is dropped after this long without scrolling.
+pub const SCROLL_IDLE_RESET: Duration = Duration::from_millis(500);
+
+/// Turns bursts of scroll events into discrete steps.
+///
+/// High-resolution touchpads send many button 4/5 events per swipe; only every
+/// `threshold`-th event in one direction yields a step. Reversing direction or
+/// pausing for [`SCROLL_IDLE_RESET`] discards the partial count.
+#[derive(Debug)]
+pub struct ScrollAccumulator {
+    threshold: u32,
+    pending: i32,
+    last: Option<Instant>,
+}
+
+impl ScrollAccumulator {
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            pending: 0,
+            last: None,
+        }
+    }
+
+    /// Feeds one click; returns `1` for a step up, `-1` for a step down and `0`
+    /// otherwise (including non-scroll buttons).
+    pub fn feed(&mut self, click: &crate::core::ClickEvent, now: Instant) -> i32 {
+        let dir = match click.button {
+            crate::core::ClickEvent::BUTTON_SCROLL_UP => 1,
+            crate::core::ClickEvent::BUTTON_SCROLL_DOWN => -1,
+            _ => return 0,
+        };
+        let idle = self
+            .last
+            .is_none_or(|last| now.duration_since(last) >= SCROLL_IDLE_RESET);
+        if idle || self.pending.signum() == -dir {
+            self.pending = 0;
+        }
+        self.last = Some(now);
+        self.pending += dir;
+        if self.pending.unsigned_abs() >= self.threshold {
+            self.pending = 0;
+            dir
+        } else {
+            0
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +180,39 @@ mod tests {
         assert!((v2 - expected_v2).abs() < 1e-9);
     }
 
+    #[test]
+    fn mode_scroll_rotates_both_ways() {
+        crate::mode_enum!(A, B, C);
+        use crate::units::testing::click;
+        let up = click(crate::core::ClickEvent::BUTTON_SCROLL_UP);
+        let down = click(crate::core::ClickEvent::BUTTON_SCROLL_DOWN);
+        let left = click(crate::core::ClickEvent::BUTTON_LEFT);
+        assert_eq!(DisplayMode::A.rotate(&up), DisplayMode::B);
+        assert_eq!(DisplayMode::A.rotate(&down), DisplayMode::C);
+        assert_eq!(DisplayMode::C.rotate(&left), DisplayMode::A);
+    }
+
+    #[test]
+    fn scroll_steps_every_threshold_events() {
+        use crate::units::testing::click;
+        let up = click(crate::core::ClickEvent::BUTTON_SCROLL_UP);
+        let down = click(crate::core::ClickEvent::BUTTON_SCROLL_DOWN);
+        let mut acc = ScrollAccumulator::new(3);
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let steps: Vec<i32> = (0..7).map(|i| acc.feed(&up, ms(i * 10))).collect();
+        assert_eq!(steps, [0, 0, 1, 0, 0, 1, 0]);
+        // Reversing drops the leftover up event.
+        assert_eq!(acc.feed(&down, ms(80)), 0);
+        assert_eq!(acc.feed(&down, ms(90)), 0);
+        assert_eq!(acc.feed(&down, ms(100)), -1);
+        // So does pausing.
+        assert_eq!(acc.feed(&up, ms(110)), 0);
+        assert_eq!(acc.feed(&up, ms(120)), 0);
+        assert_eq!(acc.feed(&up, ms(1000)), 0);
+        assert_eq!(ScrollAccumulator::new(0).feed(&up, t0), 1);
+    }
+
     #[test]
     fn ema_empty() {
         let s: Ema<f64> = Ema::new(1.0);